@@ -0,0 +1,188 @@
+use crate::error::ConversionError;
+use crate::format::format_binary;
+use crate::generate_xml::value_to_xml;
+use crate::options::ConversionOptions;
+
+use serde_json::{Map, Number, Value};
+
+/// # Convert MessagePack to XML with a custom root element.
+///
+/// The MessagePack value is decoded into the same value tree as JSON and converted with the conventions of `json_to_xml_with_root`.
+/// See `msgpack_to_xml_with_options` for how MessagePack types are mapped.
+///
+/// Requires the `msgpack` cargo feature.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::binary::msgpack_to_xml;
+///
+/// // {"id": 7, "name": "Pen", "data": <bin 01 02 03>}
+/// let bytes = [
+///     0x83, 0xa2, b'i', b'd', 0x07, 0xa4, b'n', b'a', b'm', b'e', 0xa3, b'P', b'e', b'n',
+///     0xa4, b'd', b'a', b't', b'a', 0xc4, 0x03, 0x01, 0x02, 0x03,
+/// ];
+///
+/// let xml_string = msgpack_to_xml(&bytes, "Root").unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Root>
+///   <Data>AQID</Data>
+///   <Id>7</Id>
+///   <Name>Pen</Name>
+/// </Root>
+/// ```
+#[cfg(feature = "msgpack")]
+pub fn msgpack_to_xml(bytes: &[u8], root: &str) -> Result<String, ConversionError> {
+    msgpack_to_xml_with_options(bytes, root, &ConversionOptions::default())
+}
+
+/// # Convert MessagePack to XML with a custom root element and conversion options.
+///
+/// Only the first MessagePack value in `bytes` is read.
+/// - `bin` data and the payload of `ext` values are written as text, encoded as configured by `ConversionOptions::binary_encoding`.
+/// - Strings that are not valid UTF-8 are treated as binary data.
+/// - Map keys that are not strings are written as their text form, e.g. `1` or `true`.
+/// - `NaN` and infinite floats have no JSON number representation and are written as text.
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError::MessagePack` if the input cannot be decoded.
+#[cfg(feature = "msgpack")]
+pub fn msgpack_to_xml_with_options(bytes: &[u8], root: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let value = rmpv::decode::read_value(&mut &bytes[..])?;
+
+    value_to_xml(msgpack_to_json(value, options), root, options)
+}
+
+#[cfg(feature = "msgpack")]
+fn msgpack_to_json(value: rmpv::Value, options: &ConversionOptions) -> Value {
+    use rmpv::Value as MsgpackValue;
+
+    match value {
+        MsgpackValue::Nil => Value::Null,
+        MsgpackValue::Boolean(b) => Value::Bool(b),
+        MsgpackValue::Integer(int) => match (int.as_i64(), int.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (_, Some(u)) => Value::from(u),
+            _ => Value::String(int.to_string()),
+        },
+        MsgpackValue::F32(f) => float_to_json(f as f64),
+        MsgpackValue::F64(f) => float_to_json(f),
+        MsgpackValue::String(s) => match s.as_str() {
+            Some(s) => Value::String(s.to_string()),
+            None => Value::String(format_binary(s.as_bytes(), &options.binary_encoding)),
+        },
+        MsgpackValue::Binary(bytes) | MsgpackValue::Ext(_, bytes) => Value::String(format_binary(&bytes, &options.binary_encoding)),
+        MsgpackValue::Array(items) => Value::Array(items.into_iter().map(|item| msgpack_to_json(item, options)).collect()),
+        MsgpackValue::Map(entries) => {
+            let map: Map<String, Value> = entries
+                .into_iter()
+                .map(|(key, value)| (key_to_string(msgpack_to_json(key, options)), msgpack_to_json(value, options)))
+                .collect();
+            Value::Object(map)
+        },
+    }
+}
+
+/// # Convert CBOR to XML with a custom root element.
+///
+/// The CBOR value is decoded into the same value tree as JSON and converted with the conventions of `json_to_xml_with_root`.
+/// See `cbor_to_xml_with_options` for how CBOR types are mapped.
+///
+/// Requires the `cbor` cargo feature.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::binary::cbor_to_xml;
+///
+/// // {"id": 7, "data": h'010203'}
+/// let bytes = [0xa2, 0x62, b'i', b'd', 0x07, 0x64, b'd', b'a', b't', b'a', 0x43, 0x01, 0x02, 0x03];
+///
+/// let xml_string = cbor_to_xml(&bytes, "Root").unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Root>
+///   <Data>AQID</Data>
+///   <Id>7</Id>
+/// </Root>
+/// ```
+#[cfg(feature = "cbor")]
+pub fn cbor_to_xml(bytes: &[u8], root: &str) -> Result<String, ConversionError> {
+    cbor_to_xml_with_options(bytes, root, &ConversionOptions::default())
+}
+
+/// # Convert CBOR to XML with a custom root element and conversion options.
+///
+/// Only the first CBOR data item in `bytes` is read.
+/// - Byte strings are written as text, encoded as configured by `ConversionOptions::binary_encoding`.
+/// - Tags are dropped and their content is converted, so a tagged date-time string stays a string.
+/// - Map keys that are not strings are written as their text form, e.g. `1` or `true`.
+/// - `NaN` and infinite floats have no JSON number representation and are written as text.
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError::Cbor` if the input cannot be decoded.
+#[cfg(feature = "cbor")]
+pub fn cbor_to_xml_with_options(bytes: &[u8], root: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let value: ciborium::Value = ciborium::from_reader(bytes)?;
+
+    value_to_xml(cbor_to_json(value, options), root, options)
+}
+
+#[cfg(feature = "cbor")]
+fn cbor_to_json(value: ciborium::Value, options: &ConversionOptions) -> Value {
+    use ciborium::Value as CborValue;
+
+    match value {
+        CborValue::Null => Value::Null,
+        CborValue::Bool(b) => Value::Bool(b),
+        CborValue::Integer(int) => {
+            let int = i128::from(int);
+            match (i64::try_from(int), u64::try_from(int)) {
+                (Ok(i), _) => Value::from(i),
+                (_, Ok(u)) => Value::from(u),
+                _ => Value::String(int.to_string()),
+            }
+        },
+        CborValue::Float(f) => float_to_json(f),
+        CborValue::Text(s) => Value::String(s),
+        CborValue::Bytes(bytes) => Value::String(format_binary(&bytes, &options.binary_encoding)),
+        CborValue::Tag(_, value) => cbor_to_json(*value, options),
+        CborValue::Array(items) => Value::Array(items.into_iter().map(|item| cbor_to_json(item, options)).collect()),
+        CborValue::Map(entries) => {
+            let map: Map<String, Value> = entries
+                .into_iter()
+                .map(|(key, value)| (key_to_string(cbor_to_json(key, options)), cbor_to_json(value, options)))
+                .collect();
+            Value::Object(map)
+        },
+        // `ciborium::Value` is non-exhaustive.
+        _ => Value::Null,
+    }
+}
+
+/// Infinity and NaN have no JSON number representation, so they are kept as text.
+fn float_to_json(f: f64) -> Value {
+    Number::from_f64(f).map_or_else(|| Value::String(f.to_string()), Value::Number)
+}
+
+fn key_to_string(key: Value) -> String {
+    match key {
+        Value::String(s) => s,
+        key => key.to_string(),
+    }
+}
@@ -0,0 +1,560 @@
+use crate::document::{generated_comment, Document, Element, Node};
+use crate::error::ConversionError;
+use crate::format::format_scalar;
+use crate::options::ConversionOptions;
+use crate::xml_utils::check_names;
+
+use serde_json::{Map, Number, Value};
+
+/// A mapping between JSON values and XML documents.
+///
+/// Each convention maps in both directions, so the same convention converts JSON to XML and reads it back.
+/// The crate's own `@`/`$text` mapping is `Standard`. `BadgerFish`, `Parker`, `GData`, `Abdera` and `JsonML`
+/// follow the conventions of the same names used by other XML/JSON tools.
+pub trait Convention {
+    /// Build the XML document for a value. `root` names the root element where the value does not name one itself.
+    fn to_document(&self, value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError>;
+
+    /// Map an XML document to a value.
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError>;
+}
+
+/// The crate's own convention, used by `json_to_xml_with_root`.
+///
+/// Keys are capitalized, `@` keys become attributes, `$text` becomes text and arrays of primitives become `<TagItem>` lists.
+/// See `Document::from_value` and `Document::to_value`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+/// The BadgerFish convention.
+///
+/// Element names are kept as they are, attributes are `@` keys and text is always held in a `$` key, e.g.
+/// `<alice charlie="david">bob</alice>` is `{"alice": {"@charlie": "david", "$": "bob"}}`.
+/// Namespace declarations are collected in an `@xmlns` object, with `$` for the default namespace.
+/// Repeated elements become arrays. All text is read back as strings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BadgerFish;
+
+/// The Parker convention.
+///
+/// The root element is absorbed, attributes are dropped and elements holding only text become plain values, e.g.
+/// `<root><a>1</a><b>true</b></root>` is `{"a": 1, "b": true}`.
+/// An element whose children all share one name becomes an array, and an empty element becomes `null`.
+/// Text is read back as a number when it is exactly how that number is written, e.g. `7` or `1.5` but not `007` or ` 7 `,
+/// and as a boolean when it is exactly `true` or `false`. All other text, including its whitespace, stays a string.
+/// Parker cannot preserve attributes, so converting is lossy.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Parker;
+
+/// The Google Data (GData) convention.
+///
+/// Attributes are plain keys, text is held in a `$t` key and elements are objects, e.g.
+/// `<title type="text">Hello</title>` is `{"title": {"type": "text", "$t": "Hello"}}`.
+/// The `:` of prefixed names is written as `$`, e.g. `openSearch$totalResults`, and namespaces are `xmlns` and `xmlns$prefix` keys.
+/// When reading JSON, scalar keys become attributes and object or array keys become elements.
+/// The top level has `version` and `encoding` keys next to the root element.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GData;
+
+/// The Apache Abdera convention.
+///
+/// Every element is an object with a `name`, an `attributes` object and a `children` array,
+/// in which text is written as strings, e.g. `<p class="x">Hi <b>there</b></p>` is
+/// `{"name": "p", "attributes": {"class": "x"}, "children": ["Hi ", {"name": "b", "children": ["there"]}]}`.
+/// Empty `attributes` and `children` are left out.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Abdera;
+
+/// The JsonML convention.
+///
+/// Every element is an array of its name, an optional attributes object and its children,
+/// in which text is written as strings, e.g. `<p class="x">Hi <b>there</b></p>` is `["p", {"class": "x"}, "Hi ", ["b", "there"]]`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonML;
+
+/// # Convert JSON to XML with a mapping convention.
+///
+/// Works like `json_to_xml_with_options`, but maps the JSON with the given `Convention`.
+/// Number and boolean formats apply to all conventions. Type annotations are only written by `Standard`.
+/// Names that are not XML names, repeated attributes and undeclared namespace prefixes return `ConversionError::Convention`.
+/// The document always has one root element: with `BadgerFish` and `GData`, JSON that is not a single key holding an object
+/// is wrapped in `root`, and the items of a top-level array become `<RootItem>` elements.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::convention::{json_to_xml_with_convention, BadgerFish};
+/// use json_to_xml::options::ConversionOptions;
+///
+/// let json_string = r#"{"alice": {"@charlie": "david", "bob": {"$": "text"}, "edgar": [{"$": 1}, {"$": 2}]}}"#;
+///
+/// let xml_string = json_to_xml_with_convention(json_string, "Root", &BadgerFish, &ConversionOptions::default()).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <alice charlie="david">
+///   <bob>text</bob>
+///   <edgar>1</edgar>
+///   <edgar>2</edgar>
+/// </alice>
+/// ```
+pub fn json_to_xml_with_convention(json_string: &str, root: &str, convention: &dyn Convention, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let value: Value = serde_json::from_str(json_string)?;
+
+    convention.to_document(&value, root, options)?.to_xml()
+}
+
+/// # Convert XML to JSON with a mapping convention.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::convention::{xml_to_json_with_convention, Parker};
+///
+/// let xml = "<Order><Id>7</Id><Lines><Sku>A</Sku><Sku>B</Sku></Lines><Paid>true</Paid></Order>";
+///
+/// let json_string = xml_to_json_with_convention(xml, &Parker).unwrap();
+///
+/// assert_eq!(json_string, r#"{"Id":7,"Lines":["A","B"],"Paid":true}"#);
+/// ```
+pub fn xml_to_json_with_convention(xml: &str, convention: &dyn Convention) -> Result<String, ConversionError> {
+    let value = convention.to_value(&Document::from_xml(xml)?)?;
+
+    Ok(serde_json::to_string(&value)?)
+}
+
+impl Convention for Standard {
+    fn to_document(&self, value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        Document::from_value(value, root, options)
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        Ok(document.to_value())
+    }
+}
+
+impl Convention for BadgerFish {
+    fn to_document(&self, value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        generated_document(keyed_root(value, root), |name, value, nodes| badgerfish_elements(name, value, options, nodes))
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        Ok(Value::Object(group_elements(document_elements(document), |name| name.to_string(), badgerfish_value)))
+    }
+}
+
+impl Convention for Parker {
+    fn to_document(&self, value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        generated_document(vec![(root.to_string(), value.clone())], |name, value, nodes| nodes.push(Node::Element(parker_element(name, value, options))))
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        Ok(document.root().map_or(Value::Null, parker_value))
+    }
+}
+
+impl Convention for GData {
+    fn to_document(&self, value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        let value = match value {
+            Value::Object(map) => Value::Object(map.iter().filter(|(key, _)| !matches!(key.as_str(), "version" | "encoding")).map(|(k, v)| (k.clone(), v.clone())).collect()),
+            value => value.clone(),
+        };
+
+        generated_document(keyed_root(&value, root), |name, value, nodes| gdata_elements(&gdata_name(name), value, options, nodes))
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        let mut map = Map::new();
+        map.insert("version".to_string(), Value::from("1.0"));
+        map.insert("encoding".to_string(), Value::from("UTF-8"));
+        map.extend(group_elements(document_elements(document), |name| name.replace(':', "$"), gdata_value));
+        Ok(Value::Object(map))
+    }
+}
+
+impl Convention for Abdera {
+    fn to_document(&self, value: &Value, _root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        checked_document(vec![generated_comment(), Node::Element(abdera_element(value, options)?)])
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        let root = document.root().ok_or_else(|| ConversionError::Convention("document has no root element".to_string()))?;
+        Ok(abdera_value(root))
+    }
+}
+
+impl Convention for JsonML {
+    fn to_document(&self, value: &Value, _root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        checked_document(vec![generated_comment(), Node::Element(jsonml_element(value, options)?)])
+    }
+
+    fn to_value(&self, document: &Document) -> Result<Value, ConversionError> {
+        let root = document.root().ok_or_else(|| ConversionError::Convention("document has no root element".to_string()))?;
+        Ok(jsonml_value(root))
+    }
+}
+
+/// The top-level elements of a keyed convention. A single key naming an object is the root element,
+/// anything else is wrapped in `root` so the document stays well-formed. The items of a top-level array have no key
+/// to name them, so they become repeated `<RootItem>` elements, like the items of `Parker`.
+fn keyed_root(value: &Value, root: &str) -> Vec<(String, Value)> {
+    match value {
+        Value::Object(map) if map.len() == 1 && map.values().all(Value::is_object) => map.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        Value::Array(items) => vec![(root.to_string(), Value::Object(Map::from_iter([(format!("{}Item", root), Value::Array(items.clone()))])))],
+        value => vec![(root.to_string(), value.clone())],
+    }
+}
+
+fn generated_document(roots: Vec<(String, Value)>, mut push: impl FnMut(&str, &Value, &mut Vec<Node>)) -> Result<Document, ConversionError> {
+    let mut nodes = vec![generated_comment()];
+
+    for (name, value) in roots {
+        push(&name, &value, &mut nodes);
+    }
+
+    checked_document(nodes)
+}
+
+/// A document of the converted nodes, once their names are checked. Conventions take the names from the JSON as they are,
+/// so a name that is not an XML name, a repeated attribute or an undeclared namespace prefix returns `ConversionError::Convention`.
+/// A document without exactly one root element returns `ConversionError::RootCount`.
+fn checked_document(nodes: Vec<Node>) -> Result<Document, ConversionError> {
+    let mut roots = 0;
+
+    for node in &nodes {
+        if let Node::Element(element) = node {
+            check_element(element, &mut Vec::new())?;
+            roots += 1;
+        }
+    }
+
+    if roots != 1 {
+        return Err(ConversionError::RootCount(roots));
+    }
+    Ok(Document { nodes })
+}
+
+fn check_element<'a>(element: &'a Element, prefixes: &mut Vec<&'a str>) -> Result<(), ConversionError> {
+    check_names(element, "").map_err(|e| ConversionError::Convention(e.to_string()))?;
+
+    let in_scope = prefixes.len();
+    prefixes.extend(element.namespaces.iter().filter_map(|namespace| namespace.prefix.as_deref()));
+
+    let names = std::iter::once(&element.name).chain(element.attributes.iter().map(|attribute| &attribute.name));
+    for name in names {
+        if let Some((prefix, _)) = name.split_once(':')
+            && prefix != "xml"
+            && !prefixes.contains(&prefix)
+        {
+            return Err(ConversionError::Convention(format!("undeclared namespace prefix \"{}\" in \"{}\"", prefix, name)));
+        }
+    }
+
+    for child in element.elements() {
+        check_element(child, prefixes)?;
+    }
+
+    prefixes.truncate(in_scope);
+    Ok(())
+}
+
+fn document_elements(document: &Document) -> impl Iterator<Item = &Element> {
+    document.nodes.iter().filter_map(|node| match node {
+        Node::Element(element) => Some(element),
+        _ => None,
+    })
+}
+
+/// Collect elements into an object, turning repeated names into arrays.
+fn group_elements<'a>(elements: impl Iterator<Item = &'a Element>, key: impl Fn(&str) -> String, value: impl Fn(&Element) -> Value) -> Map<String, Value> {
+    let mut groups: Vec<(String, Vec<Value>)> = Vec::new();
+
+    for element in elements {
+        let name = key(&element.name);
+        let element_value = value(element);
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, values)) => values.push(element_value),
+            None => groups.push((name, vec![element_value])),
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if values.len() == 1 { values.remove(0) } else { Value::Array(values) };
+            (name, value)
+        })
+        .collect()
+}
+
+fn push_text(element: &mut Element, value: &Value, options: &ConversionOptions) {
+    if let Some(text) = format_scalar(value, options) {
+        element.children.push(Node::Text(text));
+    }
+}
+
+fn badgerfish_elements(name: &str, value: &Value, options: &ConversionOptions, nodes: &mut Vec<Node>) {
+    let mut element = Element::new(name);
+
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                badgerfish_elements(name, item, options, nodes);
+            }
+            return;
+        },
+        Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value) {
+                    ("@xmlns", Value::Object(namespaces)) => {
+                        for (prefix, uri) in namespaces {
+                            let attribute = if prefix == "$" { "xmlns".to_string() } else { format!("xmlns:{}", prefix) };
+                            if let Some(uri) = format_scalar(uri, options) {
+                                element.push_attribute(&attribute, uri);
+                            }
+                        }
+                    },
+                    ("$", value) => push_text(&mut element, value, options),
+                    (key, value) if key.starts_with('@') => {
+                        if let Some(value) = format_scalar(value, options) {
+                            element.push_attribute(&key[1..], value);
+                        }
+                    },
+                    (key, value) => badgerfish_elements(key, value, options, &mut element.children),
+                }
+            }
+        },
+        value => push_text(&mut element, value, options),
+    }
+
+    nodes.push(Node::Element(element));
+}
+
+fn badgerfish_value(element: &Element) -> Value {
+    let mut map = Map::new();
+
+    if !element.namespaces.is_empty() {
+        let namespaces = element
+            .namespaces
+            .iter()
+            .map(|namespace| (namespace.prefix.clone().unwrap_or_else(|| "$".to_string()), Value::from(namespace.uri.as_str())))
+            .collect();
+        map.insert("@xmlns".to_string(), Value::Object(namespaces));
+    }
+    for attribute in &element.attributes {
+        map.insert(format!("@{}", attribute.name), Value::from(attribute.value.as_str()));
+    }
+
+    let text = element.text();
+    if !text.is_empty() {
+        map.insert("$".to_string(), Value::String(text));
+    }
+
+    map.extend(group_elements(element.elements(), |name| name.to_string(), badgerfish_value));
+    Value::Object(map)
+}
+
+fn parker_element(name: &str, value: &Value, options: &ConversionOptions) -> Element {
+    let mut element = Element::new(name);
+
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    Value::Array(items) => element.children.extend(items.iter().map(|item| Node::Element(parker_element(key, item, options)))),
+                    value => element.children.push(Node::Element(parker_element(key, value, options))),
+                }
+            }
+        },
+        Value::Array(items) => {
+            let item_name = format!("{}Item", name);
+            element.children.extend(items.iter().map(|item| Node::Element(parker_element(&item_name, item, options))));
+        },
+        value => push_text(&mut element, value, options),
+    }
+
+    element
+}
+
+fn parker_value(element: &Element) -> Value {
+    let elements: Vec<&Element> = element.elements().collect();
+
+    match elements.as_slice() {
+        [] => match element.text() {
+            text if text.is_empty() => Value::Null,
+            text if text == "true" => Value::Bool(true),
+            text if text == "false" => Value::Bool(false),
+            text => match text.parse::<Number>() {
+                // Only text the number writes back the same, so no digits or whitespace are lost.
+                Ok(number) if number.to_string() == text => Value::Number(number),
+                _ => Value::String(text),
+            },
+        },
+        [first, rest @ ..] if !rest.is_empty() && rest.iter().all(|child| child.name == first.name) => {
+            Value::Array(elements.iter().map(|child| parker_value(child)).collect())
+        },
+        elements => Value::Object(group_elements(elements.iter().copied(), |name| name.to_string(), parker_value)),
+    }
+}
+
+fn gdata_name(name: &str) -> String {
+    name.replace('$', ":")
+}
+
+fn gdata_elements(name: &str, value: &Value, options: &ConversionOptions, nodes: &mut Vec<Node>) {
+    let mut element = Element::new(name);
+
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                gdata_elements(name, item, options, nodes);
+            }
+            return;
+        },
+        Value::Object(map) => {
+            for (key, value) in map {
+                match value {
+                    _ if key == "$t" => push_text(&mut element, value, options),
+                    Value::Object(_) | Value::Array(_) => gdata_elements(&gdata_name(key), value, options, &mut element.children),
+                    value => {
+                        if let Some(value) = format_scalar(value, options) {
+                            element.push_attribute(&gdata_name(key), value);
+                        }
+                    },
+                }
+            }
+        },
+        value => push_text(&mut element, value, options),
+    }
+
+    nodes.push(Node::Element(element));
+}
+
+fn gdata_value(element: &Element) -> Value {
+    let mut map = Map::new();
+
+    for namespace in &element.namespaces {
+        map.insert(namespace.attribute_name().replace(':', "$"), Value::from(namespace.uri.as_str()));
+    }
+    for attribute in &element.attributes {
+        map.insert(attribute.name.replace(':', "$"), Value::from(attribute.value.as_str()));
+    }
+
+    let text = element.text();
+    if !text.is_empty() {
+        map.insert("$t".to_string(), Value::String(text));
+    }
+
+    map.extend(group_elements(element.elements(), |name| name.replace(':', "$"), gdata_value));
+    Value::Object(map)
+}
+
+fn abdera_element(value: &Value, options: &ConversionOptions) -> Result<Element, ConversionError> {
+    let name = value
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ConversionError::Convention(format!("expected an element object with a name, found {}", value)))?;
+    let mut element = Element::new(name);
+
+    if let Some(attributes) = value.get("attributes").and_then(Value::as_object) {
+        for (key, value) in attributes {
+            if let Some(value) = format_scalar(value, options) {
+                element.push_attribute(key, value);
+            }
+        }
+    }
+
+    for child in value.get("children").and_then(Value::as_array).into_iter().flatten() {
+        match child {
+            Value::Object(_) => element.children.push(Node::Element(abdera_element(child, options)?)),
+            child => push_text(&mut element, child, options),
+        }
+    }
+
+    Ok(element)
+}
+
+fn abdera_value(element: &Element) -> Value {
+    let mut map = Map::new();
+    map.insert("name".to_string(), Value::from(element.name.as_str()));
+
+    let attributes = attribute_map(element);
+    if !attributes.is_empty() {
+        map.insert("attributes".to_string(), Value::Object(attributes));
+    }
+
+    let children = content_values(element, abdera_value);
+    if !children.is_empty() {
+        map.insert("children".to_string(), Value::Array(children));
+    }
+
+    Value::Object(map)
+}
+
+fn jsonml_element(value: &Value, options: &ConversionOptions) -> Result<Element, ConversionError> {
+    let invalid = || ConversionError::Convention(format!("expected an element array starting with a name, found {}", value));
+
+    let items = value.as_array().ok_or_else(invalid)?;
+    let (name, rest) = items.split_first().ok_or_else(invalid)?;
+    let mut element = Element::new(name.as_str().ok_or_else(invalid)?);
+
+    let children = match rest.split_first() {
+        Some((Value::Object(attributes), children)) => {
+            for (key, value) in attributes {
+                if let Some(value) = format_scalar(value, options) {
+                    element.push_attribute(key, value);
+                }
+            }
+            children
+        },
+        _ => rest,
+    };
+
+    for child in children {
+        match child {
+            Value::Array(_) => element.children.push(Node::Element(jsonml_element(child, options)?)),
+            child => push_text(&mut element, child, options),
+        }
+    }
+
+    Ok(element)
+}
+
+fn jsonml_value(element: &Element) -> Value {
+    let mut items = vec![Value::from(element.name.as_str())];
+
+    let attributes = attribute_map(element);
+    if !attributes.is_empty() {
+        items.push(Value::Object(attributes));
+    }
+
+    items.extend(content_values(element, jsonml_value));
+    Value::Array(items)
+}
+
+/// Namespace declarations and attributes by their XML names.
+fn attribute_map(element: &Element) -> Map<String, Value> {
+    let namespaces = element.namespaces.iter().map(|namespace| (namespace.attribute_name(), Value::from(namespace.uri.as_str())));
+    let attributes = element.attributes.iter().map(|attribute| (attribute.name.clone(), Value::from(attribute.value.as_str())));
+
+    namespaces.chain(attributes).collect()
+}
+
+/// Child elements and non-empty text in document order.
+fn content_values(element: &Element, value: fn(&Element) -> Value) -> Vec<Value> {
+    element
+        .children
+        .iter()
+        .filter_map(|node| match node {
+            Node::Element(child) => Some(value(child)),
+            Node::Text(text) | Node::CData(text) if !text.is_empty() => Some(Value::from(text.as_str())),
+            _ => None,
+        })
+        .collect()
+}
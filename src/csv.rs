@@ -0,0 +1,174 @@
+use crate::error::ConversionError;
+use crate::generate_xml::RecordRoot;
+use crate::options::ConversionOptions;
+
+use ::csv::{ReaderBuilder, StringRecord};
+use quick_xml::Writer;
+use serde_json::{Map, Number, Value};
+use std::io::Cursor;
+
+/// Options for converting CSV input.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Name of the root element. Defaults to `Rows`.
+    pub root: String,
+    /// Name of the element written for each row. Defaults to `Row`.
+    pub row: String,
+    /// Field delimiter. Defaults to `,`.
+    pub delimiter: u8,
+    /// Write columns whose values are all numbers or all booleans as numbers or booleans, instead of strings.
+    /// Enabled by default.
+    pub infer_types: bool,
+    /// Options for converting each row.
+    pub conversion: ConversionOptions,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            root: "Rows".to_string(),
+            row: "Row".to_string(),
+            delimiter: b',',
+            infer_types: true,
+            conversion: ConversionOptions::default(),
+        }
+    }
+}
+
+/// The inferred type of a CSV column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Number,
+    Boolean,
+    String,
+}
+
+/// # Convert CSV to XML.
+///
+/// Each row becomes a JSON object keyed by the header, which is converted with the conventions of `json_to_xml_with_root`
+/// and written below one root element. A header starting with `@` (e.g. `@id`) makes the column an attribute of the row element.
+///
+/// With `infer_types`, a column is written as numbers when all of its non-empty cells are JSON numbers that keep their text,
+/// and as booleans when they are all `true` or `false`. Numbers are never rewritten, so columns with values like `007`, `1.50`
+/// or `2.5e1` stay text, unless the `arbitrary_precision` feature keeps their digits as written.
+/// Empty cells are written as empty elements.
+///
+/// The `RootPolicy` of the conversion options decides about the root element, as for `ndjson::ndjson_to_xml`:
+/// `Fragment` writes the rows without it, and `Error` requires exactly one row instead.
+///
+/// Requires the `csv` cargo feature.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::csv::{csv_to_xml, CsvOptions};
+/// use json_to_xml::options::{BooleanFormat, ConversionOptions};
+///
+/// let csv_string = "@id,name,price,in_stock\n1,Pen,1.50,true\n2,Paper,3,false\n";
+///
+/// let options = CsvOptions {
+///     root: "Products".to_string(),
+///     row: "Product".to_string(),
+///     conversion: ConversionOptions { boolean_format: BooleanFormat::YesNo, ..Default::default() },
+///     ..Default::default()
+/// };
+///
+/// let xml_string = csv_to_xml(csv_string, &options).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Products>
+///   <Product id="1">
+///     <In_stock>yes</In_stock>
+///     <Name>Pen</Name>
+///     <Price>1.50</Price>
+///   </Product>
+///   <Product id="2">
+///     <In_stock>no</In_stock>
+///     <Name>Paper</Name>
+///     <Price>3</Price>
+///   </Product>
+/// </Products>
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, a `ConversionError::Csv` if the CSV cannot be read,
+/// a `ConversionError::DuplicateColumn` if two columns have the same header,
+/// or a `ConversionError::InvalidName` if `root` or `row` is not an XML name.
+pub fn csv_to_xml(csv_string: &str, options: &CsvOptions) -> Result<String, ConversionError> {
+    let mut reader = ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .from_reader(csv_string.as_bytes());
+
+    let headers = reader.headers()?.clone();
+
+    // Rows are keyed by the header, so a repeated header would overwrite the cells of its first column.
+    for (column, header) in headers.iter().enumerate() {
+        if headers.iter().take(column).any(|other| other == header) {
+            return Err(ConversionError::DuplicateColumn(header.to_string()));
+        }
+    }
+    let records = reader.records().collect::<Result<Vec<StringRecord>, _>>()?;
+
+    let column_types: Vec<ColumnType> = (0..headers.len())
+        .map(|column| if options.infer_types {
+            infer_column_type(records.iter().filter_map(|record| record.get(column)))
+        } else {
+            ColumnType::String
+        })
+        .collect();
+
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    let mut root = RecordRoot::start(&mut writer, &options.root, &options.row, &options.conversion)?;
+
+    for record in &records {
+        let row: Map<String, Value> = headers
+            .iter()
+            .zip(record.iter())
+            .zip(&column_types)
+            .map(|((header, cell), column_type)| (header.to_string(), cell_value(cell, *column_type)))
+            .collect();
+
+        root.write_record(&mut writer, &Value::Object(row), &options.conversion)?;
+    }
+
+    root.finish(&mut writer, &options.conversion)?;
+
+    Ok(String::from_utf8(writer.into_inner().into_inner())?)
+}
+
+fn infer_column_type<'a>(cells: impl Iterator<Item = &'a str>) -> ColumnType {
+    let mut column_type = None;
+
+    for cell in cells.filter(|cell| !cell.is_empty()) {
+        let cell_type = if cell.parse::<Number>().is_ok_and(|number| number.to_string() == cell) {
+            ColumnType::Number
+        } else if cell.eq_ignore_ascii_case("true") || cell.eq_ignore_ascii_case("false") {
+            ColumnType::Boolean
+        } else {
+            return ColumnType::String;
+        };
+
+        if column_type.is_some_and(|column_type| column_type != cell_type) {
+            return ColumnType::String;
+        }
+        column_type = Some(cell_type);
+    }
+
+    column_type.unwrap_or(ColumnType::String)
+}
+
+fn cell_value(cell: &str, column_type: ColumnType) -> Value {
+    match column_type {
+        _ if cell.is_empty() => Value::String(String::new()),
+        ColumnType::Number => cell.parse::<Number>().map_or_else(|_| Value::String(cell.to_string()), Value::Number),
+        ColumnType::Boolean => Value::Bool(cell.eq_ignore_ascii_case("true")),
+        ColumnType::String => Value::String(cell.to_string()),
+    }
+}
@@ -0,0 +1,720 @@
+use crate::error::ConversionError;
+use crate::generate_xml::{capitalize_word, value_to_nodes};
+use crate::json_schema::Hints;
+use crate::format::format_boolean;
+use crate::options::{ArrayMarker, ConversionOptions, EmptyArray, NestedArray, TopLevelArray, TypeAnnotation};
+use crate::xml_utils::{check_names, check_text, write_declaration, write_start_tag, write_empty_tag, write_end_tag, write_content, write_inline};
+
+use quick_xml::{Reader, Writer};
+use quick_xml::errors::IllFormedError;
+use quick_xml::escape::{resolve_predefined_entity, unescape, EscapeError};
+use quick_xml::events::{BytesCData, BytesEnd, BytesPI, BytesStart, BytesText, Event};
+use serde_json::{Map, Number, Value};
+use std::io::{Cursor, Write};
+
+/// An XML document: the elements, text, comments and processing instructions at its top level.
+///
+/// The document model sits between the value tree shared by the JSON, YAML and TOML readers and the XML text,
+/// so each format only needs a conversion to or from the model:
+/// - `Document::from_xml` and `Document::to_xml` read and write XML.
+/// - `Document::from_value` and `Document::to_value` map a value tree with the conventions of `json_to_xml_with_root`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub nodes: Vec<Node>,
+}
+
+/// A node of a `Document` or of an element's content.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+    CData(String),
+    Comment(String),
+    ProcessingInstruction { target: String, content: String },
+}
+
+/// An XML element. The name keeps its namespace prefix, e.g. `addr:Street`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Element {
+    pub name: String,
+    /// Namespace declarations (`xmlns` and `xmlns:prefix` attributes) of this element.
+    pub namespaces: Vec<Namespace>,
+    /// All other attributes, in document order.
+    pub attributes: Vec<Attribute>,
+    pub children: Vec<Node>,
+}
+
+/// A namespace declaration. The default namespace has no prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Namespace {
+    pub prefix: Option<String>,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub value: String,
+}
+
+impl Element {
+    pub fn new(name: impl Into<String>) -> Self {
+        Element { name: name.into(), ..Default::default() }
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|attribute| attribute.name == name).map(|attribute| attribute.value.as_str())
+    }
+
+    /// Add an attribute, or a namespace declaration if the name is `xmlns` or starts with `xmlns:`.
+    pub fn push_attribute(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+
+        if name == "xmlns" {
+            self.namespaces.push(Namespace { prefix: None, uri: value });
+        } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+            self.namespaces.push(Namespace { prefix: Some(prefix.to_string()), uri: value });
+        } else {
+            self.attributes.push(Attribute { name: name.to_string(), value });
+        }
+    }
+
+    /// The child elements, skipping text, comments and processing instructions.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Whether the element holds both child elements and text other than whitespace, e.g. `<p>Hello <b>world</b></p>`.
+    pub fn has_mixed_content(&self) -> bool {
+        self.elements().next().is_some() && has_text(&self.children)
+    }
+
+    /// All text and CDATA directly inside this element, concatenated.
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                Node::Text(text) | Node::CData(text) => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Namespace {
+    /// The attribute declaring this namespace, e.g. `xmlns:addr`.
+    pub fn attribute_name(&self) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("xmlns:{}", prefix),
+            None => "xmlns".to_string(),
+        }
+    }
+}
+
+impl Document {
+    /// The first element at the top level.
+    pub fn root(&self) -> Option<&Element> {
+        self.nodes.iter().find_map(|node| match node {
+            Node::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// # Read an XML document.
+    ///
+    /// Keeps elements, attributes, namespace declarations, text, CDATA sections, comments and processing instructions.
+    /// The XML declaration and the document type declaration are skipped.
+    /// Whitespace-only text between elements is treated as indentation and dropped, unless the element has mixed content.
+    /// An element written as `<Tag></Tag>` keeps an empty text node, so it can be told apart from `<Tag/>`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use json_to_xml::document::{Document, Node};
+    ///
+    /// let document = Document::from_xml(r#"<?xml version="1.0"?>
+    /// <!-- orders -->
+    /// <Order id="1"><Item>Pen</Item></Order>"#).unwrap();
+    ///
+    /// assert_eq!(document.nodes[0], Node::Comment(" orders ".to_string()));
+    ///
+    /// let order = document.root().unwrap();
+    /// assert_eq!(order.attribute("id"), Some("1"));
+    /// assert_eq!(order.elements().next().unwrap().text(), "Pen");
+    /// ```
+    ///
+    /// ## Returns:
+    /// A `Result` which is either the `Document`, or a `ConversionError::Xml` if the XML is not well-formed
+    /// or references an entity other than the predefined ones and character references.
+    pub fn from_xml(xml: &str) -> Result<Document, ConversionError> {
+        let mut reader = Reader::from_str(xml);
+        let mut document = Document::default();
+        let mut stack: Vec<Element> = Vec::new();
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => stack.push(element_from_start(&start)?),
+                Event::Empty(start) => {
+                    let element = element_from_start(&start)?;
+                    content(&mut stack, &mut document.nodes).push(Node::Element(element));
+                },
+                Event::End(end) => {
+                    let mut element = stack.pop().ok_or_else(|| {
+                        quick_xml::Error::IllFormed(IllFormedError::UnmatchedEndTag(String::from_utf8_lossy(end.name().as_ref()).into_owned()))
+                    })?;
+
+                    if element.children.is_empty() {
+                        element.children.push(Node::Text(String::new()));
+                    } else if !has_text(&element.children) {
+                        drop_indentation(&mut element.children);
+                    }
+                    content(&mut stack, &mut document.nodes).push(Node::Element(element));
+                },
+                Event::Text(text) => {
+                    let text = text.decode().map_err(quick_xml::Error::from)?;
+                    push_text(content(&mut stack, &mut document.nodes), &unescape(&text).map_err(quick_xml::Error::from)?);
+                },
+                Event::GeneralRef(reference) => {
+                    let nodes = content(&mut stack, &mut document.nodes);
+                    if let Some(ch) = reference.resolve_char_ref()? {
+                        push_text(nodes, ch.encode_utf8(&mut [0; 4]));
+                    } else {
+                        let name = reference.decode().map_err(quick_xml::Error::from)?;
+                        let Some(entity) = resolve_predefined_entity(&name) else {
+                            // `&name;` ends at the current position.
+                            let end = reader.buffer_position() as usize - 1;
+                            let range = end - name.len()..end;
+                            return Err(quick_xml::Error::Escape(EscapeError::UnrecognizedEntity(range, name.into_owned())).into());
+                        };
+                        push_text(nodes, entity);
+                    }
+                },
+                Event::CData(cdata) => {
+                    let cdata = cdata.decode().map_err(quick_xml::Error::from)?.into_owned();
+                    content(&mut stack, &mut document.nodes).push(Node::CData(cdata));
+                },
+                Event::Comment(comment) => {
+                    let comment = comment.decode().map_err(quick_xml::Error::from)?.into_owned();
+                    content(&mut stack, &mut document.nodes).push(Node::Comment(comment));
+                },
+                Event::PI(pi) => {
+                    let target = String::from_utf8_lossy(pi.target()).into_owned();
+                    let content_text = String::from_utf8_lossy(pi.content()).trim_start().to_string();
+                    content(&mut stack, &mut document.nodes).push(Node::ProcessingInstruction { target, content: content_text });
+                },
+                Event::Decl(_) | Event::DocType(_) => {},
+                Event::Eof => break,
+            }
+        }
+
+        if let Some(element) = stack.pop() {
+            return Err(quick_xml::Error::IllFormed(IllFormedError::MissingEndTag(element.name)).into());
+        }
+
+        drop_indentation(&mut document.nodes);
+        Ok(document)
+    }
+
+    /// # Write the document as XML.
+    ///
+    /// Writes the XML declaration followed by the nodes, indented by two spaces.
+    /// Elements with mixed content are written without indentation, so their text is kept exactly.
+    /// Elements without content are written as self-closing tags.
+    ///
+    /// ## Returns:
+    /// A `Result` which is either a `String` containing the XML, or a `ConversionError` if writing fails,
+    /// e.g. `ConversionError::InvalidName` for an element or attribute name that is not an XML name.
+    pub fn to_xml(&self) -> Result<String, ConversionError> {
+        let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+        write_declaration(&mut writer, "1.0", Some("UTF-8"))?;
+
+        write_nodes(&mut writer, &self.nodes)?;
+
+        Ok(String::from_utf8(writer.into_inner().into_inner())?)
+    }
+
+    /// # Build a document from a value tree.
+    ///
+    /// Uses the conventions of `json_to_xml_with_options`:
+    /// - Keys are capitalized and become elements. Keys starting with `@` become attributes and `$text` becomes text.
+    /// - `$content` is mixed content: an array of strings, written as text, and objects, written as elements, in order, or a single one of them.
+    /// - Arrays of objects become repeated elements, other arrays become `<TagItem>` children. `ArrayMarker` marks them.
+    /// - `null` becomes `<None/>`, an empty object a self-closing element and an empty array as configured by `EmptyArray`.
+    /// - The root element is added as configured by `RootPolicy`, and always when the top-level object has `@` attributes, `$text` or `$content`.
+    ///   Top-level scalars and arrays are written inside the root element, with array items named as configured by `TopLevelArray`.
+    ///
+    /// The document starts with the "Generated with" comment, so `to_xml` gives the same output as `json_to_xml_with_options`.
+    /// With `RootPolicy::Fragment` the comment is left out, but `to_xml` still writes the XML declaration.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use json_to_xml::document::Document;
+    /// use json_to_xml::options::ConversionOptions;
+    /// use serde_json::json;
+    ///
+    /// let value = json!({ "@id": 7, "tags": ["a", "b"] });
+    ///
+    /// let document = Document::from_value(&value, "Post", &ConversionOptions::default()).unwrap();
+    ///
+    /// println!("{}", document.to_xml().unwrap());
+    /// ```
+    ///
+    /// ## Expected Output (XML):
+    ///
+    /// ```xml
+    /// <?xml version="1.0" encoding="UTF-8"?>
+    /// <!--Generated with json_to_xml 0.1.8-->
+    /// <Post id="7">
+    ///   <Tags>
+    ///     <TagsItem>a</TagsItem>
+    ///     <TagsItem>b</TagsItem>
+    ///   </Tags>
+    /// </Post>
+    /// ```
+    pub fn from_value(value: &Value, root: &str, options: &ConversionOptions) -> Result<Document, ConversionError> {
+        Ok(Document { nodes: value_to_nodes(value.clone(), root, options, Vec::new(), &Hints::default())? })
+    }
+
+    /// # Map the document to a value tree.
+    ///
+    /// The reverse of `from_value`, as far as XML allows:
+    /// - Elements become keys with their names unchanged. Repeated elements become arrays.
+    /// - Attributes and namespace declarations become `@` keys, and the text of elements with attributes becomes `$text`.
+    /// - Mixed content becomes a `$content` array of strings and single-key objects, in document order.
+    /// - An element whose children are all `<TagItem>` elements becomes an array, and `<Tag><None/></Tag>` becomes `null`.
+    /// - `<Tag/>` becomes an empty object and `<Tag></Tag>` an empty string.
+    /// - Text is kept as strings, unless the element has an `xsi:type` of `xs:integer`, `xs:decimal`, `xs:double` or `xs:boolean`.
+    ///   The annotation attribute is dropped.
+    ///
+    /// Comments and processing instructions have no value representation and are dropped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use json_to_xml::document::Document;
+    /// use serde_json::json;
+    ///
+    /// let document = Document::from_xml(r#"<Post id="7"><Tags><TagsItem>a</TagsItem><TagsItem>b</TagsItem></Tags></Post>"#).unwrap();
+    ///
+    /// assert_eq!(document.to_value(), json!({ "Post": { "@id": "7", "Tags": ["a", "b"] } }));
+    /// ```
+    pub fn to_value(&self) -> Value {
+        self.to_value_with_options(&ConversionOptions::default())
+    }
+
+    /// # Map the document to a value tree, reading the markers written with `options`.
+    ///
+    /// Works like `to_value`, reading back the markers of `options`:
+    /// - With `EmptyArray::Marked`, self-closing elements holding only the marker attribute become empty arrays.
+    /// - With `ArrayMarker`, only marked elements become arrays, so single-item arrays and objects with `<TagItem>` children read back exactly.
+    ///   The marker attribute and its namespace declaration are dropped.
+    /// - With `TypeAnnotation::Attribute`, text annotated as `number` or `boolean` becomes a number or boolean, like with `xsi:type`.
+    ///   Booleans are read in the `BooleanFormat` of `options` as well as `true`/`false` and `1`/`0`.
+    ///
+    /// Together with `TypeAnnotation::XsiType` or `TypeAnnotation::Attribute` for the leaf types, this reads XML written by `json_to_xml_with_options` back into the original JSON,
+    /// with capitalized keys. An array holding a single object whose only keys are `<TagItem>` cannot be told apart from an array of those items.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use json_to_xml::document::Document;
+    /// use json_to_xml::options::{ConversionOptions, EmptyArray};
+    /// use serde_json::json;
+    ///
+    /// let options = ConversionOptions { empty_array: EmptyArray::Marked("array".to_string()), ..Default::default() };
+    ///
+    /// let document = Document::from_xml(r#"<Post><Tags array="true"/></Post>"#).unwrap();
+    ///
+    /// assert_eq!(document.to_value_with_options(&options), json!({ "Post": { "Tags": [] } }));
+    /// ```
+    pub fn to_value_with_options(&self, options: &ConversionOptions) -> Value {
+        Value::Object(group_elements(self.nodes.iter(), options))
+    }
+}
+
+/// # Convert XML to JSON.
+///
+/// Reads the XML into a `Document` and maps it with `Document::to_value`, the reverse of the `json_to_xml` conventions.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::document::xml_to_json;
+///
+/// let xml = r#"<Book isbn="978-3-16-148410-0"><Title>The Rust Programming Language</Title></Book>"#;
+///
+/// let json_string = xml_to_json(xml).unwrap();
+///
+/// println!("{}", json_string);
+/// ```
+///
+/// ## Expected Output (JSON):
+///
+/// ```json
+/// {
+///   "Book": {
+///     "@isbn": "978-3-16-148410-0",
+///     "Title": "The Rust Programming Language"
+///   }
+/// }
+/// ```
+pub fn xml_to_json(xml: &str) -> Result<String, ConversionError> {
+    xml_to_json_with_options(xml, &ConversionOptions::default())
+}
+
+/// # Convert XML to JSON, reading the markers written with conversion options.
+///
+/// Maps the XML with `Document::to_value_with_options`, so XML written by `json_to_xml_with_options` reads back with the same `options`.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::document::xml_to_json_with_options;
+/// use json_to_xml::generate_xml::json_to_xml_with_options;
+/// use json_to_xml::options::{ConversionOptions, EmptyArray};
+///
+/// let options = ConversionOptions { empty_array: EmptyArray::Marked("array".to_string()), ..Default::default() };
+///
+/// let xml = json_to_xml_with_options(r#"{"post": {"tags": []}}"#, "Root", &options).unwrap();
+///
+/// assert_eq!(xml_to_json_with_options(&xml, &options).unwrap(), "{\n  \"Post\": {\n    \"Tags\": []\n  }\n}");
+/// ```
+pub fn xml_to_json_with_options(xml: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    Ok(serde_json::to_string_pretty(&Document::from_xml(xml)?.to_value_with_options(options))?)
+}
+
+/// # Convert XML to YAML.
+///
+/// Maps the XML like `xml_to_json` and writes the result as YAML.
+///
+/// Requires the `yaml` cargo feature.
+#[cfg(feature = "yaml")]
+pub fn xml_to_yaml(xml: &str) -> Result<String, ConversionError> {
+    Ok(serde_yaml::to_string(&json_to_yaml(Document::from_xml(xml)?.to_value()))?)
+}
+
+/// # Convert XML to TOML.
+///
+/// Maps the XML like `xml_to_json` and writes the result as TOML.
+/// TOML has no null, so `<Tag><None/></Tag>` elements are left out.
+pub fn xml_to_toml(xml: &str) -> Result<String, ConversionError> {
+    let table = match json_to_toml(Document::from_xml(xml)?.to_value()) {
+        Some(::toml::Value::Table(table)) => table,
+        _ => ::toml::Table::new(),
+    };
+
+    Ok(::toml::to_string(&table)?)
+}
+
+/// The "Generated with" comment that starts every generated document.
+/// The "Generated with" comment, naming the version of this crate as it was compiled, so no file is read.
+pub(crate) fn generated_comment() -> Node {
+    Node::Comment(format!("Generated with json_to_xml {}", env!("CARGO_PKG_VERSION")))
+}
+
+/// The content of the innermost open element, or the top level of the document.
+fn content<'a>(stack: &'a mut [Element], nodes: &'a mut Vec<Node>) -> &'a mut Vec<Node> {
+    match stack.last_mut() {
+        Some(element) => &mut element.children,
+        None => nodes,
+    }
+}
+
+/// Append text, merging it with a directly preceding text node. Entity references arrive as separate events.
+fn push_text(nodes: &mut Vec<Node>, text: &str) {
+    match nodes.last_mut() {
+        Some(Node::Text(previous)) => previous.push_str(text),
+        _ => nodes.push(Node::Text(text.to_string())),
+    }
+}
+
+/// Whether the nodes include text other than whitespace.
+fn has_text(nodes: &[Node]) -> bool {
+    nodes.iter().any(|node| matches!(node, Node::Text(text) | Node::CData(text) if !text.trim().is_empty()))
+}
+
+fn drop_indentation(nodes: &mut Vec<Node>) {
+    nodes.retain(|node| !matches!(node, Node::Text(text) if text.trim().is_empty()));
+}
+
+fn element_from_start(start: &BytesStart) -> Result<Element, ConversionError> {
+    let mut element = Element::new(String::from_utf8_lossy(start.name().as_ref()));
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(quick_xml::Error::from)?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+        element.push_attribute(&key, attribute.unescape_value()?);
+    }
+
+    Ok(element)
+}
+
+/// Write nodes as XML, indented as the writer is configured.
+pub(crate) fn write_nodes<W: Write>(writer: &mut Writer<W>, nodes: &[Node]) -> Result<(), ConversionError> {
+    nodes.iter().try_for_each(|node| write_node(writer, node))
+}
+
+/// The start tag of an element, with its namespace declarations before its attributes. The names are not checked.
+pub(crate) fn start_tag(element: &Element) -> BytesStart<'_> {
+    let mut start = BytesStart::new(element.name.as_str());
+    for namespace in &element.namespaces {
+        start.push_attribute((namespace.attribute_name().as_str(), namespace.uri.as_str()));
+    }
+    for attribute in &element.attributes {
+        start.push_attribute((attribute.name.as_str(), attribute.value.as_str()));
+    }
+    start
+}
+
+fn write_node<W: Write>(writer: &mut Writer<W>, node: &Node) -> Result<(), ConversionError> {
+    match node {
+        Node::Element(element) => {
+            check_names(element, "")?;
+
+            let start = start_tag(element);
+
+            if element.children.is_empty() {
+                write_empty_tag(writer, &start)?;
+            } else if element.has_mixed_content() {
+                write_start_tag(writer, &start)?;
+                write_inline(writer, |inline| element.children.iter().try_for_each(|child| write_node(inline, child)))?;
+                write_end_tag(writer, &BytesEnd::new(element.name.as_str()))?;
+            } else {
+                write_start_tag(writer, &start)?;
+                for child in &element.children {
+                    write_node(writer, child)?;
+                }
+                write_end_tag(writer, &BytesEnd::new(element.name.as_str()))?;
+            }
+        },
+        Node::Text(text) => {
+            check_text(text, "")?;
+            write_content(writer, text)?;
+        },
+        Node::CData(text) => {
+            check_text(text, "")?;
+            for section in BytesCData::escaped(text) {
+                writer.write_event(Event::CData(section))?;
+            }
+        },
+        Node::Comment(text) => {
+            check_text(text, "")?;
+            writer.write_event(Event::Comment(BytesText::from_escaped(text)))?;
+        },
+        Node::ProcessingInstruction { target, content } if content.is_empty() => writer.write_event(Event::PI(BytesPI::new(target)))?,
+        Node::ProcessingInstruction { target, content } => writer.write_event(Event::PI(BytesPI::new(format!("{} {}", target, content))))?,
+    }
+    Ok(())
+}
+
+/// Collect elements into an object, turning repeated names into arrays.
+fn group_elements<'a>(nodes: impl Iterator<Item = &'a Node>, options: &ConversionOptions) -> Map<String, Value> {
+    // Elements of a marked array of objects stay an array, even when there is only one.
+    let mut groups: Vec<(&str, Vec<Value>, bool)> = Vec::new();
+
+    for node in nodes {
+        if let Node::Element(element) = node {
+            let value = element_value(element, options);
+            let array_member = is_marked_array(element, options) && !is_array_wrapper(element, options);
+            match groups.iter_mut().find(|(name, _, _)| *name == element.name) {
+                Some((_, values, _)) => values.push(value),
+                None => groups.push((&element.name, vec![value], array_member)),
+            }
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(name, mut values, array_member)| {
+            let value = if values.len() == 1 && !array_member { values.remove(0) } else { Value::Array(values) };
+            (name.to_string(), value)
+        })
+        .collect()
+}
+
+fn element_value(element: &Element, options: &ConversionOptions) -> Value {
+    let attributes = own_attributes(element, options);
+    let namespaces: Vec<&Namespace> = element
+        .namespaces
+        .iter()
+        .filter(|namespace| {
+            !options.array_marker.as_ref().is_some_and(|marker| namespace.prefix.as_deref() == Some(marker.prefix.as_str()) && namespace.uri == marker.namespace)
+        })
+        .collect();
+
+    if let EmptyArray::Marked(marker) = &options.empty_array
+        && element.children.is_empty()
+        && namespaces.is_empty()
+        && matches!(attributes.as_slice(), [attribute] if attribute.name == *marker && attribute.value == "true")
+    {
+        return Value::Array(Vec::new());
+    }
+    if is_array_wrapper(element, options) {
+        return Value::Array(element.elements().map(|child| element_value(child, options)).collect());
+    }
+
+    let annotation = type_annotation(element, options);
+    let annotation_name = annotation.map(|(name, _)| name);
+    let json_type = annotation.map(|(_, json_type)| json_type);
+    let has_attributes = !namespaces.is_empty() || attributes.iter().any(|attribute| Some(attribute.name.as_str()) != annotation_name);
+    let elements: Vec<&Element> = element.elements().collect();
+    let text = element.text();
+
+    if !has_attributes {
+        match elements.as_slice() {
+            [] if element.children.is_empty() => return Value::Object(Map::new()),
+            [] => return typed_text(text, json_type, options),
+            [none] if none.name == "None" && none.children.is_empty() && text.is_empty() => return Value::Null,
+            // With the array marker every array is marked, so unmarked `<TagItem>` children belong to an object.
+            elements if options.array_marker.is_none() && text.is_empty() && elements.iter().all(|child| is_item_name(&element.name, &child.name, options)) => {
+                return Value::Array(elements.iter().map(|child| element_value(child, options)).collect());
+            },
+            _ => {},
+        }
+    }
+
+    let mut map = Map::new();
+
+    for namespace in namespaces {
+        map.insert(format!("@{}", namespace.attribute_name()), Value::from(namespace.uri.as_str()));
+    }
+    for attribute in attributes {
+        if Some(attribute.name.as_str()) != annotation_name {
+            map.insert(format!("@{}", attribute.name), Value::from(attribute.value.as_str()));
+        }
+    }
+    if let [none] = elements.as_slice()
+        && none.name == "None"
+        && none.children.is_empty()
+        && text.is_empty()
+    {
+        map.insert("$text".to_string(), Value::Null);
+        return Value::Object(map);
+    }
+    if element.has_mixed_content() {
+        map.insert("$content".to_string(), Value::Array(element.children.iter().filter_map(|node| content_value(node, options)).collect()));
+        return Value::Object(map);
+    }
+    if !text.is_empty() {
+        map.insert("$text".to_string(), typed_text(text, json_type, options));
+    }
+
+    map.extend(group_elements(element.children.iter(), options));
+    Value::Object(map)
+}
+
+/// The attributes of an element, without the `ArrayMarker` attribute.
+fn own_attributes<'a>(element: &'a Element, options: &ConversionOptions) -> Vec<&'a Attribute> {
+    let marker = options.array_marker.as_ref().map(ArrayMarker::attribute_name);
+
+    element.attributes.iter().filter(|attribute| marker.as_deref() != Some(attribute.name.as_str())).collect()
+}
+
+/// Whether the element carries the `ArrayMarker` attribute.
+fn is_marked_array(element: &Element, options: &ConversionOptions) -> bool {
+    options.array_marker.as_ref().is_some_and(|marker| element.attribute(&marker.attribute_name()) == Some("true"))
+}
+
+/// Whether the element is a marked array holding its items, rather than one element of a marked array of objects.
+fn is_array_wrapper(element: &Element, options: &ConversionOptions) -> bool {
+    let empty_array_marker = match &options.empty_array {
+        EmptyArray::Marked(marker) => Some(marker.as_str()),
+        _ => None,
+    };
+
+    is_marked_array(element, options)
+        && element.text().is_empty()
+        && own_attributes(element, options).iter().all(|attribute| Some(attribute.name.as_str()) == empty_array_marker)
+        && element.elements().all(|child| is_item_name(&element.name, &child.name, options))
+}
+
+/// Whether a child names an item of the array element `parent`: `<ParentItem>`,
+/// or a name configured by `TopLevelArray::Named` or `NestedArray::Named`.
+fn is_item_name(parent: &str, child: &str, options: &ConversionOptions) -> bool {
+    child == format!("{}Item", parent)
+        || matches!(&options.top_level_array, TopLevelArray::Named(name) if capitalize_word(name) == child)
+        || matches!(&options.nested_array, NestedArray::Named(names) if names.iter().any(|name| capitalize_word(name) == child))
+}
+
+fn content_value(node: &Node, options: &ConversionOptions) -> Option<Value> {
+    match node {
+        Node::Element(element) => Some(Value::Object(Map::from_iter([(element.name.clone(), element_value(element, options))]))),
+        Node::Text(text) | Node::CData(text) => Some(Value::from(text.as_str())),
+        _ => None,
+    }
+}
+
+/// The name of the attribute annotating the JSON type of an element, and that type: `xsi:type`,
+/// or the attribute of `TypeAnnotation::Attribute`. Attributes with other values are left alone.
+fn type_annotation<'a>(element: &'a Element, options: &ConversionOptions) -> Option<(&'a str, &'static str)> {
+    element.attributes.iter().find_map(|attribute| {
+        let json_type = match (attribute.name.as_str(), attribute.value.as_str()) {
+            ("xsi:type", "xs:integer" | "xs:decimal" | "xs:double") => "number",
+            ("xsi:type", "xs:boolean") => "boolean",
+            ("xsi:type", "xs:string") => "string",
+            (name, json_type) if matches!(&options.type_annotation, TypeAnnotation::Attribute(annotation) if annotation == name) => match json_type {
+                "number" => "number",
+                "boolean" => "boolean",
+                "string" => "string",
+                "null" => "null",
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some((attribute.name.as_str(), json_type))
+    })
+}
+
+fn typed_text(text: String, json_type: Option<&str>, options: &ConversionOptions) -> Value {
+    match json_type {
+        Some("number") => text.trim().parse::<Number>().map_or(Value::String(text), Value::Number),
+        Some("boolean") => match text.trim() {
+            "true" | "1" => Value::Bool(true),
+            "false" | "0" => Value::Bool(false),
+            b if b == format_boolean(true, &options.boolean_format) => Value::Bool(true),
+            b if b == format_boolean(false, &options.boolean_format) => Value::Bool(false),
+            _ => Value::String(text),
+        },
+        _ => Value::String(text),
+    }
+}
+
+/// Numbers are converted by hand, as `arbitrary_precision` numbers do not serialize as plain numbers.
+fn json_to_toml(value: Value) -> Option<::toml::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => ::toml::Value::Boolean(b),
+        Value::Number(num) => match (num.as_i64(), num.as_f64()) {
+            (Some(i), _) => ::toml::Value::Integer(i),
+            (None, Some(f)) if !num.is_u64() => ::toml::Value::Float(f),
+            _ => ::toml::Value::String(num.to_string()),
+        },
+        Value::String(s) => ::toml::Value::String(s),
+        Value::Array(items) => ::toml::Value::Array(items.into_iter().filter_map(json_to_toml).collect()),
+        Value::Object(map) => ::toml::Value::Table(map.into_iter().filter_map(|(key, value)| Some((key, json_to_toml(value)?))).collect()),
+    })
+}
+
+#[cfg(feature = "yaml")]
+fn json_to_yaml(value: Value) -> serde_yaml::Value {
+    use serde_yaml::Value as YamlValue;
+
+    match value {
+        Value::Null => YamlValue::Null,
+        Value::Bool(b) => YamlValue::Bool(b),
+        Value::Number(num) => match (num.as_i64(), num.as_u64(), num.as_f64()) {
+            (Some(i), _, _) => YamlValue::from(i),
+            (_, Some(u), _) => YamlValue::from(u),
+            (_, _, Some(f)) => YamlValue::from(f),
+            _ => YamlValue::String(num.to_string()),
+        },
+        Value::String(s) => YamlValue::String(s),
+        Value::Array(items) => YamlValue::Sequence(items.into_iter().map(json_to_yaml).collect()),
+        Value::Object(map) => YamlValue::Mapping(map.into_iter().map(|(key, value)| (YamlValue::String(key), json_to_yaml(value))).collect()),
+    }
+}
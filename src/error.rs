@@ -1,12 +1,39 @@
+use crate::xsd::Violation;
+
 use std::fmt;
 
+/// Variants are added over time and some only exist with their cargo feature, so matches need a wildcard arm.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ConversionError {
     Json(serde_json::Error),
     Xml(quick_xml::Error),
     Io(std::io::Error),
     Utf8(std::string::FromUtf8Error),
     Toml(toml::de::Error),
+    TomlSerialize(toml::ser::Error),
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+    #[cfg(feature = "msgpack")]
+    MessagePack(rmpv::decode::Error),
+    #[cfg(feature = "cbor")]
+    Cbor(ciborium::de::Error<std::io::Error>),
+    InvalidSchema(String),
+    Schema(Vec<Violation>),
+    Line { line: usize, error: serde_json::Error },
+    Json5 { line: usize, column: usize, message: String },
+    Convention(String),
+    RootCount(usize),
+    TextOutsideRoot(String),
+    Unbalanced { path: String, element: String },
+    InvalidPath(String),
+    InvalidName { path: String, name: String },
+    DuplicateAttribute { path: String, name: String },
+    InvalidCharacter { path: String, character: char },
+    InvalidRename { key: String, name: String },
+    DuplicateColumn(String),
 }
 
 impl fmt::Display for ConversionError {
@@ -17,6 +44,38 @@ impl fmt::Display for ConversionError {
             ConversionError::Io(e) => write!(f, "IO error: {}", e),
             ConversionError::Utf8(e) => write!(f, "UTF-8 conversion error: {}", e),
             ConversionError::Toml(e) => write!(f, "TOML error: {}", e),
+            ConversionError::TomlSerialize(e) => write!(f, "TOML serialization error: {}", e),
+            #[cfg(feature = "yaml")]
+            ConversionError::Yaml(e) => write!(f, "YAML error: {}", e),
+            #[cfg(feature = "csv")]
+            ConversionError::Csv(e) => write!(f, "CSV error: {}", e),
+            #[cfg(feature = "msgpack")]
+            ConversionError::MessagePack(e) => write!(f, "MessagePack error: {}", e),
+            #[cfg(feature = "cbor")]
+            ConversionError::Cbor(e) => write!(f, "CBOR error: {}", e),
+            ConversionError::InvalidSchema(e) => write!(f, "Invalid schema: {}", e),
+            ConversionError::Schema(violations) => {
+                write!(f, "Schema violations:")?;
+                for violation in violations {
+                    write!(f, "\n  {}", violation)?;
+                }
+                Ok(())
+            },
+            ConversionError::Line { line, error } => write!(f, "JSON error on line {}: {}", line, error),
+            ConversionError::Json5 { line, column, message } => write!(f, "JSON5 error at line {} column {}: {}", line, column, message),
+            ConversionError::Convention(e) => write!(f, "Convention error: {}", e),
+            ConversionError::RootCount(count) => write!(f, "Expected exactly one root element, found {}", count),
+            ConversionError::TextOutsideRoot(text) => write!(f, "Text outside the root element: \"{}\"", text.trim()),
+            ConversionError::Unbalanced { path, element } => write!(f, "Unbalanced element <{}> at JSON path \"{}\"", element, path),
+            ConversionError::InvalidPath(pattern) => write!(f, "Invalid path pattern: {}", pattern),
+            ConversionError::InvalidName { path, name } if path.is_empty() => write!(f, "Invalid XML name \"{}\"", name),
+            ConversionError::InvalidName { path, name } => write!(f, "Invalid XML name \"{}\" at JSON path \"{}\"", name, path),
+            ConversionError::DuplicateAttribute { path, name } if path.is_empty() => write!(f, "Duplicate attribute \"{}\"", name),
+            ConversionError::DuplicateAttribute { path, name } => write!(f, "Duplicate attribute \"{}\" at JSON path \"{}\"", name, path),
+            ConversionError::InvalidCharacter { path, character } if path.is_empty() => write!(f, "Invalid XML character U+{:04X}", *character as u32),
+            ConversionError::InvalidCharacter { path, character } => write!(f, "Invalid XML character U+{:04X} at JSON path \"{}\"", *character as u32, path),
+            ConversionError::InvalidRename { key, name } => write!(f, "Cannot rename \"{}\" to \"{}\", which is not an XML name", key, name),
+            ConversionError::DuplicateColumn(header) => write!(f, "Duplicate CSV column \"{}\"", header),
         }
     }
 }
@@ -50,3 +109,37 @@ impl From<toml::de::Error> for ConversionError {
         ConversionError::Toml(err)
     }
 }
+
+impl From<toml::ser::Error> for ConversionError {
+    fn from(err: toml::ser::Error) -> ConversionError {
+        ConversionError::TomlSerialize(err)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl From<serde_yaml::Error> for ConversionError {
+    fn from(err: serde_yaml::Error) -> ConversionError {
+        ConversionError::Yaml(err)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for ConversionError {
+    fn from(err: csv::Error) -> ConversionError {
+        ConversionError::Csv(err)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl From<rmpv::decode::Error> for ConversionError {
+    fn from(err: rmpv::decode::Error) -> ConversionError {
+        ConversionError::MessagePack(err)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl From<ciborium::de::Error<std::io::Error>> for ConversionError {
+    fn from(err: ciborium::de::Error<std::io::Error>) -> ConversionError {
+        ConversionError::Cbor(err)
+    }
+}
@@ -0,0 +1,163 @@
+use crate::options::{BinaryEncoding, BooleanFormat, ConversionOptions, NumberFormat};
+
+use serde_json::{Number, Value};
+
+/// Format a string, number or boolean as text, as configured by `ConversionOptions`.
+///
+/// Returns `None` for `null`, objects and arrays.
+pub fn format_scalar(value: &Value, options: &ConversionOptions) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(num) => Some(format_number(num, &options.number_format)),
+        Value::Bool(b) => Some(format_boolean(*b, &options.boolean_format).to_string()),
+        _ => None,
+    }
+}
+
+/// Format a JSON boolean as configured by `BooleanFormat`.
+pub fn format_boolean(b: bool, format: &BooleanFormat) -> &'static str {
+    match (format, b) {
+        (BooleanFormat::TrueFalse, true) => "true",
+        (BooleanFormat::TrueFalse, false) => "false",
+        (BooleanFormat::OneZero, true) => "1",
+        (BooleanFormat::OneZero, false) => "0",
+        (BooleanFormat::YesNo, true) => "yes",
+        (BooleanFormat::YesNo, false) => "no",
+        (BooleanFormat::Capitalized, true) => "True",
+        (BooleanFormat::Capitalized, false) => "False",
+    }
+}
+
+/// Format a JSON number as configured by `NumberFormat`.
+///
+/// The output is locale-neutral: a `.` is always used as the decimal separator and digits are never grouped.
+/// With the default `NumberFormat` the number is written exactly as `Number::to_string` writes it.
+pub fn format_number(num: &Number, format: &NumberFormat) -> String {
+    let repr = num.to_string();
+
+    match format.decimal_places {
+        Some(places) => match to_plain_notation(&repr) {
+            plain if plain.contains(['e', 'E']) => plain,
+            plain => round_decimal(&plain, places),
+        },
+        None if format.plain_notation => to_plain_notation(&repr),
+        None => repr,
+    }
+}
+
+/// Encode binary data as text, as configured by `BinaryEncoding`.
+pub fn format_binary(bytes: &[u8], encoding: &BinaryEncoding) -> String {
+    match encoding {
+        BinaryEncoding::Base64 => to_base64(bytes),
+        BinaryEncoding::Hex => bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+    }
+}
+
+fn to_base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| group | (*byte as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(group >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// The furthest the decimal point is moved by `to_plain_notation`, well beyond the range of `f64`.
+const MAX_PLAIN_EXPONENT: u64 = 1000;
+
+/// Rewrite a number in exponent notation (e.g. `1e21`) as a plain decimal number.
+///
+/// The digits are moved around as text, so no precision is lost. Numbers without an exponent are returned unchanged,
+/// and so are numbers whose decimal point would move more than 1000 places, e.g. `1e999999999` with `arbitrary_precision`.
+pub fn to_plain_notation(repr: &str) -> String {
+    let Some(exponent_index) = repr.find(['e', 'E']) else {
+        return repr.to_string();
+    };
+
+    let (mantissa, exponent) = (&repr[..exponent_index], &repr[exponent_index + 1..]);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", mantissa),
+    };
+    let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{}{}", int_part, frac_part);
+
+    let point = match exponent.parse::<i64>() {
+        Ok(exponent) if exponent.unsigned_abs() <= MAX_PLAIN_EXPONENT => int_part.len() as i64 + exponent,
+        _ => return repr.to_string(),
+    };
+
+    let (int_digits, frac_digits) = if point <= 0 {
+        (String::new(), "0".repeat(point.unsigned_abs() as usize) + &digits)
+    } else if point as usize >= digits.len() {
+        (digits.clone() + &"0".repeat(point as usize - digits.len()), String::new())
+    } else {
+        (digits[..point as usize].to_string(), digits[point as usize..].to_string())
+    };
+
+    let int_digits = int_digits.trim_start_matches('0');
+    let int_digits = if int_digits.is_empty() { "0" } else { int_digits };
+    let frac_digits = frac_digits.trim_end_matches('0');
+
+    let plain = if frac_digits.is_empty() {
+        int_digits.to_string()
+    } else {
+        format!("{}.{}", int_digits, frac_digits)
+    };
+
+    if plain.chars().all(|c| c == '0' || c == '.') {
+        plain
+    } else {
+        format!("{}{}", sign, plain)
+    }
+}
+
+/// Round a plain decimal number to a fixed number of decimal places, rounding halves away from zero.
+fn round_decimal(plain: &str, places: usize) -> String {
+    let (sign, unsigned) = match plain.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", plain),
+    };
+    let (int_part, frac_part) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+
+    let mut digits: Vec<u8> = int_part.bytes().chain(frac_part.bytes()).chain(std::iter::repeat(b'0')).take(int_part.len() + places + 1).collect();
+    let round_up = digits.pop().is_some_and(|d| d >= b'5');
+
+    if round_up {
+        let mut carry = true;
+        for digit in digits.iter_mut().rev() {
+            if *digit == b'9' {
+                *digit = b'0';
+            } else {
+                *digit += 1;
+                carry = false;
+                break;
+            }
+        }
+        if carry {
+            digits.insert(0, b'1');
+        }
+    }
+
+    let int_len = digits.len() - places;
+    let int_digits = String::from_utf8_lossy(&digits[..int_len]).into_owned();
+    let frac_digits = String::from_utf8_lossy(&digits[int_len..]).into_owned();
+    let is_zero = digits.iter().all(|d| *d == b'0');
+    let sign = if is_zero { "" } else { sign };
+
+    if places == 0 {
+        format!("{}{}", sign, int_digits)
+    } else {
+        format!("{}{}.{}", sign, int_digits, frac_digits)
+    }
+}
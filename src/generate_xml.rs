@@ -1,11 +1,15 @@
 use crate::error::ConversionError;
-use crate::xml_utils::{write_declaration, write_comment, write_start_tag, write_empty_tag, write_end_tag, write_content};
+use crate::format::{format_boolean, format_number, format_scalar};
+use crate::options::{ArrayMarker, ConversionOptions, EmptyArray, NestedArray, RootPolicy, TopLevelArray, TypeAnnotation, XSI_NAMESPACE, XS_NAMESPACE};
+use crate::rules::{action_at, child_pointer, placement, renamed, rule_text, Placement, RuleAction, RuleText};
+use crate::document::{generated_comment, start_tag, write_nodes, Element, Namespace, Node};
+use crate::json_schema::{Hints, XmlHints};
+use crate::xml_utils::{is_name, write_declaration, write_end_tag, write_start_tag, Comment, ElementWriter};
 
 use quick_xml::Writer;
-use quick_xml::events::{BytesEnd, BytesStart};
+use quick_xml::events::BytesEnd;
 use serde_json::{Value, Map, from_str};
-use std::collections::HashMap;
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 use std::fs;
 use toml::de::from_str as toml_from_str;
 /// # Convert JSON to XML with a default "Root" element.
@@ -125,7 +129,7 @@ pub fn json_to_xml(json_string: &str) -> Result<String, ConversionError> {
 /// ```xml
 /// <?xml version="1.0" encoding="UTF-8"?>
 /// <!--Generated with json_to_xml 0.1.7-->
-/// <People xmlns:pr="http://standards.fi/schemas/personData/person" xmlns:addr="http://standards.fi/schemas/personData/addresses">
+/// <People xmlns:addr="http://standards.fi/schemas/personData/addresses" xmlns:pr="http://standards.fi/schemas/personData/person">
 ///   <Person id="1234">
 ///     <Addresses type="primary">
 ///       <City>Springfield</City>
@@ -143,7 +147,7 @@ pub fn json_to_xml(json_string: &str) -> Result<String, ConversionError> {
 ///
 /// ## Parameters:
 /// - `json_string`: The input JSON string to be converted into XML.
-/// - `root`: The name for the root element of the XML. It will become the root element of the XML if the JSON contains top-level @ attributes.
+/// - `root`: The name for the root element of the XML. It wraps the top-level keys unless the JSON holds exactly one element, and always when there are top-level @ attributes, `$text` or `$content`.
 ///
 /// ## Returns:
 /// A `Result` which is either a `String` containing the XML representation of the input JSON, or a `ConversionError` if parsing or conversion fails.
@@ -152,237 +156,930 @@ pub fn json_to_xml(json_string: &str) -> Result<String, ConversionError> {
 /// - This function works recursively to handle nested structures and arrays.
 /// - JSON keys starting with `@` are treated as attributes for the parent XML element.
 /// - All XML element tags are automatically capitalized.
-/// - Empty JSON objects (`{}`) are converted into self-closing tags (e.g., `<Tag/>`).
-/// - Empty JSON arrays (`[]`) are converted into an empty element (e.g., `<Tag></Tag>`).
+/// - Empty JSON objects (`{}`) and objects holding only attributes are converted into self-closing tags (e.g., `<Tag/>`, `<Tag id="1"/>`).
+/// - Empty JSON arrays (`[]`) are converted into an empty element (e.g., `<Tag></Tag>`). See `EmptyArray` for other representations.
 /// - `null` values in JSON are converted into a self-closing `<None/>` tag.
+/// - Arrays mixing objects with other values, and arrays inside arrays, write every item as a `<TagItem>` element.
+/// - Every start tag is closed by a matching end tag. Should the conversion ever produce unbalanced elements, it returns `ConversionError::Unbalanced` with the JSON Pointer of the value instead of broken XML.
+/// - An attribute can only be written once per element, so e.g. an `@type` key next to `TypeAnnotation::Attribute("type")` returns `ConversionError::DuplicateAttribute`.
+/// - Keys must give XML names once capitalized: a key like `"first name"`, `"1st"` or `""` returns `ConversionError::InvalidName` with the JSON Pointer of the value, and so does an invalid attribute name.
+/// - Strings holding characters XML does not allow, i.e. control characters other than tab, line feed and carriage return, U+FFFE and U+FFFF,
+///   return `ConversionError::InvalidCharacter` with the JSON Pointer of the value.
+/// - Top-level strings, numbers, booleans, `null` and arrays are wrapped in the root element. See `TopLevelArray` for the names of array items.
+/// - `$text` is the text of its element and may be a string, number, boolean or `null` (written as `<None/>`), e.g. `{"@unit": "kg", "$text": 42}` becomes `<Weight unit="kg">42</Weight>`.
+/// - `$content` holds mixed content: an array of strings and objects written as text and elements in order, e.g. `"$content": ["Hello ", {"b": "world"}]` becomes `Hello <B>world</B>`. Mixed content is not indented.
+///   A single string, number, boolean or object is written like an array holding only it, e.g. `{"$content": 7}` becomes `7`.
 pub fn json_to_xml_with_root(json_string: &str, root: &str) -> Result<String, ConversionError> {
-    let json_value: Value = from_str(&json_string)?;
-    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    json_to_xml_with_options(json_string, root, &ConversionOptions::default())
+}
+
+/// # Convert JSON to XML with a custom root element and conversion options.
+///
+/// Works like `json_to_xml_with_root`, but the output can be adjusted with `ConversionOptions`.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::generate_xml::json_to_xml_with_options;
+/// use json_to_xml::options::{ConversionOptions, TypeAnnotation};
+///
+/// let json_string = r#"
+/// {
+///     "person": {
+///         "name": "John Doe",
+///         "age": 30,
+///         "active": true
+///     }
+/// }
+/// "#;
+///
+/// let options = ConversionOptions {
+///     type_annotation: TypeAnnotation::XsiType,
+///     ..Default::default()
+/// };
+///
+/// let xml_string = json_to_xml_with_options(&json_string, "People", &options).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <People xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+///   <Person>
+///     <Active xsi:type="xs:boolean">true</Active>
+///     <Age xsi:type="xs:integer">30</Age>
+///     <Name xsi:type="xs:string">John Doe</Name>
+///   </Person>
+/// </People>
+/// ```
+pub fn json_to_xml_with_options(json_string: &str, root: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let json_value: Value = from_str(json_string)?;
+
+    value_to_xml(json_value, root, options)
+}
 
-    write_declaration(&mut writer, "1.0", Some("UTF-8"))?;
-    
-    let mut version = get_dependency_version("Cargo.toml")?;
-    version = format!("Generated with json_to_xml {}", version);
-    write_comment(&mut writer, &version)?;
-    
-    create_xml_element(&json_value, &mut writer, root)?;
+/// Convert an already parsed value tree to XML, used by the other input formats.
+pub(crate) fn value_to_xml(json_value: Value, root: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    value_to_xml_with_comments(json_value, root, options, Vec::new())
+}
+
+/// Convert a value tree to XML, writing the comments next to the values at their paths.
+pub(crate) fn value_to_xml_with_comments(json_value: Value, root: &str, options: &ConversionOptions, comments: Vec<Comment>) -> Result<String, ConversionError> {
+    let nodes = value_to_nodes(json_value, root, options, comments, &Hints::default())?;
+    write_document(&nodes, options)
+}
+
+/// Write the nodes of a document as XML, after the XML declaration unless `RootPolicy::Fragment`.
+pub(crate) fn write_document(nodes: &[Node], options: &ConversionOptions) -> Result<String, ConversionError> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
 
-    if has_top_level_attributes(&json_value) {
-        write_end_tag(&mut writer, &BytesEnd::new(root))?;
+    if options.root_policy != RootPolicy::Fragment {
+        write_declaration(&mut writer, "1.0", Some("UTF-8"))?;
     }
+    write_nodes(&mut writer, nodes)?;
 
     Ok(String::from_utf8(writer.into_inner().into_inner())?)
 }
 
+/// Build the document nodes for a value tree: the "Generated with" comment, unless `RootPolicy::Fragment`, and the elements
+/// with the comments. This is the one traversal behind `value_to_xml`, `Document::from_value` and `json_to_xml_with_json_schema`,
+/// which passes the `xml` objects of its JSON Schema as `hints`.
+pub(crate) fn value_to_nodes(
+    mut json_value: Value,
+    root: &str,
+    options: &ConversionOptions,
+    comments: Vec<Comment>,
+    hints: &Hints
+) -> Result<Vec<Node>, ConversionError> {
+    prepare_root(&mut json_value, options)?;
+
+    let root = match hints.at("") {
+        Some(root_hints) => root_hints.qualify(&root_hints.name.clone().unwrap_or_else(|| capitalize_word(root))),
+        None => capitalize_word(root),
+    };
+    let mut elements = ElementWriter::with_comments(comments)?;
+
+    match &json_value {
+        Value::Object(map) if wraps_in_root(map, options, hints)? => write_value_element(&mut elements, &root, &json_value, options, hints)?,
+        Value::Object(map) => handle_object(&mut elements, map, options, hints)?,
+        value => write_top_level_value(&mut elements, &root, value, options, hints)?,
+    }
+
+    let mut nodes = Vec::new();
+    if options.root_policy != RootPolicy::Fragment {
+        nodes.push(generated_comment());
+    }
+    nodes.extend(elements.finish()?);
+    Ok(nodes)
+}
+
+/// Check `ConversionOptions::rename`, and declare the namespaces the options need as top-level attributes of an object,
+/// so they end up on the root element. Top-level scalars and arrays get them from `root_namespaces`.
+pub(crate) fn prepare_root(json_value: &mut Value, options: &ConversionOptions) -> Result<(), ConversionError> {
+    check_renames(options)?;
+
+    if options.type_annotation == TypeAnnotation::XsiType {
+        declare_xsi_namespaces(json_value);
+    }
+    if let Some(marker) = &options.array_marker {
+        declare_array_namespace(json_value, marker);
+    }
+    Ok(())
+}
+
+/// Write a top-level scalar or array inside the root element, so the document has exactly one root.
+pub(crate) fn write_top_level_value(
+    elements: &mut ElementWriter,
+    root: &str,
+    value: &Value,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    let mut element = root_element(root, options);
+    if let Some(root_hints) = value_hints(elements, hints) {
+        declare_namespace(elements, &mut element, root_hints);
+    }
+
+    match value {
+        Value::Array(items) if !has_content(elements, value, options, hints) => {
+            push_array_marker(&mut element, options);
+            if let (true, EmptyArray::Marked(marker)) = (items.is_empty(), &options.empty_array) {
+                element.push_attribute(marker.as_str(), "true");
+            }
+            elements.empty(element)?;
+        },
+        Value::Array(items) => {
+            push_array_marker(&mut element, options);
+            elements.start(element)?;
+            write_items(elements, &top_level_item_name(root, items, options), items, 0, options, hints)?;
+            elements.end(root)?;
+        },
+        value => {
+            push_type_annotation(&mut element, value, options);
+            write_element(elements, root, element, value, options, hints)?;
+        },
+    }
+    Ok(())
+}
+
+/// The namespace declarations a root element needs for the options: `xsi` and `xs` for `TypeAnnotation::XsiType`,
+/// and the namespace of the `ArrayMarker`. Used where the root element is not written for a top-level object.
+pub(crate) fn root_namespaces(options: &ConversionOptions) -> Vec<(String, &str)> {
+    let mut namespaces = Vec::new();
+    if options.type_annotation == TypeAnnotation::XsiType {
+        namespaces.push(("xmlns:xsi".to_string(), XSI_NAMESPACE));
+        namespaces.push(("xmlns:xs".to_string(), XS_NAMESPACE));
+    }
+    if let Some(marker) = &options.array_marker {
+        namespaces.push((marker.namespace_attribute(), marker.namespace.as_str()));
+    }
+    namespaces
+}
+
+/// The start tag of a root element holding a top-level scalar or array, declaring the `root_namespaces`.
+pub(crate) fn root_element(root: &str, options: &ConversionOptions) -> Element {
+    let mut element = Element::new(root);
+
+    for (name, namespace) in root_namespaces(options) {
+        element.push_attribute(&name, namespace);
+    }
+    element
+}
+
+/// The root element around the records of multi-document input, such as NDJSON lines and CSV rows, as configured by `RootPolicy`:
+/// - `WhenNeeded` and `Always` write every record inside the root element, as the number of records is not known up front.
+/// - `Error` writes the records without a root element, and `finish` returns `ConversionError::RootCount` unless there was exactly one.
+/// - `Fragment` also leaves out the XML declaration and the "Generated with" comment.
+///
+/// Without a root element, each record element declares the `root_namespaces` itself.
+pub(crate) struct RecordRoot {
+    /// The name of the root element, when the records are wrapped.
+    root: Option<String>,
+    record: String,
+    /// The number of top-level elements written for the records.
+    elements: usize,
+}
+
+impl RecordRoot {
+    /// Check the root and record names and write the start of the document.
+    /// A name that is not an XML name returns `ConversionError::InvalidName`.
+    pub(crate) fn start<W: Write>(writer: &mut Writer<W>, root: &str, record: &str, options: &ConversionOptions) -> Result<Self, ConversionError> {
+        check_renames(options)?;
+
+        let root = capitalize_word(root);
+        for name in [&root, &capitalize_word(record)] {
+            if !is_name(name) {
+                return Err(ConversionError::InvalidName { path: String::new(), name: name.to_string() });
+            }
+        }
+
+        if options.root_policy != RootPolicy::Fragment {
+            write_header(writer)?;
+        }
+
+        let wraps = matches!(options.root_policy, RootPolicy::WhenNeeded | RootPolicy::Always);
+        if wraps {
+            write_start_tag(writer, &start_tag(&root_element(&root, options)))?;
+        }
+
+        Ok(RecordRoot { root: wraps.then_some(root), record: record.to_string(), elements: 0 })
+    }
+
+    /// Write one record with the conventions of `json_to_xml_with_root`.
+    pub(crate) fn write_record<W: Write>(&mut self, writer: &mut Writer<W>, value: &Value, options: &ConversionOptions) -> Result<(), ConversionError> {
+        let mut elements = ElementWriter::new();
+        write_child_element(&mut elements, &self.record, value, options, &Hints::default())?;
+        let mut nodes = elements.finish()?;
+
+        for node in &mut nodes {
+            if let Node::Element(element) = node {
+                if self.root.is_none() {
+                    for (name, namespace) in root_namespaces(options) {
+                        if !element.namespaces.iter().any(|declared| declared.attribute_name() == name) {
+                            element.push_attribute(&name, namespace);
+                        }
+                    }
+                }
+                self.elements += 1;
+            }
+        }
+        write_nodes(writer, &nodes)
+    }
+
+    /// Write the end of the document.
+    pub(crate) fn finish<W: Write>(self, writer: &mut Writer<W>, options: &ConversionOptions) -> Result<(), ConversionError> {
+        match &self.root {
+            Some(root) => write_end_tag(writer, &BytesEnd::new(root.as_str())),
+            None if options.root_policy == RootPolicy::Error && self.elements != 1 => Err(ConversionError::RootCount(self.elements)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Whether the top-level object is wrapped in the root element: always when it has attributes or text, or its JSON Schema
+/// has an `xml` object, and otherwise as configured by `RootPolicy`.
+pub(crate) fn wraps_in_root(map: &Map<String, Value>, options: &ConversionOptions, hints: &Hints) -> Result<bool, ConversionError> {
+    if hints.at("").is_some_and(|root_hints| !root_hints.is_empty()) {
+        return Ok(true);
+    }
+    if map.keys().any(|key| key.starts_with('@') || key == "$text" || key == "$content") {
+        return Ok(true);
+    }
+    if map.iter().any(|(key, value)| matches!(key_placement("", key, value, options, hints), Placement::Attribute | Placement::Text)) {
+        return Ok(true);
+    }
+
+    wraps_elements(map.iter().map(|(key, value)| element_count("", key, value, options, hints)).sum(), options)
+}
+
+/// Whether `count` top-level elements are wrapped in the root element, as configured by `RootPolicy`.
+pub(crate) fn wraps_elements(count: usize, options: &ConversionOptions) -> Result<bool, ConversionError> {
+    match options.root_policy {
+        RootPolicy::WhenNeeded => Ok(count != 1),
+        RootPolicy::Always => Ok(true),
+        RootPolicy::Error if count != 1 => Err(ConversionError::RootCount(count)),
+        RootPolicy::Error | RootPolicy::Fragment => Ok(false),
+    }
+}
+
+/// The number of elements written for a key of the object at `path`.
+/// Arrays of objects give one element per non-empty object, arrays repeated by their JSON Schema one per item,
+/// and omitted empty arrays, attributes and keys skipped by rules none.
+pub(crate) fn element_count(path: &str, key: &str, value: &Value, options: &ConversionOptions, hints: &Hints) -> usize {
+    if key_placement(path, key, value, options, hints) != Placement::Element {
+        return 0;
+    }
+
+    let array_hints = if hints.is_empty() { None } else { hints.at(&child_pointer(path, key)) };
+
+    match value.as_array() {
+        Some(items) if array_hints.is_some_and(|array_hints| !array_hints.is_wrapped()) => items.len(),
+        Some(items) if items.is_empty() => usize::from(options.empty_array != EmptyArray::Omit),
+        Some(items) if array_hints.is_none() && items.iter().all(Value::is_object) => {
+            items.iter().filter(|item| item.as_object().is_some_and(|map| !map.is_empty())).count()
+        },
+        _ => 1,
+    }
+}
+
+/// The element name for the items of a top-level array, as configured by `TopLevelArray`.
+pub(crate) fn top_level_item_name(root: &str, items: &[Value], options: &ConversionOptions) -> String {
+    with_prefix(root, |root| match &options.top_level_array {
+        TopLevelArray::RootItem => item_name(root, items, 0, options),
+        TopLevelArray::Named(name) => capitalize_word(name),
+    })
+}
+
+/// The element name for the items of an array inside `level` other arrays, as configured by `NestedArray`.
+pub(crate) fn item_name(parent_tag: &str, items: &[Value], level: usize, options: &ConversionOptions) -> String {
+    with_prefix(parent_tag, |parent_tag| match &options.nested_array {
+        NestedArray::Named(names) if level > 0 || items.iter().any(Value::is_array) => match names.get(level) {
+            Some(name) => capitalize_word(name),
+            None => format!("{}Item", capitalize_word(parent_tag)),
+        },
+        _ => format!("{}Item", capitalize_word(parent_tag)),
+    })
+}
+
+/// Apply `local_name` to the local part of a qualified name, keeping its namespace prefix, e.g. for the items of `pr:Tags`.
+fn with_prefix(name: &str, local_name: impl FnOnce(&str) -> String) -> String {
+    match name.split_once(':') {
+        Some((prefix, local)) => format!("{}:{}", prefix, local_name(local)),
+        None => local_name(name),
+    }
+}
+
+/// Write the XML declaration and the "Generated with" comment
+pub(crate) fn write_header<W: Write>(writer: &mut Writer<W>) -> Result<(), ConversionError> {
+    write_declaration(writer, "1.0", Some("UTF-8"))?;
+    write_nodes(writer, &[generated_comment()])
+}
+
 /// Helper function to get json_to_xml version from the Cargo.toml file
 pub fn get_dependency_version(file_path: &str) -> Result<String, ConversionError> {
     let content = fs::read_to_string(file_path)?;
     let toml: Value = toml_from_str(&content)?;
 
-    if let Some(deps) = toml.get("dependencies") {
-        if let Some(dep) = deps.get("json_to_xml") {
-            if dep.is_object() {
-                if let Some(version) = dep.get("version").and_then(|v| v.as_str()) {
-                    return Ok(version.to_string());
-                }
-            } else if let Some(version) = dep.as_str() {
+    if let Some(dep) = toml.get("dependencies").and_then(|deps| deps.get("json_to_xml")) {
+        if dep.is_object() {
+            if let Some(version) = dep.get("version").and_then(|v| v.as_str()) {
                 return Ok(version.to_string());
             }
+        } else if let Some(version) = dep.as_str() {
+            return Ok(version.to_string());
         }
     }
 
     // If not found in dependencies, try the package section
-    if let Some(pkg) = toml.get("package") {
-        if let Some(version) = pkg.get("version").and_then(|v| v.as_str()) {
-            return Ok(version.to_string());
-        }
+    if let Some(version) = toml.get("package").and_then(|pkg| pkg.get("version")).and_then(|v| v.as_str()) {
+        return Ok(version.to_string());
     }
 
     Err(ConversionError::Toml(toml_from_str::<Value>("").unwrap_err()))
 }
 
-fn create_xml_element(
-    json_data: &Value, 
-    writer: &mut Writer<Cursor<Vec<u8>>>, 
-    parent_tag: &str
+/// Write the content of the element for a value, without its start and end tags.
+fn write_content_of(
+    elements: &mut ElementWriter,
+    json_data: &Value,
+    parent_tag: &str,
+    options: &ConversionOptions,
+    hints: &Hints
 ) -> Result<(), ConversionError> {
     match json_data {
         Value::Object(map) => {
-            handle_object(writer, map, parent_tag)?;
+            handle_object(elements, map, options, hints)?;
         },
         Value::Array(arr) => {
-            handle_array(writer, arr, parent_tag)?;
+            write_items(elements, &items_tag(elements, parent_tag, arr, options, hints), arr, 0, options, hints)?;
         },
         Value::String(s) => {
-            write_content(writer, s)?;
+            elements.text(s)?;
         },
         Value::Number(num) => {
-            write_content(writer, &num.to_string())?;
+            elements.text(&format_number(num, &options.number_format))?;
         }
         Value::Bool(b) => {
-            write_content(writer, &b.to_string())?;
+            elements.text(format_boolean(*b, &options.boolean_format))?;
         }
         Value::Null => {
-            write_empty_tag(writer, &BytesStart::new("None"))?;
+            elements.empty(Element::new("None"))?;
         }
     }
     Ok(())
 }
 
+/// Write the text, mixed content and child elements of an object. Its attributes are written by `element_start`.
 fn handle_object(
-    writer: &mut Writer<Cursor<Vec<u8>>>, 
-    map: &Map<String, Value>, 
-    parent_tag: &str
+    elements: &mut ElementWriter,
+    map: &Map<String, Value>,
+    options: &ConversionOptions,
+    hints: &Hints
 ) -> Result<(), ConversionError> {
-    let parent_tag = capitalize_word(parent_tag);
-    let mut element = BytesStart::new(parent_tag.clone());
-
-    let attributes: HashMap<_, _> = map
-        .iter()
-        .filter(|(key, _)| key.starts_with("@"))
-        .map(|(key, value)| (&key[1..], value))
-        .collect();
-
-    for (key, value) in &attributes {
-        if let Some(value_str) = value.as_str() {
-            element.push_attribute((*key, value_str));
-        }
+    if let Some(text) = map.get("$text") {
+        elements.enter("$text");
+        write_text(elements, text, options)?;
+        elements.leave();
     }
 
-    if !attributes.is_empty() {
-        write_start_tag(writer, &element)?;
+    if let Some(content) = map.get("$content") {
+        elements.enter("$content");
+        write_mixed_content(elements, content, options, hints)?;
+        elements.leave();
     }
 
-    if let Some(text_content) = map.get("$text").and_then(|v| v.as_str()) {
-        write_content(writer, text_content)?;
+    for (key, value) in map {
+        write_child_element(elements, key, value, options, hints)?;
     }
+    Ok(())
+}
 
-    for (key, value) in map {
-        let key_tag = capitalize_word(key);
-        element = BytesStart::new(key_tag.clone());
+/// Write the element for one key of an object.
+/// The `xml` object of its JSON Schema decides where it goes and how it is named, and otherwise the rules do.
+pub(crate) fn write_child_element(
+    elements: &mut ElementWriter,
+    key: &str,
+    value: &Value,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    elements.enter(key);
 
-        if value.as_object().map_or(false, |m| m.is_empty()) {
-            write_empty_tag(writer, &element)?;
-            continue;
+    let key_hints = value_hints(elements, hints);
+    let placement = if key.starts_with('@') || key == "$text" || key == "$content" {
+        // Written by `element_start` and `handle_object`. The key is still entered for the comments at its path.
+        Placement::Skipped
+    } else {
+        match key_hints {
+            Some(key_hints) if key_hints.attribute => Placement::Attribute,
+            Some(_) => Placement::Element,
+            None => rule_placement(elements, value, options),
         }
+    };
 
-        if key.starts_with("@") || key == "$text" {
-            continue;
-        } else {
-            if !(is_attribute_key(value) || is_array_with_attribute_key(value)) {
-                write_start_tag(writer, &element)?;
+    match placement {
+        // Attributes are written with the start tag of the parent, by `element_start`.
+        Placement::Skipped | Placement::Attribute => {},
+        Placement::Text => write_text(elements, value, options)?,
+        Placement::Element => {
+            let key_tag = match key_hints {
+                Some(key_hints) => match &key_hints.name {
+                    Some(name) => key_hints.qualify(name),
+                    None => key_hints.qualify(&rule_name(elements, &key_tag(elements, key, options), options)),
+                },
+                None => rule_name(elements, &key_tag(elements, key, options), options),
+            };
+            match value {
+                Value::Array(arr) => handle_array(elements, arr, &key_tag, options, hints)?,
+                value => write_value_element(elements, &key_tag, value, options, hints)?,
             }
+        },
+    }
 
-            create_xml_element(value, writer, key)?;
-            
-            if !value.is_array() {
-                write_end_tag(writer, &BytesEnd::new(key_tag))?;
-            }
+    elements.leave();
+    Ok(())
+}
+
+/// Where the value being written ends up, as configured by `ConversionOptions::rules`.
+pub(crate) fn rule_placement(elements: &ElementWriter, value: &Value, options: &ConversionOptions) -> Placement {
+    if options.rules.is_empty() {
+        return Placement::Element;
+    }
+    placement(&options.rules, &elements.path(), value)
+}
+
+/// Where the value of a key of the object at `path` ends up, as configured by the `xml` object of its JSON Schema or `ConversionOptions::rules`.
+pub(crate) fn key_placement(path: &str, key: &str, value: &Value, options: &ConversionOptions, hints: &Hints) -> Placement {
+    if key.starts_with('@') || key == "$text" || key == "$content" {
+        return Placement::Element;
+    }
+    if let Some(key_hints) = hints.at(&child_pointer(path, key)).filter(|key_hints| !key_hints.is_empty()) {
+        return if key_hints.attribute { Placement::Attribute } else { Placement::Element };
+    }
+    if options.rules.is_empty() {
+        return Placement::Element;
+    }
+    placement(&options.rules, &child_pointer(path, key), value)
+}
+
+/// The `xml` object of the JSON Schema of the value being written, if it says anything about the value itself.
+fn value_hints<'a>(elements: &ElementWriter, hints: &'a Hints) -> Option<&'a XmlHints> {
+    if hints.is_empty() {
+        return None;
+    }
+    hints.at(&elements.path()).filter(|value_hints| !value_hints.is_empty())
+}
+
+/// Declare the namespace of an `xml` object on the element, unless the same declaration is already in scope.
+fn declare_namespace(elements: &ElementWriter, element: &mut Element, hints: &XmlHints) {
+    if let Some(namespace) = hints.declaration()
+        && !element.namespaces.contains(&namespace)
+        && !elements.in_scope(&namespace)
+    {
+        element.namespaces.push(namespace);
+    }
+}
+
+/// The element name for the key being written: the key renamed by `ConversionOptions::rename`, capitalized.
+pub(crate) fn key_tag(elements: &ElementWriter, key: &str, options: &ConversionOptions) -> String {
+    if options.rename.is_empty() {
+        return capitalize_word(key);
+    }
+    capitalize_word(renamed_key(&elements.path(), key, options))
+}
+
+/// Check that `ConversionOptions::rename` only gives XML names. `@` keys may be renamed with or without the `@`.
+pub(crate) fn check_renames(options: &ConversionOptions) -> Result<(), ConversionError> {
+    match options.rename.iter().find(|(_, name)| !is_name(name.strip_prefix('@').unwrap_or(name))) {
+        Some((key, name)) => Err(ConversionError::InvalidRename { key: key.clone(), name: name.clone() }),
+        None => Ok(()),
+    }
+}
+
+/// The key at `pointer` as renamed by `ConversionOptions::rename`: by its path first, then by the key alone.
+pub(crate) fn renamed_key<'a>(pointer: &str, key: &'a str, options: &'a ConversionOptions) -> &'a str {
+    options.rename.get(pointer).or_else(|| options.rename.get(key)).map_or(key, String::as_str)
+}
+
+/// The element name for the value being written: the name given by a `RuleAction::Rename` rule, or `name`.
+pub(crate) fn rule_name(elements: &ElementWriter, name: &str, options: &ConversionOptions) -> String {
+    if options.rules.is_empty() {
+        return name.to_string();
+    }
+    renamed(&options.rules, &elements.path()).unwrap_or(name).to_string()
+}
+
+/// The text written instead of the value being written, as configured by a `RuleAction::CData` or `RuleAction::Format` rule.
+pub(crate) fn replacement_text(elements: &ElementWriter, value: &Value, options: &ConversionOptions) -> Option<RuleText> {
+    if options.rules.is_empty() {
+        return None;
+    }
+    rule_text(&elements.path(), value, options)
+}
+
+/// Write one element holding a value.
+/// Values without content, like `{}` or `{"@id": 1}`, become self-closing elements, everything else is written between start and end tags.
+fn write_value_element(
+    elements: &mut ElementWriter,
+    name: &str,
+    value: &Value,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    write_element(elements, name, element_start(elements, name, value, options, hints), value, options, hints)
+}
+
+/// Write one element named `name` holding a value at the current path, declaring `namespaces` on it.
+/// Used for the `xs:anyType` elements of `json_to_xml_with_schema`, which follow the conventions of `json_to_xml_with_root`.
+pub(crate) fn write_declared_element(
+    elements: &mut ElementWriter,
+    name: &str,
+    namespaces: Vec<Namespace>,
+    value: &Value,
+    options: &ConversionOptions
+) -> Result<(), ConversionError> {
+    let hints = Hints::default();
+    let mut element = element_start(elements, name, value, options, &hints);
+    element.namespaces.extend(namespaces);
+    write_element(elements, name, element, value, options, &hints)
+}
+
+/// Write one element holding a value, starting with the given start tag.
+fn write_element(
+    elements: &mut ElementWriter,
+    name: &str,
+    element: Element,
+    value: &Value,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    if let Some(text) = replacement_text(elements, value, options) {
+        elements.start(element)?;
+        match text {
+            RuleText::Text(text) => elements.text(&text)?,
+            RuleText::CData(text) => elements.cdata(&text)?,
         }
+        return elements.end(name);
     }
-    Ok(())
+
+    if value.as_array().is_some_and(Vec::is_empty) {
+        return write_empty_array(elements, name, element, options);
+    }
+
+    if !has_content(elements, value, options, hints) {
+        return elements.empty(element);
+    }
+
+    elements.start(element)?;
+    write_content_of(elements, value, name, options, hints)?;
+    elements.end(name)
 }
 
-fn handle_array(
-    writer: &mut Writer<Cursor<Vec<u8>>>, 
-    arr: &Vec<Value>, 
-    parent_tag: &str
+/// Whether the element for the value being written has any content: text, or child elements that are not left out.
+fn has_content(elements: &ElementWriter, value: &Value, options: &ConversionOptions, hints: &Hints) -> bool {
+    let path = if options.rules.is_empty() && hints.is_empty() { String::new() } else { elements.path() };
+
+    match value {
+        Value::Object(map) => map.iter().any(|(key, value)| match key.as_str() {
+            "$text" | "$content" => true,
+            key if key.starts_with('@') => false,
+            key => key_placement(&path, key, value, options, hints) == Placement::Text || element_count(&path, key, value, options, hints) > 0,
+        }),
+        Value::Array(items) => items.iter().enumerate().any(|(index, item)| {
+            (!item.as_array().is_some_and(Vec::is_empty) || options.empty_array != EmptyArray::Omit)
+                && (options.rules.is_empty() || placement(&options.rules, &child_pointer(&path, &index.to_string()), item) != Placement::Skipped)
+        }),
+        _ => true,
+    }
+}
+
+/// Write an empty array as configured by `EmptyArray`.
+pub(crate) fn write_empty_array(
+    elements: &mut ElementWriter,
+    name: &str,
+    mut element: Element,
+    options: &ConversionOptions
 ) -> Result<(), ConversionError> {
-    let mut parent_tag = capitalize_word(parent_tag);
-    let original_tag = parent_tag.clone();
-    let item_tag = parent_tag.clone() + "Item";
-    let mixed_array = contains_objects_and_primitives(arr);
+    match &options.empty_array {
+        EmptyArray::Element => {
+            elements.start(element)?;
+            // An empty text event keeps the end tag on the same line.
+            elements.text("")?;
+            elements.end(name)
+        },
+        EmptyArray::Omit => Ok(()),
+        EmptyArray::SelfClosing => elements.empty(element),
+        EmptyArray::Marked(marker) => {
+            element.push_attribute(marker.as_str(), "true");
+            elements.empty(element)
+        },
+    }
+}
 
-    if arr.is_empty() {
-        write_end_tag(writer, &BytesEnd::new(&parent_tag))?;
-        return Ok(());
+/// The start tag for the value being written, with the attributes of an object, the type annotation of a leaf value and the marker of an array.
+/// A JSON Schema can name the element and declare its namespace, and that of the attributes it names.
+fn element_start(elements: &ElementWriter, name: &str, value: &Value, options: &ConversionOptions, hints: &Hints) -> Element {
+    let mut element = Element::new(name);
+    if let Some(value_hints) = value_hints(elements, hints) {
+        declare_namespace(elements, &mut element, value_hints);
     }
 
-    for (i, value) in arr.iter().enumerate() {
-        if value.is_object() {
-            if i == 0 && mixed_array {
-                parent_tag = item_tag.clone(); 
-                write_start_tag(writer, &BytesStart::new(&parent_tag))?;
+    match value {
+        Value::Object(map) => {
+            let path = if options.rules.is_empty() && options.rename.is_empty() && hints.is_empty() { String::new() } else { elements.path() };
+            for (key, value) in map {
+                let attribute = match hints.at(&child_pointer(&path, key)).filter(|key_hints| !key_hints.is_empty()) {
+                    Some(key_hints) => {
+                        let attribute = hinted_attribute(&path, key, value, key_hints, options);
+                        if attribute.is_some() {
+                            declare_namespace(elements, &mut element, key_hints);
+                        }
+                        attribute
+                    },
+                    None => object_attribute(&path, key, value, options),
+                };
+                if let Some((name, text)) = attribute {
+                    element.push_attribute(&name, text);
+                }
             }
-            
-            handle_object_array(writer, i, value, &parent_tag)?;
-        } else {
-            if parent_tag != item_tag { 
-                parent_tag = item_tag.clone(); 
+            if let Some(text) = text_only_value(map) {
+                push_type_annotation(&mut element, text, options);
             }
+        },
+        Value::Array(_) => push_array_marker(&mut element, options),
+        value => push_type_annotation(&mut element, value, options),
+    }
+    element
+}
+
+/// The attribute name and value written for one key of the object at `path`, if it is written as an attribute:
+/// `@` keys and the keys placed as attributes by rules. Rules can also rename, format or skip `@` keys, matched by paths
+/// like `/order/@id`, and `ConversionOptions::rename` renames them.
+pub(crate) fn object_attribute(path: &str, key: &str, value: &Value, options: &ConversionOptions) -> Option<(String, String)> {
+    if options.rules.is_empty() && options.rename.is_empty() {
+        return Some((key.strip_prefix('@')?.to_string(), format_scalar(value, options)?));
+    }
+
+    let pointer = child_pointer(path, key);
+    if !key.starts_with('@') && placement(&options.rules, &pointer, value) != Placement::Attribute {
+        return None;
+    }
+    let text = match action_at(&options.rules, &pointer) {
+        Some(RuleAction::Skip) => return None,
+        Some(RuleAction::Format(format)) => format(value),
+        _ => format_scalar(value, options)?,
+    };
+    let name = match renamed(&options.rules, &pointer) {
+        Some(name) => name,
+        None => {
+            let key = renamed_key(&pointer, key, options);
+            key.strip_prefix('@').unwrap_or(key)
+        },
+    };
+    Some((name.to_string(), text))
+}
+
+/// The attribute for a key whose JSON Schema has an `xml` object: `@` keys and keys with `xml.attribute`,
+/// named by `xml.name` or the key renamed by `ConversionOptions::rename`. Rules do not apply.
+fn hinted_attribute(path: &str, key: &str, value: &Value, key_hints: &XmlHints, options: &ConversionOptions) -> Option<(String, String)> {
+    if !key.starts_with('@') && !key_hints.attribute {
+        return None;
+    }
 
-            write_start_tag(writer, &BytesStart::new(&parent_tag))?;
-            create_xml_element(value, writer, &original_tag)?;
-            write_end_tag(writer, &BytesEnd::new(&parent_tag))?;
+    let text = format_scalar(value, options)?;
+    let name = match &key_hints.name {
+        Some(name) => name.as_str(),
+        None => {
+            let key = renamed_key(&child_pointer(path, key), key, options);
+            key.strip_prefix('@').unwrap_or(key)
+        },
+    };
+    Some((key_hints.qualify(name), text))
+}
 
-            if i == arr.len() - 1 {
-                write_end_tag(writer, &BytesEnd::new(&original_tag))?;
+/// Marks an element written for an array, as configured by `ArrayMarker`.
+pub(crate) fn push_array_marker(element: &mut Element, options: &ConversionOptions) {
+    if let Some(marker) = &options.array_marker {
+        element.push_attribute(marker.attribute_name().as_str(), "true");
+    }
+}
+
+/// Write the `$text` of an object: strings, numbers and booleans as text formatted by `ConversionOptions`, `null` as `<None/>`.
+pub(crate) fn write_text(
+    elements: &mut ElementWriter,
+    text: &Value,
+    options: &ConversionOptions
+) -> Result<(), ConversionError> {
+    match text {
+        Value::Null => elements.empty(Element::new("None"))?,
+        text => {
+            if let Some(text) = format_scalar(text, options) {
+                elements.text(&text)?;
             }
-        }
+        },
+    }
+    Ok(())
+}
 
-        if i == arr.len() - 1 && value.is_object() && mixed_array {
-            write_end_tag(writer, &BytesEnd::new(&original_tag))?;
-        }
+/// The `$text` of an object holding nothing but text and attributes. Its type is the type of the element.
+pub(crate) fn text_only_value(map: &Map<String, Value>) -> Option<&Value> {
+    if map.keys().all(|key| key.starts_with('@') || key == "$text") {
+        map.get("$text")
+    } else {
+        None
+    }
+}
+
+/// Write `$content`: strings and other scalars as text, objects as elements and the items of arrays in order.
+pub(crate) fn write_mixed_content(
+    elements: &mut ElementWriter,
+    content: &Value,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    match content {
+        Value::Object(map) => {
+            for (key, value) in map {
+                write_child_element(elements, key, value, options, hints)?;
+            }
+        },
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                elements.enter(&index.to_string());
+                write_mixed_content(elements, item, options, hints)?;
+                elements.leave();
+            }
+        },
+        Value::Null => elements.empty(Element::new("None"))?,
+        item => {
+            if let Some(text) = format_scalar(item, options) {
+                elements.text(&text)?;
+            }
+        },
     }
     Ok(())
 }
 
-fn handle_object_array(
-    writer: &mut Writer<Cursor<Vec<u8>>>, 
-    index: usize, 
-    value: &Value, 
-    parent_tag: &str
+/// Write the value of an array key.
+/// Arrays of objects become repeated elements, leaving out empty objects. Each of them carries the array marker.
+/// All other arrays become one element holding the items as `<TagItem>` elements.
+/// An `xml` object in the JSON Schema of the array or its items repeats every item instead, unless it is `wrapped`.
+fn handle_array(
+    elements: &mut ElementWriter,
+    arr: &[Value],
+    tag: &str,
+    options: &ConversionOptions,
+    hints: &Hints
 ) -> Result<(), ConversionError> {
-    if let Some(obj) = value.as_object() {
-        if !obj.is_empty() {
-            let first_key = obj.keys().next().unwrap();
-            
-            if !first_key.starts_with("@") && index > 0 {
-                write_start_tag(writer, &BytesStart::new(parent_tag))?;
-            } 
-
-            create_xml_element(value, writer, &parent_tag)?;
-            write_end_tag(writer, &BytesEnd::new(parent_tag))?;
+    let array_hints = if hints.is_empty() { None } else { hints.at(&elements.path()) };
+    let repeated = array_hints.is_some_and(|array_hints| !array_hints.is_wrapped());
+
+    if array_hints.is_some_and(XmlHints::is_wrapped) || (!repeated && (arr.is_empty() || !arr.iter().all(Value::is_object))) {
+        return write_value_element(elements, tag, &Value::Array(arr.to_vec()), options, hints);
+    }
+
+    for (index, value) in arr.iter().enumerate() {
+        if repeated || value.as_object().is_some_and(|obj| !obj.is_empty()) {
+            elements.enter(&index.to_string());
+            if rule_placement(elements, value, options) != Placement::Skipped {
+                let name = item_tag(elements, tag, options, hints);
+                let mut element = element_start(elements, &name, value, options, hints);
+                push_array_marker(&mut element, options);
+                write_element(elements, &name, element, value, options, hints)?;
+            }
+            elements.leave();
         }
     }
     Ok(())
 }
 
-fn contains_objects_and_primitives(arr: &Vec<Value>) -> bool {
-    let mut contains_obj = false;
-    let mut contains_primitive = false;
+/// The element name for the items of the array being written: like the array when its JSON Schema wraps it,
+/// and otherwise as configured by `NestedArray`.
+fn items_tag(elements: &ElementWriter, parent_tag: &str, items: &[Value], options: &ConversionOptions, hints: &Hints) -> String {
+    match value_hints(elements, hints) {
+        Some(array_hints) if array_hints.is_wrapped() => {
+            let local_name = parent_tag.split_once(':').map_or(parent_tag, |(_, local_name)| local_name);
+            array_hints.items.as_deref().map_or_else(|| local_name.to_string(), |item_hints| item_hints.qualify(local_name))
+        },
+        _ => item_name(parent_tag, items, 0, options),
+    }
+}
 
-    for v in arr {
-        if v.is_object() {
-            contains_obj = true;
-        } else {
-            contains_primitive = true;
-        }
-        if contains_obj && contains_primitive {
-            return true;
+/// The element name for the array item being written: the `xml.name` of its JSON Schema, or the name given by a rule, or `name`.
+fn item_tag(elements: &ElementWriter, name: &str, options: &ConversionOptions, hints: &Hints) -> String {
+    match value_hints(elements, hints).and_then(|item_hints| Some(item_hints.qualify(item_hints.name.as_deref()?))) {
+        Some(name) => name,
+        None => rule_name(elements, name, options),
+    }
+}
+
+/// Write the items of an array as elements named `item_tag`.
+/// Arrays inside the array are written as items holding their own items, one level deeper.
+fn write_items(
+    elements: &mut ElementWriter,
+    item_tag: &str,
+    items: &[Value],
+    level: usize,
+    options: &ConversionOptions,
+    hints: &Hints
+) -> Result<(), ConversionError> {
+    for (index, item) in items.iter().enumerate() {
+        elements.enter(&index.to_string());
+        match rule_placement(elements, item, options) {
+            Placement::Skipped => {},
+            Placement::Text => write_text(elements, item, options)?,
+            Placement::Element | Placement::Attribute => {
+                let name = self::item_tag(elements, item_tag, options, hints);
+                match item {
+                    Value::Array(inner) if has_content(elements, item, options, hints) && replacement_text(elements, item, options).is_none() => {
+                        elements.start(element_start(elements, &name, item, options, hints))?;
+                        write_items(elements, &item_name(&name, inner, level + 1, options), inner, level + 1, options, hints)?;
+                        elements.end(&name)?;
+                    },
+                    item => write_value_element(elements, &name, item, options, hints)?,
+                }
+            },
         }
+        elements.leave();
     }
+    Ok(())
+}
 
-    false
+/// Adds the JSON type of a leaf value as an attribute, as configured by `TypeAnnotation`.
+pub(crate) fn push_type_annotation(element: &mut Element, value: &Value, options: &ConversionOptions) {
+    if let Some((name, json_type)) = type_annotation(value, options) {
+        element.push_attribute(name, json_type);
+    }
 }
 
-fn has_top_level_attributes(json: &Value) -> bool {
-    if let Value::Object(map) = json {
-        map.keys().any(|key| key.starts_with('@'))
-    } else {
-        false
+/// The attribute name and value annotating the JSON type of a leaf value, if `TypeAnnotation` asks for one.
+pub(crate) fn type_annotation<'a>(value: &Value, options: &'a ConversionOptions) -> Option<(&'a str, &'static str)> {
+    match &options.type_annotation {
+        TypeAnnotation::None => None,
+        TypeAnnotation::XsiType => {
+            let xs_type = match value {
+                Value::String(_) => "xs:string",
+                Value::Number(num) if (num.is_i64() || num.is_u64()) && options.number_format.decimal_places.unwrap_or(0) == 0 => "xs:integer",
+                Value::Number(num) if format_number(num, &options.number_format).contains(['e', 'E']) => "xs:double",
+                Value::Number(_) => "xs:decimal",
+                Value::Bool(_) => "xs:boolean",
+                _ => return None,
+            };
+            Some(("xsi:type", xs_type))
+        },
+        TypeAnnotation::Attribute(name) => {
+            let json_type = match value {
+                Value::String(_) => "string",
+                Value::Number(_) => "number",
+                Value::Bool(_) => "boolean",
+                Value::Null => "null",
+                _ => return None,
+            };
+            Some((name.as_str(), json_type))
+        },
     }
 }
 
-fn is_attribute_key(value: &Value) -> bool {
-    value.as_object().map_or(false, |m| m.keys().any(|key| key.starts_with("@")))
+/// Declares the `xsi` and `xs` namespaces as top-level attributes, so they end up on the root element.
+pub(crate) fn declare_xsi_namespaces(json: &mut Value) {
+    if let Value::Object(map) = json {
+        map.entry("@xmlns:xsi").or_insert_with(|| Value::from(XSI_NAMESPACE));
+        map.entry("@xmlns:xs").or_insert_with(|| Value::from(XS_NAMESPACE));
+    }
 }
 
-fn is_array_with_attribute_key(value: &Value) -> bool {
-    value.as_array().and_then(|a| a.first()).map_or(false, |v| is_attribute_key(v))
+/// Declares the namespace of the array marker as a top-level attribute, so it ends up on the root element.
+pub(crate) fn declare_array_namespace(json: &mut Value, marker: &ArrayMarker) {
+    if let Value::Object(map) = json {
+        map.entry(format!("@{}", marker.namespace_attribute())).or_insert_with(|| Value::from(marker.namespace.as_str()));
+    }
 }
 
-fn capitalize_word(word: &str) -> String {
+pub(crate) fn capitalize_word(word: &str) -> String {
     let mut chars = word.chars();
     match chars.next() {
         None => String::new(),
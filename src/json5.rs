@@ -0,0 +1,490 @@
+use crate::error::ConversionError;
+use crate::generate_xml::value_to_xml_with_comments;
+use crate::options::ConversionOptions;
+use crate::xml_utils::{Comment, CommentPlacement};
+
+use serde_json::{Map, Number, Value};
+
+/// Maximum nesting of objects and arrays, as in `serde_json`.
+const MAX_DEPTH: usize = 128;
+
+/// Options for converting JSON5 and JSON with comments.
+#[derive(Debug, Clone, Default)]
+pub struct Json5Options {
+    /// Write the comments of the input as XML comments, next to the elements they describe.
+    pub keep_comments: bool,
+    /// Options for the conversion itself.
+    pub conversion: ConversionOptions,
+}
+
+/// # Convert JSON5 or JSON with comments to XML.
+///
+/// Accepts the JSON5 syntax on top of plain JSON: `//` and `/* */` comments, trailing commas, unquoted keys,
+/// single-quoted strings, hexadecimal numbers, leading or trailing decimal points, `+` signs and multi-line strings.
+/// The result is converted with the conventions of `json_to_xml_with_root`.
+/// `Infinity` and `NaN` have no JSON number representation and are written as text.
+///
+/// With `keep_comments`, a comment is written before the element of the key or array item that follows it,
+/// or after the element when it is on the same line as the end of a value. Comments of values without an element
+/// of their own, like attributes, are written inside the element holding them, or in front of it when it is empty.
+/// The comments do not change the elements: the root element is added as for JSON, as configured by `RootPolicy`.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::json5::{json5_to_xml, Json5Options};
+///
+/// let json5_string = r#"
+/// {
+///     // Service settings
+///     service: {
+///         name: 'api',
+///         port: 0x1F90, // 8080
+///         ratio: .75,
+///     },
+/// }
+/// "#;
+///
+/// let options = Json5Options { keep_comments: true, ..Default::default() };
+///
+/// let xml_string = json5_to_xml(json5_string, "Root", &options).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <!--Service settings-->
+/// <Service>
+///   <Name>api</Name>
+///   <Port>8080</Port>
+///   <!--8080-->
+///   <Ratio>0.75</Ratio>
+/// </Service>
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError::Json5` with the line and column of a syntax error.
+pub fn json5_to_xml(json5_string: &str, root: &str, options: &Json5Options) -> Result<String, ConversionError> {
+    let mut parser = Parser::new(json5_string, options.keep_comments);
+    let json_value = parser.parse_document()?;
+
+    value_to_xml_with_comments(json_value, root, &options.conversion, parser.comments)
+}
+
+/// # Parse JSON5 or JSON with comments into a JSON value.
+///
+/// Accepts the same syntax as `json5_to_xml`. Comments are dropped.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::json5::parse_json5;
+///
+/// let value = parse_json5("{list: [1, 2,], /* note */ 'key': 0xff}").unwrap();
+///
+/// assert_eq!(value, serde_json::json!({"list": [1, 2], "key": 255}));
+/// ```
+pub fn parse_json5(json5_string: &str) -> Result<Value, ConversionError> {
+    Parser::new(json5_string, false).parse_document()
+}
+
+/// A pending comment, and whether a line break preceded it.
+struct PendingComment {
+    text: String,
+    on_new_line: bool,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    keep_comments: bool,
+    pending: Vec<PendingComment>,
+    comments: Vec<Comment>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, keep_comments: bool) -> Self {
+        Parser { input, pos: 0, keep_comments, pending: Vec::new(), comments: Vec::new() }
+    }
+
+    fn parse_document(&mut self) -> Result<Value, ConversionError> {
+        self.skip_whitespace()?;
+        let value = self.parse_value("", 0)?;
+        self.skip_whitespace()?;
+        self.attach_pending("", CommentPlacement::After);
+
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(self.error(&format!("unexpected character `{}` after the value", c))),
+        }
+    }
+
+    fn parse_value(&mut self, path: &str, depth: usize) -> Result<Value, ConversionError> {
+        self.attach_pending(path, CommentPlacement::Before);
+
+        if depth > MAX_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+
+        match self.peek() {
+            Some('{') => self.parse_object(path, depth),
+            Some('[') => self.parse_array(path, depth),
+            Some(quote @ ('"' | '\'')) => {
+                self.bump();
+                Ok(Value::String(self.parse_string(quote)?))
+            },
+            Some(c) if c.is_ascii_digit() || matches!(c, '+' | '-' | '.') => self.parse_number(),
+            Some(c) if is_identifier_start(c) => {
+                let start = self.pos;
+                let word = self.parse_identifier()?;
+
+                match word.as_str() {
+                    "true" => Ok(Value::Bool(true)),
+                    "false" => Ok(Value::Bool(false)),
+                    "null" => Ok(Value::Null),
+                    "Infinity" | "NaN" => Ok(Value::String(word)),
+                    _ => {
+                        self.pos = start;
+                        Err(self.error(&format!("unexpected identifier `{}`", word)))
+                    },
+                }
+            },
+            Some(c) => Err(self.error(&format!("unexpected character `{}`", c))),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_object(&mut self, path: &str, depth: usize) -> Result<Value, ConversionError> {
+        self.bump();
+        let mut map = Map::new();
+
+        loop {
+            self.skip_whitespace()?;
+
+            if self.eat('}') {
+                self.attach_pending(path, CommentPlacement::End);
+                return Ok(Value::Object(map));
+            }
+
+            let key = match self.peek() {
+                Some(quote @ ('"' | '\'')) => {
+                    self.bump();
+                    self.parse_string(quote)?
+                },
+                Some(c) if is_identifier_start(c) => self.parse_identifier()?,
+                Some(c) => return Err(self.error(&format!("expected a key, found `{}`", c))),
+                None => return Err(self.error("unterminated object")),
+            };
+            let member_path = pointer(path, &key);
+            self.attach_pending(&member_path, CommentPlacement::Before);
+
+            self.skip_whitespace()?;
+            if !self.eat(':') {
+                return Err(self.error("expected `:` after the key"));
+            }
+            self.skip_whitespace()?;
+
+            let value = self.parse_value(&member_path, depth + 1)?;
+            map.insert(key, value);
+
+            self.skip_whitespace()?;
+            let comma = self.eat(',');
+            self.attach_trailing(&member_path)?;
+
+            if !comma {
+                self.skip_whitespace()?;
+                if self.peek() != Some('}') {
+                    return Err(self.error("expected `,` or `}` after the value"));
+                }
+            }
+        }
+    }
+
+    fn parse_array(&mut self, path: &str, depth: usize) -> Result<Value, ConversionError> {
+        self.bump();
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_whitespace()?;
+
+            if self.eat(']') {
+                self.attach_pending(path, CommentPlacement::End);
+                return Ok(Value::Array(items));
+            }
+
+            let item_path = pointer(path, &items.len().to_string());
+            items.push(self.parse_value(&item_path, depth + 1)?);
+
+            self.skip_whitespace()?;
+            let comma = self.eat(',');
+            self.attach_trailing(&item_path)?;
+
+            if !comma {
+                self.skip_whitespace()?;
+                if self.peek() != Some(']') {
+                    return Err(self.error("expected `,` or `]` after the value"));
+                }
+            }
+        }
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<String, ConversionError> {
+        let mut s = String::new();
+
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated string")),
+                Some(c) if c == quote => return Ok(s),
+                Some('\n' | '\r') => return Err(self.error("line break in string")),
+                Some('\\') => {
+                    if let Some(c) = self.parse_escape()? {
+                        s.push(c);
+                    }
+                },
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    /// Parse the character after a backslash. Line continuations produce no character.
+    fn parse_escape(&mut self) -> Result<Option<char>, ConversionError> {
+        let c = match self.bump() {
+            Some('b') => '\u{8}',
+            Some('f') => '\u{C}',
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('v') => '\u{B}',
+            Some('0') if !self.peek().is_some_and(|c| c.is_ascii_digit()) => '\0',
+            Some('x') => char::from(self.parse_hex(2)? as u8),
+            Some('u') => return self.parse_unicode_escape().map(Some),
+            Some('\r') => {
+                self.eat('\n');
+                return Ok(None);
+            },
+            Some('\n' | '\u{2028}' | '\u{2029}') => return Ok(None),
+            Some(c) if c.is_ascii_digit() => return Err(self.error("invalid escape sequence")),
+            Some(c) => c,
+            None => return Err(self.error("unterminated string")),
+        };
+        Ok(Some(c))
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, ConversionError> {
+        let high = self.parse_hex(4)?;
+
+        if !(0xD800..0xDC00).contains(&high) {
+            return char::from_u32(high).ok_or_else(|| self.error("invalid unicode escape"));
+        }
+
+        if !(self.eat('\\') && self.eat('u')) {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+
+        let low = self.parse_hex(4)?;
+        if !(0xDC00..0xE000).contains(&low) {
+            return Err(self.error("unpaired surrogate in unicode escape"));
+        }
+
+        char::from_u32(0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn parse_hex(&mut self, digits: usize) -> Result<u32, ConversionError> {
+        let hex = self.input.get(self.pos..self.pos + digits).filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()));
+
+        match hex {
+            Some(hex) => {
+                self.pos += digits;
+                Ok(u32::from_str_radix(hex, 16).unwrap())
+            },
+            None => Err(self.error("invalid hexadecimal escape")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String, ConversionError> {
+        let mut identifier = String::new();
+
+        while let Some(c) = self.peek() {
+            if c == '\\' {
+                self.bump();
+                if !self.eat('u') {
+                    return Err(self.error("invalid escape in identifier"));
+                }
+                identifier.push(self.parse_unicode_escape()?);
+            } else if is_identifier_start(c) || c.is_alphanumeric() || c == '\u{200C}' || c == '\u{200D}' {
+                self.bump();
+                identifier.push(c);
+            } else {
+                break;
+            }
+        }
+        Ok(identifier)
+    }
+
+    fn parse_number(&mut self) -> Result<Value, ConversionError> {
+        let start = self.pos;
+        let sign = match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.bump();
+                if sign == '-' { "-" } else { "" }
+            },
+            _ => "",
+        };
+
+        if self.input[self.pos..].starts_with("Infinity") {
+            self.pos += "Infinity".len();
+            return Ok(Value::String(format!("{}Infinity", sign)));
+        }
+        if self.input[self.pos..].starts_with("NaN") {
+            self.pos += "NaN".len();
+            return Ok(Value::String("NaN".to_string()));
+        }
+
+        if self.input[self.pos..].starts_with("0x") || self.input[self.pos..].starts_with("0X") {
+            self.pos += 2;
+            let digits = self.take_while(|c| c.is_ascii_hexdigit());
+
+            return match (u64::from_str_radix(digits, 16), sign) {
+                (Ok(n), "-") => i64::try_from(n).map(|n| Value::from(-n)).map_err(|_| self.number_error(start)),
+                (Ok(n), _) => Ok(Value::from(n)),
+                (Err(_), _) => Err(self.number_error(start)),
+            };
+        }
+
+        let int_part = self.take_while(|c| c.is_ascii_digit());
+        let mut normalized = format!("{}{}", sign, if int_part.is_empty() { "0" } else { int_part });
+
+        if self.eat('.') {
+            let frac_part = self.take_while(|c| c.is_ascii_digit());
+            if int_part.is_empty() && frac_part.is_empty() {
+                return Err(self.number_error(start));
+            }
+            normalized.push('.');
+            normalized.push_str(if frac_part.is_empty() { "0" } else { frac_part });
+        } else if int_part.is_empty() {
+            return Err(self.number_error(start));
+        }
+
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            self.bump();
+            normalized.push(e);
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                self.bump();
+                normalized.push(sign);
+            }
+            normalized.push_str(self.take_while(|c| c.is_ascii_digit()));
+        }
+
+        normalized.parse::<Number>().map(Value::Number).map_err(|_| self.number_error(start))
+    }
+
+    /// Skip whitespace and comments, collecting the comments as pending.
+    fn skip_whitespace(&mut self) -> Result<(), ConversionError> {
+        let mut on_new_line = false;
+
+        loop {
+            match self.peek() {
+                Some('\n' | '\r' | '\u{2028}' | '\u{2029}') => {
+                    self.bump();
+                    on_new_line = true;
+                },
+                Some(c) if c.is_whitespace() || c == '\u{FEFF}' => {
+                    self.bump();
+                },
+                Some('/') if self.input[self.pos..].starts_with("//") => {
+                    let end = self.input[self.pos..].find(['\n', '\r']).map_or(self.input.len(), |end| self.pos + end);
+                    let text = self.input[self.pos + 2..end].to_string();
+                    self.pos = end;
+                    self.push_pending(text, on_new_line);
+                },
+                Some('/') if self.input[self.pos..].starts_with("/*") => {
+                    let Some(end) = self.input[self.pos + 2..].find("*/") else {
+                        return Err(self.error("unterminated comment"));
+                    };
+                    let text = self.input[self.pos + 2..self.pos + 2 + end].to_string();
+                    self.pos += end + 4;
+                    self.push_pending(text, on_new_line);
+                },
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn push_pending(&mut self, text: String, on_new_line: bool) {
+        if self.keep_comments {
+            self.pending.push(PendingComment { text, on_new_line });
+        }
+    }
+
+    fn attach_pending(&mut self, path: &str, placement: CommentPlacement) {
+        for pending in self.pending.drain(..) {
+            self.comments.push(Comment { path: path.to_string(), placement, text: pending.text });
+        }
+    }
+
+    /// Attach the comments on the same line as the end of a value to that value.
+    fn attach_trailing(&mut self, path: &str) -> Result<(), ConversionError> {
+        self.skip_whitespace()?;
+
+        let trailing = self.pending.iter().take_while(|pending| !pending.on_new_line).count();
+
+        for pending in self.pending.drain(..trailing) {
+            self.comments.push(Comment { path: path.to_string(), placement: CommentPlacement::After, text: pending.text });
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += expected.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&predicate) {
+            self.bump();
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn number_error(&mut self, start: usize) -> ConversionError {
+        let number = self.input[start..self.pos].to_string();
+        self.pos = start;
+        self.error(&format!("invalid number `{}`", number))
+    }
+
+    fn error(&self, message: &str) -> ConversionError {
+        let before = &self.input[..self.pos];
+        let line = before.matches('\n').count() + 1;
+        let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+
+        ConversionError::Json5 { line, column, message: message.to_string() }
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '$' || c == '_' || c == '\\'
+}
+
+/// Append a key to a JSON pointer, escaping `~` and `/`.
+fn pointer(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
@@ -0,0 +1,264 @@
+use crate::error::ConversionError;
+use crate::document::Namespace;
+use crate::generate_xml::{value_to_nodes, write_document};
+use crate::options::ConversionOptions;
+use crate::rules::child_pointer;
+
+use serde_json::{Value, from_str};
+use std::collections::HashMap;
+
+/// Maximum number of `$ref` indirections followed, which also guards against reference cycles.
+const MAX_REFERENCE_DEPTH: usize = 32;
+
+/// # Convert JSON to XML with hints from a JSON Schema.
+///
+/// The JSON Schema describing the payload can carry an `xml` object per property, the same object OpenAPI uses:
+/// - `name`: the exact element or attribute name, instead of the capitalized key.
+/// - `attribute`: write the property as an attribute of its parent element.
+/// - `wrapped`: write an array inside a wrapper element, with one child element per item.
+///   Items are named by the `xml.name` of `items`, or like the wrapper element.
+/// - `namespace` and `prefix`: qualify the name and declare the namespace on the element where it is first used.
+///
+/// An array with an `xml` object on the property or its `items`, but without `wrapped`, becomes repeated elements,
+/// so an empty one writes no element. Other empty arrays are written as configured by `EmptyArray`.
+/// Properties without hints follow the conventions of `json_to_xml_with_root`, and all arrays are marked as configured by `ArrayMarker`.
+/// Items without an `xml.name` are named as configured by `NestedArray` and `TopLevelArray`, keeping the namespace prefix of their array.
+/// `ConversionOptions::rules` and `rename` apply as in `json_to_xml_with_options`, except that the `xml` object of a property wins:
+/// a rule cannot move a hinted property into an attribute or text, and neither renames an element or attribute named by `xml.name`.
+/// Local `$ref`s (e.g. `#/$defs/Address` or `#/components/schemas/Address`) and `allOf` are followed.
+///
+/// The root element is named by the `xml.name` of the schema, or `root`. It is written when it has attributes or the schema
+/// has an `xml` object, and otherwise as configured by `RootPolicy`, counting the elements written for the properties.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::json_schema::json_to_xml_with_json_schema;
+/// use json_to_xml::options::ConversionOptions;
+///
+/// let json_schema = r#"
+/// {
+///     "type": "object",
+///     "xml": {"name": "people", "namespace": "http://example.com/people", "prefix": "pr"},
+///     "properties": {
+///         "person": {
+///             "type": "object",
+///             "properties": {
+///                 "id": {"type": "integer", "xml": {"attribute": true}},
+///                 "name": {"type": "string", "xml": {"name": "fullName"}},
+///                 "tags": {"type": "array", "xml": {"wrapped": true}, "items": {"type": "string", "xml": {"name": "tag"}}}
+///             }
+///         }
+///     }
+/// }
+/// "#;
+///
+/// let json_string = r#"{"person": {"id": 7, "name": "John Doe", "tags": ["a", "b"]}}"#;
+///
+/// let xml_string = json_to_xml_with_json_schema(json_string, json_schema, "Root", &ConversionOptions::default()).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <pr:people xmlns:pr="http://example.com/people">
+///   <Person id="7">
+///     <fullName>John Doe</fullName>
+///     <Tags>
+///       <tag>a</tag>
+///       <tag>b</tag>
+///     </Tags>
+///   </Person>
+/// </pr:people>
+/// ```
+///
+/// ## Parameters:
+/// - `json_string`: The input JSON string to be converted into XML.
+/// - `json_schema`: The JSON Schema of the input, as a string.
+/// - `root`: The name for the root element, unless the schema names it.
+/// - `options`: The conversion options, as for `json_to_xml_with_options`.
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError` if the JSON, the schema or the conversion fails.
+pub fn json_to_xml_with_json_schema(json_string: &str, json_schema: &str, root: &str, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let json_value: Value = from_str(json_string)?;
+    let schema: Value = from_str(json_schema)?;
+
+    let resolver = SchemaResolver { root_schema: &schema };
+    let mut hints = Hints::default();
+    resolver.collect(&mut hints, "", &json_value, Some(&schema), resolver.hints(&schema), false);
+
+    let nodes = value_to_nodes(json_value, root, options, Vec::new(), &hints)?;
+    write_document(&nodes, options)
+}
+
+/// The `xml` object of a JSON Schema.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct XmlHints {
+    pub(crate) name: Option<String>,
+    pub(crate) namespace: Option<String>,
+    pub(crate) prefix: Option<String>,
+    pub(crate) attribute: bool,
+    pub(crate) wrapped: Option<bool>,
+    /// The `xml` object of the items of an array, if it has one.
+    pub(crate) items: Option<Box<XmlHints>>,
+}
+
+impl XmlHints {
+    fn from_value(xml: &Value) -> Self {
+        let string = |key: &str| xml.get(key).and_then(Value::as_str).map(str::to_string);
+
+        XmlHints {
+            name: string("name"),
+            namespace: string("namespace"),
+            prefix: string("prefix"),
+            attribute: xml.get("attribute").and_then(Value::as_bool).unwrap_or(false),
+            wrapped: xml.get("wrapped").and_then(Value::as_bool),
+            items: None,
+        }
+    }
+
+    /// Whether the `xml` object says nothing about the value itself. The hints of its items are not counted.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.name.is_none() && self.namespace.is_none() && self.prefix.is_none() && !self.attribute && self.wrapped.is_none()
+    }
+
+    pub(crate) fn is_wrapped(&self) -> bool {
+        self.wrapped == Some(true)
+    }
+
+    /// Add the namespace prefix, if any, to a local name.
+    pub(crate) fn qualify(&self, local_name: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{}:{}", prefix, local_name),
+            None => local_name.to_string(),
+        }
+    }
+
+    /// The namespace declaration this name needs.
+    pub(crate) fn declaration(&self) -> Option<Namespace> {
+        let uri = self.namespace.clone()?;
+        Some(Namespace { prefix: self.prefix.clone(), uri })
+    }
+}
+
+/// The `xml` objects of a JSON Schema resolved for the values of one JSON document, by the JSON Pointers of the values.
+/// `json_to_xml_with_options` converts with no hints at all.
+#[derive(Debug, Default)]
+pub(crate) struct Hints {
+    by_path: HashMap<String, XmlHints>,
+}
+
+impl Hints {
+    /// The hints for the value at a JSON Pointer, e.g. `/order/lines/0`, if its schema has an `xml` object or its items do.
+    pub(crate) fn at(&self, pointer: &str) -> Option<&XmlHints> {
+        if self.by_path.is_empty() {
+            return None;
+        }
+        self.by_path.get(pointer)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.by_path.is_empty()
+    }
+}
+
+/// Looks up the schemas of the values of a JSON document, following local `$ref`s and `allOf`.
+struct SchemaResolver<'a> {
+    root_schema: &'a Value,
+}
+
+impl<'a> SchemaResolver<'a> {
+    /// Record the hints of the value at `pointer`, described by `schema`, and of its properties and items.
+    /// Items of a hinted array property, which are written as repeated elements, take the hints of the array unless they have their own.
+    fn collect(&self, hints: &mut Hints, pointer: &str, value: &Value, schema: Option<&'a Value>, mut own: XmlHints, is_property: bool) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if let Some(property) = schema.and_then(|schema| self.property(schema, key)) {
+                        self.collect(hints, &child_pointer(pointer, key), value, Some(property), self.hints(property), true);
+                    }
+                }
+            },
+            Value::Array(items) => {
+                let item_schema = schema.and_then(|schema| self.items(schema));
+                let item_hints = item_schema.map(|item_schema| self.hints(item_schema)).unwrap_or_default();
+
+                let inherited = if is_property && item_hints.is_empty() && !own.is_empty() && !own.is_wrapped() {
+                    XmlHints { items: None, ..own.clone() }
+                } else {
+                    item_hints.clone()
+                };
+                for (index, item) in items.iter().enumerate() {
+                    self.collect(hints, &child_pointer(pointer, &index.to_string()), item, item_schema, inherited.clone(), false);
+                }
+
+                if !item_hints.is_empty() {
+                    own.items = Some(Box::new(item_hints));
+                }
+            },
+            _ => {},
+        }
+
+        if !own.is_empty() || own.items.is_some() {
+            hints.by_path.insert(pointer.to_string(), own);
+        }
+    }
+
+    /// The `xml` object of a schema, or of the schemas it references or combines.
+    fn hints(&self, schema: &'a Value) -> XmlHints {
+        schema
+            .get("xml")
+            .or_else(|| self.find(schema, 0, &|schema| schema.get("xml")))
+            .map(XmlHints::from_value)
+            .unwrap_or_default()
+    }
+
+    /// The schema of a property, looked up with and without the `@` prefix of attribute keys.
+    fn property(&self, schema: &'a Value, key: &str) -> Option<&'a Value> {
+        self.find(schema, 0, &|schema| {
+            let properties = schema.get("properties")?;
+            properties.get(key).or_else(|| properties.get(key.strip_prefix('@')?))
+        }).or_else(|| self.resolve(schema).get("additionalProperties").filter(|schema| schema.is_object()))
+    }
+
+    fn items(&self, schema: &'a Value) -> Option<&'a Value> {
+        self.find(schema, 0, &|schema| schema.get("items").filter(|items| items.is_object()))
+    }
+
+    /// Look something up in a schema and the schemas it combines with `allOf`.
+    fn find(&self, schema: &'a Value, depth: usize, lookup: &dyn Fn(&'a Value) -> Option<&'a Value>) -> Option<&'a Value> {
+        let schema = self.resolve(schema);
+
+        if let Some(found) = lookup(schema) {
+            return Some(found);
+        }
+
+        if depth >= MAX_REFERENCE_DEPTH {
+            return None;
+        }
+
+        schema.get("allOf")?.as_array()?.iter().find_map(|part| self.find(part, depth + 1, lookup))
+    }
+
+    /// Follow local `$ref`s, such as `#/$defs/Address`.
+    fn resolve(&self, mut schema: &'a Value) -> &'a Value {
+        for _ in 0..MAX_REFERENCE_DEPTH {
+            let target = schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|reference| reference.strip_prefix('#'))
+                .and_then(|pointer| self.root_schema.pointer(pointer));
+
+            match target {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+}
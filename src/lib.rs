@@ -1,3 +1,19 @@
 pub mod generate_xml;
 pub mod xml_utils;
 pub mod error;
+pub mod options;
+pub mod format;
+pub mod xsd;
+pub mod json_schema;
+pub mod toml;
+pub mod ndjson;
+pub mod json5;
+pub mod document;
+pub mod convention;
+pub mod rules;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub mod binary;
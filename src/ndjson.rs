@@ -0,0 +1,151 @@
+use crate::error::ConversionError;
+use crate::generate_xml::RecordRoot;
+use crate::options::ConversionOptions;
+
+use quick_xml::Writer;
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Read, Write};
+
+/// Options for converting NDJSON (JSON Lines) input.
+#[derive(Debug, Clone)]
+pub struct NdjsonOptions {
+    /// Name of the shared root element. Defaults to `Records`.
+    pub root: String,
+    /// Name of the element written for each line. Defaults to `Record`.
+    pub record: String,
+    /// What to do with lines that are not valid JSON.
+    pub malformed_lines: MalformedLines,
+    /// Options for converting each record.
+    pub conversion: ConversionOptions,
+}
+
+impl Default for NdjsonOptions {
+    fn default() -> Self {
+        NdjsonOptions {
+            root: "Records".to_string(),
+            record: "Record".to_string(),
+            malformed_lines: MalformedLines::default(),
+            conversion: ConversionOptions::default(),
+        }
+    }
+}
+
+/// Handling of lines that are not valid JSON.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum MalformedLines {
+    /// Stop with `ConversionError::Line`. The records before the line have already been written.
+    #[default]
+    Abort,
+    /// Skip the line and report it in `NdjsonSummary::skipped`.
+    Skip,
+}
+
+/// The result of an NDJSON conversion.
+#[derive(Debug, Default)]
+pub struct NdjsonSummary {
+    /// Number of records written.
+    pub records: usize,
+    /// Lines skipped with `MalformedLines::Skip`.
+    pub skipped: Vec<SkippedLine>,
+}
+
+/// A malformed line skipped with `MalformedLines::Skip`.
+#[derive(Debug)]
+pub struct SkippedLine {
+    /// 1-based line number.
+    pub line: usize,
+    pub error: serde_json::Error,
+}
+
+/// # Convert NDJSON (JSON Lines) to XML.
+///
+/// Reads one JSON document per line from `reader` and writes each one as a record element below a shared root element.
+/// Records are converted with the conventions of `json_to_xml_with_root` and written as soon as they are read,
+/// so the input is never held in memory as a whole. Blank lines are ignored.
+///
+/// The `RootPolicy` of the conversion options decides about the root element: `WhenNeeded` and `Always` wrap the records in it,
+/// `Fragment` writes the records as a fragment, and `Error` writes them without a root element but returns `ConversionError::RootCount`
+/// unless there is exactly one record.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::ndjson::{ndjson_to_xml, MalformedLines, NdjsonOptions};
+///
+/// let input = r#"{"@id": 1, "event": "login", "user": "alice"}
+/// not json
+/// {"@id": 2, "event": "logout", "user": "alice"}
+/// "#;
+///
+/// let options = NdjsonOptions {
+///     malformed_lines: MalformedLines::Skip,
+///     ..Default::default()
+/// };
+///
+/// let mut output = Vec::new();
+/// let summary = ndjson_to_xml(input.as_bytes(), &mut output, &options).unwrap();
+///
+/// assert_eq!(summary.records, 2);
+/// assert_eq!(summary.skipped[0].line, 2);
+///
+/// println!("{}", String::from_utf8(output).unwrap());
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Records>
+///   <Record id="1">
+///     <Event>login</Event>
+///     <User>alice</User>
+///   </Record>
+///   <Record id="2">
+///     <Event>logout</Event>
+///     <User>alice</User>
+///   </Record>
+/// </Records>
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either an `NdjsonSummary`, or a `ConversionError`.
+/// With `MalformedLines::Abort`, the first malformed line is returned as `ConversionError::Line` with its line number.
+/// A `root` or `record` that is not an XML name returns `ConversionError::InvalidName` before anything is written.
+pub fn ndjson_to_xml<R: Read, W: Write>(reader: R, writer: W, options: &NdjsonOptions) -> Result<NdjsonSummary, ConversionError> {
+    let mut reader = BufReader::new(reader);
+    let mut writer = Writer::new_with_indent(writer, b' ', 2);
+    let mut summary = NdjsonSummary::default();
+
+    let mut root = RecordRoot::start(&mut writer, &options.root, &options.record, &options.conversion)?;
+
+    let mut buffer = Vec::new();
+    let mut line = 0;
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        line += 1;
+
+        if buffer.trim_ascii().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_slice::<Value>(&buffer) {
+            Ok(value) => {
+                root.write_record(&mut writer, &value, &options.conversion)?;
+                summary.records += 1;
+            },
+            Err(error) => match options.malformed_lines {
+                MalformedLines::Abort => return Err(ConversionError::Line { line, error }),
+                MalformedLines::Skip => summary.skipped.push(SkippedLine { line, error }),
+            },
+        }
+    }
+
+    root.finish(&mut writer, &options.conversion)?;
+    writer.get_mut().flush()?;
+    Ok(summary)
+}
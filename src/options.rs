@@ -0,0 +1,221 @@
+use crate::rules::Rule;
+
+use std::collections::HashMap;
+
+/// # Options for converting JSON to XML.
+///
+/// The defaults reproduce the output of `json_to_xml_with_root`, so options only need to be set
+/// for the behaviour that should change.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::generate_xml::json_to_xml_with_options;
+/// use json_to_xml::options::{ConversionOptions, TypeAnnotation};
+///
+/// let options = ConversionOptions {
+///     type_annotation: TypeAnnotation::Attribute("type".to_string()),
+///     ..Default::default()
+/// };
+///
+/// let xml_string = json_to_xml_with_options(r#"{"age": 30}"#, "Root", &options).unwrap();
+///
+/// assert!(xml_string.contains(r#"<Age type="number">30</Age>"#));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ConversionOptions {
+    /// How leaf elements are annotated with the JSON type of their value.
+    pub type_annotation: TypeAnnotation,
+    /// How numbers are written.
+    pub number_format: NumberFormat,
+    /// How booleans are written, both in element content and in attribute values.
+    pub boolean_format: BooleanFormat,
+    /// How binary data from MessagePack or CBOR input is written as text.
+    pub binary_encoding: BinaryEncoding,
+    /// How the items of a top-level array are named inside the root element.
+    pub top_level_array: TopLevelArray,
+    /// When the top-level keys of an object are wrapped in the root element.
+    pub root_policy: RootPolicy,
+    /// How empty arrays are written.
+    pub empty_array: EmptyArray,
+    /// Marks the elements written for arrays, e.g. `json:array="true"`. Off by default.
+    pub array_marker: Option<ArrayMarker>,
+    /// Element names for the items of arrays inside arrays.
+    pub nested_array: NestedArray,
+    /// Per-field overrides, applied to the values whose path matches. See `Rule`.
+    pub rules: Vec<Rule>,
+    /// Renames JSON keys before they are capitalized into element names, e.g. `addr_line_1` to `AddressLine1`.
+    ///
+    /// Keys starting with `/` are JSON Pointers renaming only the key at that path, e.g. `/customer/addr_line_1`,
+    /// and win over plain keys, which rename the key wherever it appears. `@` keys are renamed the same way and stay attributes.
+    /// A `RuleAction::Rename` rule for the same value takes precedence.
+    ///
+    /// A name that is not an XML name, like `""` or `"first name"`, returns `ConversionError::InvalidRename`, and renaming
+    /// an `@` key to an attribute the element already has returns `ConversionError::DuplicateAttribute`.
+    /// Renaming a key to the name of a sibling element is not checked: both are written, e.g. as two `<B>` elements,
+    /// and read back as an array.
+    pub rename: HashMap<String, String>,
+}
+
+/// Type annotation written on leaf elements (elements holding a string, number, boolean or `null`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TypeAnnotation {
+    /// No annotation. Numbers, booleans and strings are written as plain text.
+    #[default]
+    None,
+    /// An XML Schema instance type, e.g. `xsi:type="xs:integer"`.
+    /// Numbers written in exponent notation, like `1e21`, are `xs:double`, as `xs:decimal` has no exponent.
+    /// `null` is not annotated: it is written as `<None/>`, without `xsi:nil`.
+    ///
+    /// The `xsi` and `xs` namespaces are declared on the root element, so the output is always wrapped in the root element.
+    XsiType,
+    /// A custom attribute holding the JSON type name, e.g. `type="number"`.
+    /// An object with an attribute of the same name returns `ConversionError::DuplicateAttribute`.
+    Attribute(String),
+}
+
+/// Formatting of JSON numbers.
+///
+/// Numbers are always written locale-neutral, with `.` as the decimal separator and without digit grouping.
+/// Enable the `arbitrary_precision` cargo feature to keep large integers and decimals exactly as they appeared in the input.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// Never use exponent notation, e.g. `1e21` is written as `1000000000000000000000`.
+    /// Exponents beyond ±1000, only possible with `arbitrary_precision`, are kept.
+    pub plain_notation: bool,
+    /// Write every number with exactly this many decimal places, rounding halves away from zero.
+    /// Implies `plain_notation`.
+    pub decimal_places: Option<usize>,
+}
+
+/// Formatting of JSON booleans.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BooleanFormat {
+    /// `true` / `false`
+    #[default]
+    TrueFalse,
+    /// `1` / `0`
+    OneZero,
+    /// `yes` / `no`
+    YesNo,
+    /// `True` / `False`
+    Capitalized,
+}
+
+/// Text encoding of binary data, such as MessagePack `bin` and CBOR byte strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BinaryEncoding {
+    /// Standard base64 with padding, e.g. `AQID`.
+    #[default]
+    Base64,
+    /// Lowercase hexadecimal, e.g. `010203`.
+    Hex,
+}
+
+/// Element names for the items of a top-level JSON array.
+///
+/// A top-level array is always written inside the root element. Names are capitalized like keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum TopLevelArray {
+    /// Items are named after the root element, e.g. `<Root><RootItem>…</RootItem></Root>`, like other arrays of primitives.
+    #[default]
+    RootItem,
+    /// Items have the given name, e.g. `TopLevelArray::Named("item".to_string())` gives `<Root><Item>…</Item></Root>`.
+    Named(String),
+}
+
+/// When the elements of a top-level JSON object are wrapped in the root element.
+///
+/// Top-level `@` attributes, `$text` and `$content` belong to the root element, so they are always wrapped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RootPolicy {
+    /// Wrap unless the object holds exactly one element, which then is the root element,
+    /// e.g. `{"book": {…}}` gives `<Book>…</Book>` and `{"a": 1, "b": 2}` gives `<Root><A>1</A><B>2</B></Root>`.
+    #[default]
+    WhenNeeded,
+    /// Always wrap, e.g. `{"book": {…}}` gives `<Root><Book>…</Book></Root>`.
+    Always,
+    /// Never wrap, and return `ConversionError::RootCount` unless the object holds exactly one element.
+    Error,
+    /// Never wrap, and return the elements as an XML fragment with several root elements.
+    /// The XML declaration and the "Generated with" comment are left out, so the fragment can be embedded in another document.
+    Fragment,
+}
+
+/// How an empty JSON array is written.
+///
+/// A top-level empty array is always written as the root element, `<Root/>`, with the marker attribute for `Marked`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum EmptyArray {
+    /// An element without content, e.g. `<Tag></Tag>`, which reads back as an empty string.
+    #[default]
+    Element,
+    /// No element at all.
+    Omit,
+    /// A self-closing element, e.g. `<Tag/>`, which reads back as an empty object.
+    SelfClosing,
+    /// A self-closing element with the given attribute set to `true`, e.g. `EmptyArray::Marked("json:array".to_string())` gives `<Tag json:array="true"/>`.
+    /// `Document::to_value_with_options` reads it back as an empty array.
+    ///
+    /// A prefixed attribute needs its namespace declared, e.g. with a top-level `"@xmlns:json"` key.
+    Marked(String),
+}
+
+/// Element names for the items of nested arrays, like the rows and cells of a matrix.
+///
+/// Every level has its own name, so `document::xml_to_json` reads nested arrays back level by level.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum NestedArray {
+    /// Each level appends `Item` to the name of the level above,
+    /// e.g. `{"matrix": [[1, 2]]}` gives `<Matrix><MatrixItem><MatrixItemItem>1</MatrixItemItem>…</MatrixItem></Matrix>`.
+    #[default]
+    ItemSuffix,
+    /// Names for the levels of an array holding arrays, starting with its own items,
+    /// e.g. `NestedArray::Named(vec!["row".to_string(), "cell".to_string()])` gives `<Matrix><Row><Cell>1</Cell>…</Row></Matrix>`.
+    /// Levels beyond the list append `Item` to the name of the level above. Arrays without arrays inside keep `<TagItem>`.
+    ///
+    /// The names should differ from each other and from the keys of the JSON, or reading the XML back becomes ambiguous.
+    Named(Vec<String>),
+}
+
+/// Marker attribute on the elements written for JSON arrays, e.g. `json:array="true"`.
+///
+/// Arrays of objects mark each of their elements, all other arrays the element holding the items.
+/// A single-item array can then be told apart from a plain value, and `Document::to_value_with_options` reads the arrays back exactly.
+///
+/// The namespace is declared on the root element, so the output is always wrapped in the root element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayMarker {
+    /// Namespace prefix of the `array` attribute.
+    pub prefix: String,
+    /// Namespace URI bound to the prefix.
+    pub namespace: String,
+}
+
+impl Default for ArrayMarker {
+    /// `json:array="true"` in the `JSON_NAMESPACE` namespace.
+    fn default() -> Self {
+        ArrayMarker { prefix: "json".to_string(), namespace: JSON_NAMESPACE.to_string() }
+    }
+}
+
+impl ArrayMarker {
+    /// The qualified name of the marker attribute, e.g. `json:array`.
+    pub fn attribute_name(&self) -> String {
+        format!("{}:array", self.prefix)
+    }
+
+    /// The attribute declaring the namespace, e.g. `xmlns:json`.
+    pub fn namespace_attribute(&self) -> String {
+        format!("xmlns:{}", self.prefix)
+    }
+}
+
+/// Namespace of the default `ArrayMarker`.
+pub const JSON_NAMESPACE: &str = "urn:json-to-xml:json";
+
+/// XML Schema instance namespace used by `TypeAnnotation::XsiType`.
+pub const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// XML Schema namespace used by `TypeAnnotation::XsiType`.
+pub const XS_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
@@ -0,0 +1,275 @@
+use crate::error::ConversionError;
+use crate::format::format_scalar;
+use crate::options::ConversionOptions;
+use crate::xml_utils::is_name;
+
+use serde_json::Value;
+use std::str::FromStr;
+
+/// # Path-based conversion rules.
+///
+/// A rule applies an action to the values whose path matches its pattern, overriding the conventions of
+/// `json_to_xml_with_options` for single fields without changing the JSON. Rules are set in `ConversionOptions::rules`
+/// and evaluated while the value tree is traversed.
+///
+/// Patterns are JSON Pointers or JSONPath-style expressions, see `PathPattern`. When several rules match a value,
+/// the first `RuleAction::Rename` names it and the first other action decides how it is written, so a rename
+/// can be combined with e.g. `RuleAction::Attribute` or `RuleAction::CData`.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::generate_xml::json_to_xml_with_options;
+/// use json_to_xml::options::ConversionOptions;
+/// use json_to_xml::rules::{Rule, RuleAction};
+///
+/// let options = ConversionOptions {
+///     rules: vec![
+///         Rule::new("$.order.id", RuleAction::Attribute).unwrap(),
+///         Rule::new("$.order.items[*]", RuleAction::Rename("Line".to_string())).unwrap(),
+///         Rule::new("/order/note", RuleAction::CData).unwrap(),
+///         Rule::new("$..meta", RuleAction::Skip).unwrap(),
+///     ],
+///     ..Default::default()
+/// };
+///
+/// let json_string = r#"
+/// {
+///     "order": {
+///         "id": 7,
+///         "items": [{"sku": "A1"}, {"sku": "B2"}],
+///         "note": "Leave at <door>",
+///         "meta": {"source": "web"}
+///     }
+/// }
+/// "#;
+///
+/// let xml_string = json_to_xml_with_options(json_string, "Root", &options).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Order id="7">
+///   <Line>
+///     <Sku>A1</Sku>
+///   </Line>
+///   <Line>
+///     <Sku>B2</Sku>
+///   </Line>
+///   <Note><![CDATA[Leave at <door>]]></Note>
+/// </Order>
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rule {
+    /// The paths the rule applies to.
+    pub pattern: PathPattern,
+    /// What the rule does with the matching values.
+    pub action: RuleAction,
+}
+
+impl Rule {
+    /// A rule for a pattern given as a JSON Pointer or JSONPath-style expression.
+    ///
+    /// Returns `ConversionError::InvalidPath` for an invalid pattern, and `ConversionError::InvalidName` if a
+    /// `RuleAction::Rename` gives a name that is not an XML name.
+    pub fn new(pattern: &str, action: RuleAction) -> Result<Rule, ConversionError> {
+        if let RuleAction::Rename(name) = &action
+            && !is_name(name)
+        {
+            return Err(ConversionError::InvalidName { path: pattern.to_string(), name: name.clone() });
+        }
+        Ok(Rule { pattern: pattern.parse()?, action })
+    }
+}
+
+/// What a rule does with the values it matches.
+#[derive(Debug, Clone)]
+pub enum RuleAction {
+    /// Name the element exactly as given, instead of the capitalized key or the item name.
+    /// Also renames attributes, and the elements of an array of objects when the rule matches its items.
+    Rename(String),
+    /// Write a string, number or boolean as an attribute of the parent element, named after the key.
+    /// Objects, arrays and `null` are written as elements. An attribute the parent already has returns `ConversionError::DuplicateAttribute`.
+    Attribute,
+    /// Write the value as text of the parent element, like `$text`. Objects and arrays are written as elements.
+    Text,
+    /// Leave the value out, as element, attribute or array item.
+    Skip,
+    /// Write a string, number or boolean as a CDATA section.
+    CData,
+    /// Write the text returned by the function instead of the value, for elements and attributes.
+    /// The function receives the JSON value, so it can also flatten objects and arrays into text.
+    Format(fn(&Value) -> String),
+}
+
+/// # A pattern matching JSON paths.
+///
+/// Two notations are accepted:
+/// - JSON Pointers, e.g. `/order/id` or `/items/0`. A `*` segment matches any key or index.
+/// - JSONPath-style expressions starting with `$`, e.g. `$.order.id`, `$.items[*]`, `$.items[0]` or `$['order']['id']`.
+///   `*` matches any key or index, and `..` any number of levels, e.g. `$..id` matches `id` keys at every depth.
+///
+/// Array indexes are matched as keys, so `$.items[0]` and `$.items.0` are the same pattern.
+/// The root value has the empty path, matched by `$` and the empty JSON Pointer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPattern {
+    steps: Vec<Step>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Any,
+    Descendants,
+}
+
+impl FromStr for PathPattern {
+    type Err = ConversionError;
+
+    fn from_str(pattern: &str) -> Result<PathPattern, ConversionError> {
+        let steps = if let Some(path) = pattern.strip_prefix('$') {
+            parse_json_path(path).ok_or_else(|| ConversionError::InvalidPath(pattern.to_string()))?
+        } else if pattern.is_empty() || pattern.starts_with('/') {
+            pointer_segments(pattern).map(|segment| if segment == "*" { Step::Any } else { Step::Key(segment) }).collect()
+        } else {
+            return Err(ConversionError::InvalidPath(pattern.to_string()));
+        };
+        Ok(PathPattern { steps })
+    }
+}
+
+impl PathPattern {
+    /// Whether the pattern matches the JSON Pointer of a value, e.g. `/order/items/0`.
+    pub fn matches(&self, pointer: &str) -> bool {
+        let segments: Vec<String> = pointer_segments(pointer).collect();
+        matches_steps(&self.steps, &segments)
+    }
+}
+
+/// The unescaped segments of a JSON Pointer.
+fn pointer_segments(pointer: &str) -> impl Iterator<Item = String> + '_ {
+    pointer.split('/').skip(1).map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+}
+
+fn matches_steps(steps: &[Step], segments: &[String]) -> bool {
+    match steps.split_first() {
+        None => segments.is_empty(),
+        Some((Step::Descendants, rest)) => (0..=segments.len()).any(|skip| matches_steps(rest, &segments[skip..])),
+        Some((step, rest)) => match segments.split_first() {
+            Some((segment, segments)) => (*step == Step::Any || matches!(step, Step::Key(key) if key == segment)) && matches_steps(rest, segments),
+            None => false,
+        },
+    }
+}
+
+/// Parse the steps of a JSONPath-style expression after the leading `$`.
+fn parse_json_path(mut path: &str) -> Option<Vec<Step>> {
+    let mut steps = Vec::new();
+
+    while !path.is_empty() {
+        if let Some(rest) = path.strip_prefix("..") {
+            steps.push(Step::Descendants);
+            path = rest;
+            if path.starts_with('[') {
+                continue;
+            }
+        } else if let Some(rest) = path.strip_prefix('.') {
+            path = rest;
+        } else if let Some(rest) = path.strip_prefix('[') {
+            let end = rest.find(']')?;
+            let selector = rest[..end].trim();
+            path = &rest[end + 1..];
+
+            let step = match selector {
+                "*" => Step::Any,
+                quoted if quoted.len() >= 2 && (quoted.starts_with('\'') && quoted.ends_with('\'') || quoted.starts_with('"') && quoted.ends_with('"')) => {
+                    Step::Key(quoted[1..quoted.len() - 1].to_string())
+                },
+                index if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) => Step::Key(index.to_string()),
+                _ => return None,
+            };
+            steps.push(step);
+            continue;
+        } else {
+            return None;
+        }
+
+        let end = path.find(['.', '[']).unwrap_or(path.len());
+        let step = match &path[..end] {
+            "" => return None,
+            "*" => Step::Any,
+            key => Step::Key(key.to_string()),
+        };
+        steps.push(step);
+        path = &path[end..];
+    }
+    Some(steps)
+}
+
+/// The name given by the first `RuleAction::Rename` rule matching the path.
+pub(crate) fn renamed<'a>(rules: &'a [Rule], pointer: &str) -> Option<&'a str> {
+    rules.iter().find_map(|rule| match &rule.action {
+        RuleAction::Rename(name) if rule.pattern.matches(pointer) => Some(name.as_str()),
+        _ => None,
+    })
+}
+
+/// The first action other than `RuleAction::Rename` of the rules matching the path.
+pub(crate) fn action_at<'a>(rules: &'a [Rule], pointer: &str) -> Option<&'a RuleAction> {
+    rules
+        .iter()
+        .find(|rule| !matches!(rule.action, RuleAction::Rename(_)) && rule.pattern.matches(pointer))
+        .map(|rule| &rule.action)
+}
+
+/// How the value at a path is written, after applying the rules matching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Placement {
+    Element,
+    Attribute,
+    Text,
+    Skipped,
+}
+
+/// Where the value at a path ends up: attributes and text only hold strings, numbers and booleans (and `null` as text),
+/// so other values stay elements.
+pub(crate) fn placement(rules: &[Rule], pointer: &str, value: &Value) -> Placement {
+    if rules.is_empty() {
+        return Placement::Element;
+    }
+    match action_at(rules, pointer) {
+        Some(RuleAction::Skip) => Placement::Skipped,
+        Some(RuleAction::Attribute) if is_scalar(value) => Placement::Attribute,
+        Some(RuleAction::Text) if is_scalar(value) || value.is_null() => Placement::Text,
+        _ => Placement::Element,
+    }
+}
+
+/// Text written instead of a value by a `RuleAction::CData` or `RuleAction::Format` rule.
+pub(crate) enum RuleText {
+    Text(String),
+    CData(String),
+}
+
+/// The text replacing the value at a path, if a rule asks for CDATA or a custom format.
+pub(crate) fn rule_text(pointer: &str, value: &Value, options: &ConversionOptions) -> Option<RuleText> {
+    match action_at(&options.rules, pointer)? {
+        RuleAction::CData => format_scalar(value, options).map(RuleText::CData),
+        RuleAction::Format(format) => Some(RuleText::Text(format(value))),
+        _ => None,
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_))
+}
+
+/// The JSON Pointer of a key or array index below `pointer`.
+pub(crate) fn child_pointer(pointer: &str, segment: &str) -> String {
+    format!("{}/{}", pointer, segment.replace('~', "~0").replace('/', "~1"))
+}
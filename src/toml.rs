@@ -0,0 +1,87 @@
+use crate::error::ConversionError;
+use crate::generate_xml::value_to_xml;
+use crate::options::ConversionOptions;
+
+use serde_json::{Map, Number, Value};
+use ::toml::{Table, Value as TomlValue};
+
+/// # Convert TOML to XML with a custom root element.
+///
+/// The TOML document is read into the same value tree as JSON and converted with the conventions of `json_to_xml_with_root`:
+/// tables become elements, arrays of tables become repeated elements and arrays of values become `<TagItem>` lists.
+/// Keys starting with `@` (quoted in TOML, e.g. `"@id" = 1`) become attributes.
+///
+/// Datetimes are written as ISO-8601 text, e.g. `1979-05-27T07:32:00Z`. Local dates and times keep their short forms.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::toml::toml_to_xml;
+///
+/// let toml_string = r#"
+/// "@version" = "2"
+///
+/// [owner]
+/// name = "Tom"
+/// born = 1979-05-27T07:32:00Z
+///
+/// [[servers]]
+/// host = "alpha"
+///
+/// [[servers]]
+/// host = "beta"
+/// "#;
+///
+/// let xml_string = toml_to_xml(toml_string, "Config").unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Config version="2">
+///   <Owner>
+///     <Born>1979-05-27T07:32:00Z</Born>
+///     <Name>Tom</Name>
+///   </Owner>
+///   <Servers>
+///     <Host>alpha</Host>
+///   </Servers>
+///   <Servers>
+///     <Host>beta</Host>
+///   </Servers>
+/// </Config>
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError::Toml` if the TOML cannot be parsed.
+pub fn toml_to_xml(toml_string: &str, root: &str) -> Result<String, ConversionError> {
+    let table: Table = ::toml::from_str(toml_string)?;
+
+    value_to_xml(table_to_json(table), root, &ConversionOptions::default())
+}
+
+fn table_to_json(table: Table) -> Value {
+    let map: Map<String, Value> = table
+        .into_iter()
+        .map(|(key, value)| (key, toml_to_json(value)))
+        .collect();
+    Value::Object(map)
+}
+
+/// Convert a TOML value into the JSON value tree used by the generator.
+fn toml_to_json(toml_value: TomlValue) -> Value {
+    match toml_value {
+        TomlValue::String(s) => Value::String(s),
+        TomlValue::Integer(i) => Value::from(i),
+        // Infinity and NaN have no JSON number representation.
+        TomlValue::Float(f) => Number::from_f64(f).map_or_else(|| Value::String(TomlValue::Float(f).to_string()), Value::Number),
+        TomlValue::Boolean(b) => Value::Bool(b),
+        TomlValue::Datetime(datetime) => Value::String(datetime.to_string()),
+        TomlValue::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        TomlValue::Table(table) => table_to_json(table),
+    }
+}
@@ -1,17 +1,18 @@
+use crate::document::{Element, Namespace, Node};
 use crate::error::ConversionError;
 use quick_xml::Writer;
 use quick_xml::events::{BytesDecl, BytesStart, BytesEnd, BytesText, Event};
-use std::io::Cursor;
+use std::io::Write;
 
 /// Write XML declaration
-pub fn write_declaration(writer: &mut Writer<Cursor<Vec<u8>>>, xml_version: &str, encoding: Option<&str>) -> Result<(), ConversionError> {
+pub fn write_declaration<W: Write>(writer: &mut Writer<W>, xml_version: &str, encoding: Option<&str>) -> Result<(), ConversionError> {
     writer
         .write_event(Event::Decl(BytesDecl::new(xml_version, encoding, None)))?;
     Ok(())
 }
 
 /// Write Comment
-pub fn write_comment(writer: &mut Writer<Cursor<Vec<u8>>>, version: &str) -> Result<(), ConversionError> {
+pub fn write_comment<W: Write>(writer: &mut Writer<W>, version: &str) -> Result<(), ConversionError> {
     writer
         .write_event(
             Event::Comment(BytesText::new(version)))?;
@@ -19,29 +20,300 @@ pub fn write_comment(writer: &mut Writer<Cursor<Vec<u8>>>, version: &str) -> Res
 }
 
 /// Write start tag
-pub fn write_start_tag(writer: &mut Writer<Cursor<Vec<u8>>>, element: &BytesStart<'_>) -> Result<(), ConversionError> {
+pub fn write_start_tag<W: Write>(writer: &mut Writer<W>, element: &BytesStart<'_>) -> Result<(), ConversionError> {
     writer
         .write_event(Event::Start(element.to_owned()))?;
     Ok(())
 }
 
 /// Write empty tag
-pub fn write_empty_tag(writer: &mut Writer<Cursor<Vec<u8>>>, element: &BytesStart<'_>) -> Result<(), ConversionError> {
+pub fn write_empty_tag<W: Write>(writer: &mut Writer<W>, element: &BytesStart<'_>) -> Result<(), ConversionError> {
     writer
         .write_event(Event::Empty(element.to_owned()))?;
     Ok(())
 }
 
 /// Write end tag
-pub fn write_end_tag(writer: &mut Writer<Cursor<Vec<u8>>>, element: &BytesEnd<'_>) -> Result<(), ConversionError> {
+pub fn write_end_tag<W: Write>(writer: &mut Writer<W>, element: &BytesEnd<'_>) -> Result<(), ConversionError> {
     writer
         .write_event(Event::End(element.to_owned()))?;
     Ok(())
 }
 
 /// Write text content between tags
-pub fn write_content(writer: &mut Writer<Cursor<Vec<u8>>>, s: &str) -> Result<(), ConversionError> {
+pub fn write_content<W: Write>(writer: &mut Writer<W>, s: &str) -> Result<(), ConversionError> {
     writer
         .write_event(Event::Text(BytesText::new(s)))?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Write content without indentation, e.g. mixed content where added whitespace would change the text.
+/// The content is written by `write` to a writer without indentation and copied into `writer` as is.
+pub fn write_inline<W, F>(writer: &mut Writer<W>, write: F) -> Result<(), ConversionError>
+where
+    W: Write,
+    F: FnOnce(&mut Writer<Vec<u8>>) -> Result<(), ConversionError>,
+{
+    let mut inline = Writer::new(Vec::new());
+    write(&mut inline)?;
+
+    // An empty text event keeps `writer` from breaking the line before the content and before the next end tag.
+    write_content(writer, "")?;
+    writer.get_mut().write_all(&inline.into_inner())?;
+    Ok(())
+}
+
+/// Whether `name` can name an element or attribute: an XML name with at most one colon separating a namespace prefix,
+/// e.g. `Item`, `_id` or `addr:Street`, but not ``, `a b`, `1st` or `a:`.
+pub(crate) fn is_name(name: &str) -> bool {
+    match name.split_once(':') {
+        Some((prefix, local)) => is_ncname(prefix) && is_ncname(local),
+        None => is_ncname(name),
+    }
+}
+
+/// Whether `name` is an XML name without colons, as used for namespace prefixes.
+pub(crate) fn is_ncname(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(is_name_start_char) && chars.all(|c| is_name_start_char(c) || is_name_char(c))
+}
+
+fn is_name_start_char(c: char) -> bool {
+    matches!(c,
+        'A'..='Z' | '_' | 'a'..='z' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}' | '\u{370}'..='\u{37D}'
+        | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}'
+        | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
+}
+
+fn is_name_char(c: char) -> bool {
+    matches!(c, '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
+
+/// Whether `c` may appear in an XML document. C0 control characters other than tab, line feed and carriage return,
+/// and U+FFFE and U+FFFF, may not, not even escaped.
+pub(crate) fn is_xml_char(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)
+}
+
+/// Check that text, an attribute value or a comment only holds characters XML allows.
+pub(crate) fn check_text(text: &str, path: &str) -> Result<(), ConversionError> {
+    match text.chars().find(|c| !is_xml_char(*c)) {
+        Some(character) => Err(ConversionError::InvalidCharacter { path: path.to_string(), character }),
+        None => Ok(()),
+    }
+}
+
+/// Check the names of an element, its namespace prefixes and its attributes, that no attribute is written twice,
+/// and that the attribute values and namespace URIs only hold characters XML allows.
+pub(crate) fn check_names(element: &Element, path: &str) -> Result<(), ConversionError> {
+    let invalid = std::iter::once(&element.name)
+        .chain(element.attributes.iter().map(|attribute| &attribute.name))
+        .find(|name| !is_name(name))
+        .or_else(|| element.namespaces.iter().filter_map(|namespace| namespace.prefix.as_ref()).find(|prefix| !is_ncname(prefix)));
+
+    if let Some(name) = invalid {
+        return Err(ConversionError::InvalidName { path: path.to_string(), name: name.clone() });
+    }
+
+    let names: Vec<String> = element.namespaces.iter().map(|namespace| namespace.attribute_name())
+        .chain(element.attributes.iter().map(|attribute| attribute.name.clone()))
+        .collect();
+    if let Some((_, name)) = names.iter().enumerate().find(|(index, name)| names[..*index].contains(name)) {
+        return Err(ConversionError::DuplicateAttribute { path: path.to_string(), name: name.clone() });
+    }
+
+    element.namespaces.iter().map(|namespace| &namespace.uri)
+        .chain(element.attributes.iter().map(|attribute| &attribute.value))
+        .try_for_each(|value| check_text(value, path))
+}
+
+/// Where a comment is written, relative to the element of its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CommentPlacement {
+    Before,
+    After,
+    /// Inside the element, after its children.
+    End,
+}
+
+/// A comment belonging to the value at a JSON path, e.g. one kept from JSON5 input.
+#[derive(Debug)]
+pub(crate) struct Comment {
+    /// JSON pointer of the value the comment belongs to.
+    pub(crate) path: String,
+    pub(crate) placement: CommentPlacement,
+    pub(crate) text: String,
+}
+
+/// Builds the nodes of a document while tracking the open elements, so every start tag is closed by a matching end tag.
+///
+/// The JSON path of the value being written is tracked as a JSON Pointer and reported by `ConversionError::Unbalanced`.
+/// The finished nodes are written as XML by `write_nodes`, the same way as a `Document`.
+///
+/// Comments are written next to the element of their path when its value is entered and left. Comments of values
+/// without an element of their own, like attributes, are written inside the nearest element, or in front of it when it is empty.
+pub(crate) struct ElementWriter {
+    nodes: Vec<Node>,
+    /// The open elements, with the JSON path where each was opened.
+    open: Vec<(Element, String)>,
+    path: Vec<String>,
+    /// The comments not written yet.
+    comments: Vec<Comment>,
+}
+
+impl ElementWriter {
+    pub(crate) fn new() -> Self {
+        ElementWriter { nodes: Vec::new(), open: Vec::new(), path: Vec::new(), comments: Vec::new() }
+    }
+
+    /// Write the comments next to the values at their paths. Comments before the root value come first.
+    pub(crate) fn with_comments(comments: Vec<Comment>) -> Result<Self, ConversionError> {
+        for comment in &comments {
+            check_text(&comment.text, &comment.path)?;
+        }
+
+        let mut elements = ElementWriter { nodes: Vec::new(), open: Vec::new(), path: Vec::new(), comments };
+        elements.write_comments(|comment| comment.path.is_empty() && comment.placement == CommentPlacement::Before);
+        Ok(elements)
+    }
+
+    /// Enter the value of an object key or the item of an array at `index`.
+    pub(crate) fn enter(&mut self, segment: &str) {
+        self.path.push(segment.replace('~', "~0").replace('/', "~1"));
+
+        if !self.comments.is_empty() {
+            let path = self.path();
+            self.write_comments(|comment| comment.path == path && comment.placement == CommentPlacement::Before);
+        }
+    }
+
+    pub(crate) fn leave(&mut self) {
+        if !self.comments.is_empty() {
+            let path = self.path();
+            self.write_comments(inside(&path));
+            self.write_comments(|comment| comment.path == path && comment.placement == CommentPlacement::After);
+        }
+        self.path.pop();
+    }
+
+    /// The JSON Pointer of the value being written, e.g. `/people/0/name`.
+    pub(crate) fn path(&self) -> String {
+        self.path.iter().map(|segment| format!("/{}", segment)).collect()
+    }
+
+    /// Whether an open element declares the namespace with the same prefix, and no element inside it declares another one for the prefix.
+    pub(crate) fn in_scope(&self, namespace: &Namespace) -> bool {
+        self.open
+            .iter()
+            .rev()
+            .flat_map(|(element, _)| &element.namespaces)
+            .find(|declared| declared.prefix == namespace.prefix)
+            .is_some_and(|declared| declared.uri == namespace.uri)
+    }
+
+    /// The content of the innermost open element, or the top level.
+    fn content(&mut self) -> &mut Vec<Node> {
+        match self.open.last_mut() {
+            Some((element, _)) => &mut element.children,
+            None => &mut self.nodes,
+        }
+    }
+
+    pub(crate) fn start(&mut self, element: Element) -> Result<(), ConversionError> {
+        let path = self.path();
+        check_names(&element, &path)?;
+        self.open.push((element, path));
+        Ok(())
+    }
+
+    /// Close the innermost open element, which must be named `name`.
+    pub(crate) fn end(&mut self, name: &str) -> Result<(), ConversionError> {
+        if let Some((_, path)) = self.open.last()
+            && !self.comments.is_empty()
+        {
+            let path = path.clone();
+            self.write_comments(inside(&path));
+        }
+
+        match self.open.pop() {
+            Some((element, _)) if element.name == name => {
+                self.content().push(Node::Element(element));
+                Ok(())
+            },
+            _ => Err(ConversionError::Unbalanced { path: self.path(), element: name.to_string() }),
+        }
+    }
+
+    pub(crate) fn empty(&mut self, element: Element) -> Result<(), ConversionError> {
+        let path = self.path();
+        check_names(&element, &path)?;
+        self.write_comments(inside(&path));
+        self.content().push(Node::Element(element));
+        Ok(())
+    }
+
+    /// Write text, merged with directly preceding text.
+    pub(crate) fn text(&mut self, s: &str) -> Result<(), ConversionError> {
+        check_text(s, &self.path())?;
+        let content = self.content();
+        match content.last_mut() {
+            Some(Node::Text(previous)) => previous.push_str(s),
+            _ => content.push(Node::Text(s.to_string())),
+        }
+        Ok(())
+    }
+
+    /// Write text as CDATA. It is split into several sections where it contains `]]>` when written.
+    pub(crate) fn cdata(&mut self, s: &str) -> Result<(), ConversionError> {
+        check_text(s, &self.path())?;
+        self.content().push(Node::CData(s.to_string()));
+        Ok(())
+    }
+
+    /// Write the comments matching `filter` into the current content, in their order.
+    fn write_comments(&mut self, filter: impl Fn(&Comment) -> bool) {
+        if self.comments.is_empty() {
+            return;
+        }
+
+        let (matching, rest): (Vec<Comment>, Vec<Comment>) = std::mem::take(&mut self.comments).into_iter().partition(|comment| filter(comment));
+        self.comments = rest;
+        for comment in matching {
+            self.content().push(Node::Comment(comment_text(&comment.text)));
+        }
+    }
+
+    /// Check that every element has been closed, and return the nodes. The remaining comments end the document.
+    pub(crate) fn finish(mut self) -> Result<Vec<Node>, ConversionError> {
+        if let Some((element, path)) = self.open.pop() {
+            return Err(ConversionError::Unbalanced { path, element: element.name });
+        }
+
+        self.write_comments(inside(""));
+        self.write_comments(|_| true);
+        Ok(self.nodes)
+    }
+}
+
+/// Matches the comments inside the value at `path`: those of its members and items, and those at its end.
+fn inside(path: &str) -> impl Fn(&Comment) -> bool + '_ {
+    move |comment| {
+        comment.path.strip_prefix(path).is_some_and(|rest| rest.starts_with('/'))
+            || (comment.path == path && comment.placement == CommentPlacement::End)
+    }
+}
+
+/// Make text valid inside `<!-- -->`: no `--` and no `-` at the end.
+fn comment_text(text: &str) -> String {
+    let mut text = text.trim().to_string();
+
+    // Each replacement can leave a new `--` behind, e.g. `---` becomes `- --`.
+    while text.contains("--") {
+        text = text.replace("--", "- -");
+    }
+
+    if text.ends_with('-') {
+        text.push(' ');
+    }
+    text
+}
@@ -0,0 +1,504 @@
+use crate::document::{self, start_tag, write_nodes};
+use crate::error::ConversionError;
+use crate::format::{format_boolean, format_number, to_plain_notation};
+use crate::generate_xml::{check_renames, write_declared_element, write_header};
+use crate::options::{BooleanFormat, ConversionOptions, NumberFormat};
+use crate::xml_utils::{check_text, is_name, write_start_tag, write_empty_tag, write_end_tag, write_content, ElementWriter};
+use crate::xsd::{BuiltinType, ComplexType, Content, Element, ElementType, Group, GroupKind, Particle, Schema, SimpleType, Violation, ViolationKind};
+
+use quick_xml::Writer;
+use quick_xml::events::BytesStart;
+use serde_json::{Map, Value, from_str};
+use std::io::Cursor;
+
+/// The prefix bound to the target namespace when local elements are unqualified.
+const TARGET_PREFIX: &str = "tns";
+
+/// # Convert JSON to XML guided by an XML Schema.
+///
+/// The schema decides the element order, the exact casing of names, which fields are attributes and the value types:
+/// - JSON keys are matched to schema elements and attributes case-insensitively. A key can name an attribute with or without the `@` prefix.
+/// - Child elements follow the `xs:sequence` order of the schema.
+/// - Values are formatted to their declared simple types, e.g. `30.0` is written as `30` for `xs:integer`.
+/// - An array becomes repeated elements, or the children of a wrapper element whose only child repeats.
+///   In an `xs:choice` with `maxOccurs` above 1, every alternative present in the JSON is written, in schema order.
+/// - `$content` arrays for mixed content in document order are not supported and reported as violations.
+/// - The content of `xs:anyType` elements follows the conventions of `json_to_xml_with_root`, including `$content` and rules,
+///   which match the JSON paths of the input.
+///
+/// The root element is the global element matching the single top-level key, or the only global element of the schema.
+/// It declares the `targetNamespace` of the schema, as the default namespace with `elementFormDefault="qualified"`.
+/// Otherwise only global elements belong to the namespace, so it is bound to the `tns` prefix and local elements stay unprefixed.
+/// Violations are collected and returned together as `ConversionError::Schema`, so no invalid XML is emitted.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::options::ConversionOptions;
+/// use json_to_xml::xsd::{json_to_xml_with_schema, parse_xsd};
+///
+/// let schema = parse_xsd(r#"
+/// <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+///   <xs:element name="person">
+///     <xs:complexType>
+///       <xs:sequence>
+///         <xs:element name="name" type="xs:string"/>
+///         <xs:element name="age" type="xs:integer"/>
+///       </xs:sequence>
+///       <xs:attribute name="id" type="xs:string" use="required"/>
+///     </xs:complexType>
+///   </xs:element>
+/// </xs:schema>
+/// "#).unwrap();
+///
+/// let json_string = r#"{"Person": {"Age": 30.0, "ID": "1234", "Name": "John Doe"}}"#;
+///
+/// let xml_string = json_to_xml_with_schema(json_string, &schema, &ConversionOptions::default()).unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <person id="1234">
+///   <name>John Doe</name>
+///   <age>30</age>
+/// </person>
+/// ```
+pub fn json_to_xml_with_schema(json_string: &str, schema: &Schema, options: &ConversionOptions) -> Result<String, ConversionError> {
+    let json_value: Value = from_str(json_string)?;
+    let mut converter = SchemaConverter {
+        schema,
+        options,
+        writer: Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2),
+        violations: Vec::new(),
+        pointer: Vec::new(),
+    };
+
+    write_header(&mut converter.writer)?;
+
+    match find_root(schema, &json_value) {
+        Some((element, key, value)) => {
+            converter.pointer.extend(key.map(String::from));
+            converter.write_element(element, value, "", true)?;
+        },
+        None => converter.violations.push(Violation {
+            path: "/".to_string(),
+            kind: ViolationKind::UnknownRoot(root_keys(&json_value)),
+        }),
+    }
+
+    if !converter.violations.is_empty() {
+        return Err(ConversionError::Schema(converter.violations));
+    }
+
+    Ok(String::from_utf8(converter.writer.into_inner().into_inner())?)
+}
+
+struct SchemaConverter<'a> {
+    schema: &'a Schema,
+    options: &'a ConversionOptions,
+    writer: Writer<Cursor<Vec<u8>>>,
+    violations: Vec<Violation>,
+    /// The keys and indexes leading to the JSON value being written, for the paths of `xs:anyType` content and errors.
+    pointer: Vec<String>,
+}
+
+/// The fields of a JSON object, tracking which ones have been written.
+struct Fields<'v> {
+    entries: Vec<(&'v str, &'v Value, bool)>,
+    /// The fields were made up around a value that is not an object, so their keys are not part of the JSON path.
+    made_up: bool,
+}
+
+impl<'v> Fields<'v> {
+    fn new(map: &'v Map<String, Value>, made_up: bool) -> Self {
+        Fields { entries: map.iter().map(|(key, value)| (key.as_str(), value, false)).collect(), made_up }
+    }
+
+    /// Take the first unused field whose key matches `name`, ignoring case and an optional `@` prefix, with its key.
+    fn take(&mut self, name: &str, allow_attribute_prefix: bool) -> Option<(&'v str, &'v Value)> {
+        let entry = self.entries.iter_mut().find(|(key, _, used)| {
+            let key = if allow_attribute_prefix { key.strip_prefix('@').unwrap_or(key) } else { key };
+            !*used && key.eq_ignore_ascii_case(name)
+        })?;
+        entry.2 = true;
+        Some((entry.0, entry.1))
+    }
+
+    /// The first unused field whose key matches `name`, ignoring case, without taking it.
+    fn get(&self, name: &str) -> Option<&'v Value> {
+        self.entries.iter().find(|(key, _, used)| !*used && key.eq_ignore_ascii_case(name)).map(|(_, value, _)| *value)
+    }
+}
+
+impl SchemaConverter<'_> {
+    fn write_element(&mut self, element: &Element, value: &Value, parent_path: &str, is_root: bool) -> Result<(), ConversionError> {
+        let path = format!("{}/{}", parent_path, element.name);
+        let tag = self.tag(element, is_root);
+        let start = start_tag(&tag).into_owned();
+
+        match self.schema.resolve(&element.ty) {
+            ElementType::Simple(simple) => self.write_simple_element(start, simple, value, &path),
+            ElementType::Any => self.write_any_element(tag, value),
+            ty => match self.schema.complex_type(ty) {
+                Some(complex) => self.write_complex_element(start, element, complex, value, &path),
+                None => {
+                    self.violate(&path, ViolationKind::UnexpectedElement(element.name.clone()));
+                    Ok(())
+                },
+            },
+        }
+    }
+
+    /// The name and namespace declaration of an element, in the target namespace as `elementFormDefault` asks, declaring the namespace on the root.
+    fn tag(&self, element: &Element, is_root: bool) -> document::Element {
+        let Some(namespace) = &self.schema.target_namespace else {
+            return document::Element::new(element.name.as_str());
+        };
+
+        let is_global = is_root || matches!(element.ty, ElementType::Ref(_));
+        let mut tag = if is_global && !self.schema.elements_qualified {
+            document::Element::new(format!("{}:{}", TARGET_PREFIX, element.name))
+        } else {
+            document::Element::new(element.name.as_str())
+        };
+
+        if is_root {
+            let declaration = if self.schema.elements_qualified { "xmlns".to_string() } else { format!("xmlns:{}", TARGET_PREFIX) };
+            tag.push_attribute(&declaration, namespace.as_str());
+        }
+        tag
+    }
+
+    /// The JSON Pointer of the value being written, e.g. `/order/lines/0`.
+    fn json_path(&self) -> String {
+        self.pointer.iter().map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1"))).collect()
+    }
+
+    fn write_simple_element(&mut self, start: BytesStart, simple: &SimpleType, value: &Value, path: &str) -> Result<(), ConversionError> {
+        let value = match value {
+            Value::Object(map) => {
+                for key in map.keys().filter(|key| *key != "$text") {
+                    self.violate(path, ViolationKind::UnexpectedElement(key.clone()));
+                }
+                map.get("$text").unwrap_or(&Value::Null)
+            },
+            value => value,
+        };
+
+        let Some(text) = self.format_value(value, simple, path) else {
+            return Ok(());
+        };
+
+        write_start_tag(&mut self.writer, &start)?;
+        write_content(&mut self.writer, &text)?;
+        write_end_tag(&mut self.writer, &start.to_end())?;
+        Ok(())
+    }
+
+    fn write_complex_element(&mut self, mut start: BytesStart, element: &Element, complex: &ComplexType, value: &Value, path: &str) -> Result<(), ConversionError> {
+        let wrapped;
+        let map = match value {
+            Value::Object(map) => map,
+            Value::Array(_) => match only_repeated_child(complex) {
+                Some(child) => {
+                    wrapped = Map::from_iter([(child.name.clone(), value.clone())]);
+                    &wrapped
+                },
+                None => {
+                    self.violate(path, ViolationKind::TooManyElements { name: element.name.clone(), max: 1 });
+                    return Ok(());
+                },
+            },
+            Value::Null => &Map::new(),
+            scalar => {
+                wrapped = Map::from_iter([("$text".to_string(), scalar.clone())]);
+                &wrapped
+            },
+        };
+        let mut fields = Fields::new(map, !value.is_object());
+
+        for attribute in &complex.attributes {
+            match fields.take(&attribute.name, true) {
+                Some((_, value)) => {
+                    if let Some(text) = self.format_value(value, &attribute.ty, &format!("{}/@{}", path, attribute.name)) {
+                        start.push_attribute((attribute.name.as_str(), text.as_str()));
+                    }
+                },
+                None if attribute.required => self.violate(path, ViolationKind::MissingAttribute(attribute.name.clone())),
+                None => {},
+            }
+        }
+
+        // Namespace declarations are passed through, checked as `json_to_xml_with_root` checks them.
+        for entry in fields.entries.iter_mut().filter(|(key, _, _)| key.starts_with("@xmlns")) {
+            if let Some(namespace) = entry.1.as_str() {
+                let path = format!("{}/{}", self.json_path(), entry.0);
+                if !is_name(&entry.0[1..]) {
+                    return Err(ConversionError::InvalidName { path, name: entry.0[1..].to_string() });
+                }
+                check_text(namespace, &path)?;
+
+                start.push_attribute((&entry.0[1..], namespace));
+                entry.2 = true;
+            }
+        }
+
+        // The order of `$content` cannot be checked against the content model.
+        if fields.take("$content", false).is_some() {
+            self.violate(path, ViolationKind::Unsupported("$content".to_string()));
+        }
+
+        let text = fields.take("$text", false).map(|(_, text)| text);
+
+        match &complex.content {
+            Content::Empty if text.is_none() && fields.entries.iter().all(|(_, _, used)| *used) => {
+                write_empty_tag(&mut self.writer, &start)?;
+                return Ok(());
+            },
+            Content::Simple(simple) => {
+                let text = text.and_then(|text| self.format_value(text, simple, path)).unwrap_or_default();
+
+                write_start_tag(&mut self.writer, &start)?;
+                write_content(&mut self.writer, &text)?;
+            },
+            content => {
+                write_start_tag(&mut self.writer, &start)?;
+
+                if let Some(text) = text {
+                    match text.as_str() {
+                        Some(text) if complex.mixed => {
+                            check_text(text, &format!("{}/$text", self.json_path()))?;
+                            write_content(&mut self.writer, text)?;
+                        },
+                        _ => self.violate(path, ViolationKind::UnexpectedText),
+                    }
+                }
+
+                if let Content::Group(group) = content {
+                    self.write_group(group, &mut fields, path)?;
+                }
+            },
+        }
+
+        for (key, _, used) in &fields.entries {
+            if !used {
+                self.violate(path, ViolationKind::UnexpectedElement(key.to_string()));
+            }
+        }
+
+        write_end_tag(&mut self.writer, &start.to_end())?;
+        Ok(())
+    }
+
+    fn write_group(&mut self, group: &Group, fields: &mut Fields, path: &str) -> Result<(), ConversionError> {
+        if group.kind != GroupKind::Choice {
+            for particle in &group.particles {
+                self.write_particle(particle, fields, path, group.min_occurs > 0, Some(1))?;
+            }
+            return Ok(());
+        }
+
+        if group.max_occurs == Some(1) {
+            match group.particles.iter().find(|particle| particle_matches(particle, fields)) {
+                Some(particle) => self.write_particle(particle, fields, path, true, Some(1))?,
+                None if group.min_occurs > 0 => self.violate(path, ViolationKind::MissingElement(choice_names(group))),
+                None => {},
+            }
+            return Ok(());
+        }
+
+        // A repeated choice writes every alternative present, each item of an array counting as one occurrence.
+        let chosen: Vec<&Particle> = group.particles.iter().filter(|particle| particle_matches(particle, fields)).collect();
+        let occurrences: usize = chosen.iter().map(|particle| occurrences(particle, fields)).sum();
+
+        if let Some(max) = group.max_occurs.filter(|max| occurrences > *max as usize) {
+            self.violate(path, ViolationKind::TooManyElements { name: choice_names(group), max });
+        }
+        if occurrences < group.min_occurs as usize {
+            self.violate(path, ViolationKind::MissingElement(choice_names(group)));
+        }
+
+        for particle in chosen {
+            self.write_particle(particle, fields, path, true, group.max_occurs)?;
+        }
+        Ok(())
+    }
+
+    /// Write one particle of a group that occurs up to `repeats` times, so an element can repeat up to its own `maxOccurs` times that.
+    fn write_particle(&mut self, particle: &Particle, fields: &mut Fields, path: &str, required: bool, repeats: Option<u32>) -> Result<(), ConversionError> {
+        let element = match particle {
+            Particle::Element(element) => element,
+            Particle::Group(group) => return self.write_group(group, fields, path),
+        };
+
+        let (key, value) = match fields.take(&element.name, false) {
+            Some((_, Value::Null)) | None => {
+                if required && element.min_occurs > 0 {
+                    self.violate(path, ViolationKind::MissingElement(element.name.clone()));
+                }
+                return Ok(());
+            },
+            Some(field) => field,
+        };
+
+        if !fields.made_up {
+            self.pointer.push(key.to_string());
+        }
+        self.write_field(element, value, path, required, repeats)?;
+        if !fields.made_up {
+            self.pointer.pop();
+        }
+        Ok(())
+    }
+
+    /// Write the elements for the value of one field, repeated for the items of an array.
+    fn write_field(&mut self, element: &Element, value: &Value, path: &str, required: bool, repeats: Option<u32>) -> Result<(), ConversionError> {
+        let max_occurs = element.max_occurs.zip(repeats).map(|(max, repeats)| max.saturating_mul(repeats));
+
+        match value {
+            Value::Array(items) if max_occurs != Some(1) => {
+                if let Some(max) = max_occurs.filter(|max| items.len() > *max as usize) {
+                    self.violate(path, ViolationKind::TooManyElements { name: element.name.clone(), max });
+                }
+                if required && items.len() < element.min_occurs as usize {
+                    self.violate(path, ViolationKind::MissingElement(element.name.clone()));
+                }
+                for (index, item) in items.iter().enumerate() {
+                    self.pointer.push(index.to_string());
+                    self.write_element(element, item, path, false)?;
+                    self.pointer.pop();
+                }
+            },
+            value => self.write_element(element, value, path, false)?,
+        }
+        Ok(())
+    }
+
+    /// Write an `xs:anyType` element with the conventions of `generate_xml`, at the JSON path of its value so that names, text and rules are checked as there.
+    fn write_any_element(&mut self, tag: document::Element, value: &Value) -> Result<(), ConversionError> {
+        check_renames(self.options)?;
+
+        let mut elements = ElementWriter::new();
+        for segment in &self.pointer {
+            elements.enter(segment);
+        }
+
+        match value {
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    elements.enter(&index.to_string());
+                    write_declared_element(&mut elements, &tag.name, tag.namespaces.clone(), item, self.options)?;
+                    elements.leave();
+                }
+            },
+            value => write_declared_element(&mut elements, &tag.name, tag.namespaces, value, self.options)?,
+        }
+
+        write_nodes(&mut self.writer, &elements.finish()?)
+    }
+
+    /// Format a JSON value as the given simple type, recording a violation if it does not fit.
+    fn format_value(&mut self, value: &Value, simple: &SimpleType, path: &str) -> Option<String> {
+        let text = match (simple.base, value) {
+            (BuiltinType::Boolean, Value::Bool(b)) => match self.options.boolean_format {
+                BooleanFormat::OneZero => format_boolean(*b, &BooleanFormat::OneZero).to_string(),
+                _ => b.to_string(),
+            },
+            (BuiltinType::Integer, Value::Number(num)) => {
+                let plain = to_plain_notation(&num.to_string());
+                match plain.split_once('.') {
+                    Some((int_part, frac_part)) if frac_part.bytes().all(|d| d == b'0') => int_part.to_string(),
+                    _ => plain,
+                }
+            },
+            (BuiltinType::Decimal, Value::Number(num)) => {
+                let format = NumberFormat { plain_notation: true, ..self.options.number_format.clone() };
+                format_number(num, &format)
+            },
+            (_, Value::Number(num)) => format_number(num, &self.options.number_format),
+            (_, Value::Bool(b)) => format_boolean(*b, &self.options.boolean_format).to_string(),
+            (_, Value::String(s)) => s.clone(),
+            _ => String::new(),
+        };
+
+        if (value.is_string() || value.is_number() || value.is_boolean()) && simple.accepts(&text) {
+            return Some(text);
+        }
+
+        self.violate(path, ViolationKind::InvalidValue {
+            value: value.to_string(),
+            expected: simple.describe(),
+        });
+        None
+    }
+
+    fn violate(&mut self, path: &str, kind: ViolationKind) {
+        self.violations.push(Violation { path: path.to_string(), kind });
+    }
+}
+
+/// Find the global element to use as the document root, and the JSON value it holds.
+fn find_root<'a>(schema: &'a Schema, json: &'a Value) -> Option<(&'a Element, Option<&'a str>, &'a Value)> {
+    if let Some(map) = json.as_object().filter(|map| map.len() == 1) {
+        let (key, value) = map.iter().next().unwrap();
+
+        if let Some(element) = schema.elements.iter().find(|e| e.name.eq_ignore_ascii_case(key)) {
+            return Some((element, Some(key.as_str()), value));
+        }
+    }
+
+    match schema.elements.as_slice() {
+        [element] => Some((element, None, json)),
+        _ => None,
+    }
+}
+
+fn root_keys(json: &Value) -> String {
+    match json {
+        Value::Object(map) => map.keys().cloned().collect::<Vec<_>>().join(", "),
+        value => value.to_string(),
+    }
+}
+
+fn particle_matches(particle: &Particle, fields: &Fields) -> bool {
+    match particle {
+        Particle::Element(element) => fields.get(&element.name).is_some(),
+        Particle::Group(group) => group.particles.iter().any(|particle| particle_matches(particle, fields)),
+    }
+}
+
+/// How often a particle of a repeated choice occurs: once per item of an array, or once.
+fn occurrences(particle: &Particle, fields: &Fields) -> usize {
+    match particle {
+        Particle::Element(element) => match fields.get(&element.name) {
+            Some(Value::Array(items)) => items.len(),
+            _ => 1,
+        },
+        Particle::Group(_) => 1,
+    }
+}
+
+/// The element names of the alternatives of a choice, e.g. `email | phone`.
+fn choice_names(group: &Group) -> String {
+    let names: Vec<&str> = group.particles.iter().filter_map(|particle| match particle {
+        Particle::Element(element) => Some(element.name.as_str()),
+        Particle::Group(_) => None,
+    }).collect();
+    names.join(" | ")
+}
+
+/// The child element of a wrapper type whose only content is one repeated element.
+fn only_repeated_child(complex: &ComplexType) -> Option<&Element> {
+    match &complex.content {
+        Content::Group(group) => match group.particles.as_slice() {
+            [Particle::Element(element)] if element.max_occurs != Some(1) => Some(element),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,358 @@
+use crate::error::ConversionError;
+use crate::format::format_number;
+use crate::generate_xml::{capitalize_word, wraps_in_root};
+use crate::json_schema::Hints;
+use crate::options::{ConversionOptions, NumberFormat};
+use crate::xsd::{Attribute, BuiltinType, ComplexType, Content, Element, ElementType, Group, GroupKind, Particle, Schema, SimpleType};
+
+use serde_json::{Value, from_str};
+use std::collections::{BTreeMap, HashMap};
+
+/// # Infer an XML Schema from JSON samples.
+///
+/// This function takes one or more JSON samples and infers an XSD describing the XML that `json_to_xml_with_root` produces for them.
+/// Object keys become `xs:element`s, `@` keys become `xs:attribute`s and arrays map to `maxOccurs="unbounded"`.
+/// Leaf types are inferred from the JSON types, and fields missing from some samples become optional.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::xsd::infer_xsd;
+///
+/// let samples = [
+///     r#"{"@version": "1", "person": {"@id": 1, "name": "John Doe", "age": 30}}"#,
+///     r#"{"@version": "2", "person": {"@id": 2, "name": "Jane Doe", "emails": ["jane@example.com"]}}"#,
+/// ];
+///
+/// let xsd = infer_xsd(&samples, "People").unwrap();
+///
+/// println!("{}", xsd);
+/// ```
+///
+/// ## Expected Output (XSD):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" elementFormDefault="qualified">
+///   <xs:element name="People">
+///     <xs:complexType>
+///       <xs:sequence>
+///         <xs:element name="Person">
+///           <xs:complexType>
+///             <xs:sequence>
+///               <xs:element name="Age" minOccurs="0" type="xs:integer"/>
+///               <xs:element name="Emails" minOccurs="0">
+///                 <xs:complexType>
+///                   <xs:sequence>
+///                     <xs:element name="EmailsItem" maxOccurs="unbounded" type="xs:string"/>
+///                   </xs:sequence>
+///                 </xs:complexType>
+///               </xs:element>
+///               <xs:element name="Name" type="xs:string"/>
+///             </xs:sequence>
+///             <xs:attribute name="id" type="xs:integer" use="required"/>
+///           </xs:complexType>
+///         </xs:element>
+///       </xs:sequence>
+///       <xs:attribute name="version" type="xs:string" use="required"/>
+///     </xs:complexType>
+///   </xs:element>
+/// </xs:schema>
+/// ```
+///
+/// ## Notes:
+/// - The root element is declared for the samples the generator wraps in it, like `{"a": 1, "b": 2}` or `{"item": [{…}, {…}]}`,
+///   with the default `RootPolicy`. The keys of the other samples are declared as global elements.
+/// - Namespace declarations (`@xmlns:...`) and prefixed attributes are not declared in the schema.
+/// - Numbers in exponent notation, like `1e-7`, are `xs:double`, other non-integers `xs:decimal`.
+/// - Elements with `$content` are mixed, and the elements of their content a repeated `xs:choice`, as they may come in any order.
+/// - A field that is a number in one sample and a string in another is widened to `xs:string`.
+/// - `null` values are declared as the `<None/>` element that the generator writes for them. No `xsi:nil` is involved,
+///   also not with `TypeAnnotation::XsiType`.
+pub fn infer_xsd(samples: &[&str], root: &str) -> Result<String, ConversionError> {
+    let values = samples
+        .iter()
+        .map(|sample| from_str(sample))
+        .collect::<Result<Vec<Value>, _>>()?;
+
+    infer_schema(&values, root).to_xsd()
+}
+
+/// Infer a `Schema` from parsed JSON samples. See `infer_xsd`.
+pub fn infer_schema(samples: &[Value], root: &str) -> Schema {
+    let mut root_node = Node::default();
+    let mut top_level = Node::default();
+
+    for sample in samples {
+        match sample {
+            Value::Object(map) if !wraps_in_root(map, &ConversionOptions::default(), &Hints::default()).unwrap_or(true) => top_level.observe(sample),
+            _ => root_node.observe(sample),
+        }
+    }
+
+    let mut elements: Vec<Element> = top_level.children
+        .values()
+        .map(|child| Element::new(&child.name, child.node.element_type()))
+        .collect();
+
+    if root_node.instances > 0 || elements.is_empty() {
+        elements.insert(0, Element::new(&capitalize_word(root), root_node.element_type()));
+    }
+
+    Schema { elements, elements_qualified: true, ..Default::default() }
+}
+
+/// Everything observed about one element across all of its occurrences.
+#[derive(Default)]
+struct Node {
+    instances: usize,
+    any: bool,
+    /// Whether the element has `$content`, so its child elements may come in any order.
+    mixed: bool,
+    text: Option<BuiltinType>,
+    /// Attribute name to (type, number of instances having it).
+    attributes: BTreeMap<String, (BuiltinType, usize)>,
+    /// Child elements keyed by the JSON key, so they keep the generator's order.
+    children: BTreeMap<String, Child>,
+}
+
+struct Child {
+    name: String,
+    min: usize,
+    max: usize,
+    repeated: bool,
+    node: Node,
+}
+
+/// Child element counts of a single occurrence.
+#[derive(Default)]
+struct Instance {
+    counts: HashMap<String, usize>,
+}
+
+impl Node {
+    /// Record one occurrence of this element holding `value`.
+    fn observe(&mut self, value: &Value) {
+        let mut instance = Instance::default();
+        self.instances += 1;
+
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    if let Some(name) = key.strip_prefix("@") {
+                        if name == "xmlns" || name.contains(':') {
+                            continue;
+                        }
+                        if let Some(ty) = scalar_type(value) {
+                            self.observe_attribute(name, ty);
+                        }
+                    } else if key == "$text" {
+                        if let Some(ty) = scalar_type(value) {
+                            self.observe_text(ty);
+                        }
+                    } else if key == "$content" {
+                        self.observe_content(value, &mut instance);
+                    } else {
+                        self.observe_child(key, value, &mut instance);
+                    }
+                }
+            },
+            Value::Array(_) => self.any = true,
+            Value::Null => self.observe_null(&mut instance),
+            scalar => {
+                if let Some(ty) = scalar_type(scalar) {
+                    self.observe_text(ty);
+                }
+            },
+        }
+
+        self.record(instance);
+    }
+
+    /// Record one occurrence of a wrapper element holding the `<TagItem>` elements of an array.
+    fn observe_items(&mut self, item_key: &str, item_name: &str, arr: &[Value]) {
+        let mut instance = Instance::default();
+        self.instances += 1;
+
+        for value in arr {
+            let child = self.child(item_key, item_name);
+            child.repeated = true;
+            child.node.observe(value);
+        }
+        instance.counts.insert(item_key.to_string(), arr.len());
+
+        self.record(instance);
+    }
+
+    fn observe_child(&mut self, key: &str, value: &Value, instance: &mut Instance) {
+        let name = capitalize_word(key);
+
+        match value {
+            Value::Array(arr) if !arr.is_empty() && arr.iter().all(|v| v.is_object()) => {
+                let child = self.child(key, &name);
+                child.repeated = true;
+
+                let objects: Vec<&Value> = arr.iter().filter(|v| v.as_object().is_some_and(|m| !m.is_empty())).collect();
+                for object in &objects {
+                    child.node.observe(object);
+                }
+                *instance.counts.entry(key.to_string()).or_insert(0) += objects.len();
+            },
+            Value::Array(arr) => {
+                let item_key = format!("{}Item", key);
+                let item_name = format!("{}Item", name);
+
+                self.child(key, &name).node.observe_items(&item_key, &item_name, arr);
+                *instance.counts.entry(key.to_string()).or_insert(0) += 1;
+            },
+            _ => {
+                self.child(key, &name).node.observe(value);
+                *instance.counts.entry(key.to_string()).or_insert(0) += 1;
+            },
+        }
+    }
+
+    /// Record the mixed content of one occurrence: text, and elements that may come in any order.
+    fn observe_content(&mut self, content: &Value, instance: &mut Instance) {
+        self.mixed = true;
+
+        match content {
+            Value::Object(map) => {
+                for (key, value) in map {
+                    // The generator leaves attributes, text and content of the objects in mixed content out.
+                    if !key.starts_with('@') && key != "$text" && key != "$content" {
+                        self.observe_child(key, value, instance);
+                    }
+                }
+            },
+            Value::Array(items) => {
+                for item in items {
+                    self.observe_content(item, instance);
+                }
+            },
+            Value::Null => self.observe_null(instance),
+            scalar => {
+                if let Some(ty) = scalar_type(scalar) {
+                    self.observe_text(ty);
+                }
+            },
+        }
+    }
+
+    fn observe_null(&mut self, instance: &mut Instance) {
+        self.child(&null_key(), "None").node.instances += 1;
+        *instance.counts.entry(null_key()).or_insert(0) += 1;
+    }
+
+    fn observe_attribute(&mut self, name: &str, ty: BuiltinType) {
+        let entry = self.attributes.entry(name.to_string()).or_insert((ty, 0));
+        entry.0 = widen(entry.0, ty);
+        entry.1 += 1;
+    }
+
+    fn observe_text(&mut self, ty: BuiltinType) {
+        self.text = Some(self.text.map_or(ty, |text| widen(text, ty)));
+    }
+
+    fn child(&mut self, key: &str, name: &str) -> &mut Child {
+        let instances = self.instances;
+
+        self.children.entry(key.to_string()).or_insert_with(|| Child {
+            name: name.to_string(),
+            min: if instances > 1 { 0 } else { usize::MAX },
+            max: 0,
+            repeated: false,
+            node: Node::default(),
+        })
+    }
+
+    fn record(&mut self, instance: Instance) {
+        for (key, child) in self.children.iter_mut() {
+            let count = instance.counts.get(key).copied().unwrap_or(0);
+            child.min = child.min.min(count);
+            child.max = child.max.max(count);
+        }
+    }
+
+    fn element_type(&self) -> ElementType {
+        if self.any {
+            return ElementType::Any;
+        }
+
+        let attributes: Vec<Attribute> = self.attributes
+            .iter()
+            .map(|(name, (ty, count))| Attribute {
+                name: name.clone(),
+                ty: SimpleType::new(*ty),
+                required: *count == self.instances,
+            })
+            .collect();
+
+        if self.children.is_empty() {
+            return match (self.text, attributes.is_empty()) {
+                (Some(text), true) => ElementType::Simple(SimpleType::new(text)),
+                (text, _) => ElementType::Complex(ComplexType {
+                    mixed: false,
+                    attributes,
+                    content: text.map_or(Content::Empty, |text| Content::Simple(SimpleType::new(text))),
+                }),
+            };
+        }
+
+        let particles = self.children
+            .values()
+            .map(|child| {
+                let repeated = child.repeated || child.max > 1;
+
+                Particle::Element(Box::new(Element {
+                    name: child.name.clone(),
+                    // Array lengths in the samples say nothing about the lengths of other arrays.
+                    min_occurs: if self.mixed { 1 } else { child.min.min(child.max).min(if repeated { 1 } else { usize::MAX }) as u32 },
+                    max_occurs: if repeated && !self.mixed { None } else { Some(1) },
+                    ty: child.node.element_type(),
+                }))
+            })
+            .collect();
+
+        // Mixed content is a repeated choice, as its elements come in the order of the `$content` items.
+        let group = if self.mixed {
+            Group { kind: GroupKind::Choice, min_occurs: 0, max_occurs: None, particles }
+        } else {
+            Group { kind: GroupKind::Sequence, min_occurs: 1, max_occurs: Some(1), particles }
+        };
+
+        ElementType::Complex(ComplexType {
+            mixed: self.text.is_some() || self.mixed,
+            attributes,
+            content: Content::Group(group),
+        })
+    }
+}
+
+/// Key of the `<None/>` child written for `null`. A JSON key may be the same string, but NUL is not allowed in XML names,
+/// so the generator rejects such a key with `ConversionError::InvalidName` and it never needs an element of its own.
+fn null_key() -> String {
+    "\0None".to_string()
+}
+
+fn scalar_type(value: &Value) -> Option<BuiltinType> {
+    match value {
+        Value::String(_) => Some(BuiltinType::String),
+        Value::Number(num) if num.is_i64() || num.is_u64() => Some(BuiltinType::Integer),
+        // The generator writes numbers like `1e-7` as is, and only `xs:double` has an exponent.
+        Value::Number(num) if format_number(num, &NumberFormat::default()).contains(['e', 'E']) => Some(BuiltinType::Double),
+        Value::Number(_) => Some(BuiltinType::Decimal),
+        Value::Bool(_) => Some(BuiltinType::Boolean),
+        _ => None,
+    }
+}
+
+/// The narrowest type that accepts values of both types.
+fn widen(a: BuiltinType, b: BuiltinType) -> BuiltinType {
+    match (a, b) {
+        (a, b) if a == b => a,
+        (BuiltinType::Integer, BuiltinType::Decimal) | (BuiltinType::Decimal, BuiltinType::Integer) => BuiltinType::Decimal,
+        (BuiltinType::Integer | BuiltinType::Decimal, BuiltinType::Double) | (BuiltinType::Double, BuiltinType::Integer | BuiltinType::Decimal) => BuiltinType::Double,
+        _ => BuiltinType::String,
+    }
+}
@@ -0,0 +1,657 @@
+//! # XML Schema (XSD) support.
+//!
+//! A small XML Schema model, with functions to infer a schema from JSON samples (`infer_xsd`),
+//! to read an existing XSD (`parse_xsd`) and to convert JSON to XML guided by a schema (`json_to_xml_with_schema`).
+
+mod convert;
+mod infer;
+mod parse;
+mod tree;
+mod validate;
+
+pub use convert::json_to_xml_with_schema;
+pub use infer::{infer_schema, infer_xsd};
+pub use parse::parse_xsd;
+pub use validate::validate_xml;
+
+use crate::error::ConversionError;
+use crate::options::XS_NAMESPACE;
+use crate::xml_utils::{is_xml_char, write_declaration, write_start_tag, write_empty_tag, write_end_tag};
+
+use quick_xml::Writer;
+use quick_xml::events::{BytesEnd, BytesStart};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Cursor;
+use std::sync::LazyLock;
+
+/// An XML Schema with its global element declarations.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Schema {
+    /// The `targetNamespace` of the schema, if any.
+    pub target_namespace: Option<String>,
+    /// Whether local elements belong to the target namespace, from `elementFormDefault="qualified"`. Global elements always do.
+    pub elements_qualified: bool,
+    pub elements: Vec<Element>,
+    /// Named complex types, referenced by `ElementType::Named`.
+    pub complex_types: BTreeMap<String, ComplexType>,
+}
+
+/// An element declaration (`xs:element`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub min_occurs: u32,
+    /// `None` means `maxOccurs="unbounded"`.
+    pub max_occurs: Option<u32>,
+    pub ty: ElementType,
+}
+
+/// The type of an element declaration.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ElementType {
+    Simple(SimpleType),
+    Complex(ComplexType),
+    /// A named complex type from `Schema::complex_types`.
+    Named(String),
+    /// The type of a global element from `Schema::elements`, for element references (`ref`).
+    Ref(String),
+    /// `xs:anyType`, accepts any content.
+    Any,
+}
+
+/// A complex type (`xs:complexType`).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ComplexType {
+    /// Text is allowed between child elements.
+    pub mixed: bool,
+    pub attributes: Vec<Attribute>,
+    pub content: Content,
+}
+
+/// The content model of a complex type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Content {
+    /// No child elements and no text.
+    #[default]
+    Empty,
+    /// Text only (`xs:simpleContent`).
+    Simple(SimpleType),
+    /// Child elements (`xs:sequence`, `xs:choice` or `xs:all`).
+    Group(Group),
+}
+
+/// A model group of child elements.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    pub kind: GroupKind,
+    pub min_occurs: u32,
+    /// `None` means `maxOccurs="unbounded"`.
+    pub max_occurs: Option<u32>,
+    pub particles: Vec<Particle>,
+}
+
+/// The kind of a model group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    Sequence,
+    Choice,
+    All,
+}
+
+/// A member of a model group.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Particle {
+    Element(Box<Element>),
+    Group(Group),
+}
+
+/// An attribute declaration (`xs:attribute`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub name: String,
+    pub ty: SimpleType,
+    pub required: bool,
+}
+
+/// A simple type for text content and attribute values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimpleType {
+    pub base: BuiltinType,
+    /// Restrictions of the base type.
+    pub facets: Facets,
+}
+
+/// Constraining facets of an `xs:restriction`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Facets {
+    /// Allowed values. Empty means any value of the base type.
+    pub enumeration: Vec<String>,
+    /// Regular expressions the whole value must match.
+    pub patterns: Vec<Pattern>,
+    pub min_inclusive: Option<f64>,
+    pub max_inclusive: Option<f64>,
+    pub min_exclusive: Option<f64>,
+    pub max_exclusive: Option<f64>,
+    pub length: Option<usize>,
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    pub total_digits: Option<usize>,
+    pub fraction_digits: Option<usize>,
+}
+
+/// An `xs:pattern` regular expression, compiled when it is read.
+///
+/// The XSD name escapes `\i`, `\I`, `\c` and `\C` are supported. Patterns compare equal when they are written the same.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+/// The characters of `\i`, which can start an XML name.
+const NAME_START_CHARS: &str = r":A-Z_a-z\x{C0}-\x{D6}\x{D8}-\x{F6}\x{F8}-\x{2FF}\x{370}-\x{37D}\x{37F}-\x{1FFF}\x{200C}-\x{200D}\x{2070}-\x{218F}\x{2C00}-\x{2FEF}\x{3001}-\x{D7FF}\x{F900}-\x{FDCF}\x{FDF0}-\x{FFFD}\x{10000}-\x{EFFFF}";
+/// The characters `\c` adds to `\i`, which can continue an XML name.
+const NAME_CHARS: &str = r"\-.0-9\x{B7}\x{300}-\x{36F}\x{203F}-\x{2040}";
+
+impl Pattern {
+    /// Compile an XSD pattern, which must match the whole value.
+    ///
+    /// ## Returns:
+    /// A `Result` which is either the `Pattern`, or a `ConversionError::InvalidSchema` if the regular expression does not compile.
+    pub fn new(source: &str) -> Result<Pattern, ConversionError> {
+        let mut translated = String::new();
+        let mut chars = source.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                translated.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('i') => translated.push_str(&format!("[{}]", NAME_START_CHARS)),
+                Some('I') => translated.push_str(&format!("[^{}]", NAME_START_CHARS)),
+                Some('c') => translated.push_str(&format!("[{}{}]", NAME_START_CHARS, NAME_CHARS)),
+                Some('C') => translated.push_str(&format!("[^{}{}]", NAME_START_CHARS, NAME_CHARS)),
+                Some(escaped) => {
+                    translated.push('\\');
+                    translated.push(escaped);
+                },
+                None => translated.push('\\'),
+            }
+        }
+
+        let regex = Regex::new(&format!("^(?:{})$", translated))
+            .map_err(|_| ConversionError::InvalidSchema(format!("invalid pattern {}", source)))?;
+
+        Ok(Pattern { source: source.to_string(), regex })
+    }
+
+    /// The pattern as written in the schema.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the whole of `text` matches the pattern.
+    pub fn is_match(&self, text: &str) -> bool {
+        self.regex.is_match(text)
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+/// The built-in XML Schema types.
+///
+/// Derived built-in types are mapped to their closest primitive, e.g. `xs:int` to `Integer` and `xs:token` to `String`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinType {
+    String,
+    Integer,
+    Decimal,
+    Double,
+    Boolean,
+    Date,
+    DateTime,
+    Time,
+}
+
+static INTEGER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[+-]?[0-9]+$").unwrap());
+static DECIMAL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)$").unwrap());
+static DOUBLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)([eE][+-]?[0-9]+)?|-?INF|NaN)$").unwrap());
+static DATE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-?[0-9]{4,}-[0-9]{2}-[0-9]{2}(Z|[+-][0-9]{2}:[0-9]{2})?$").unwrap());
+static DATE_TIME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^-?[0-9]{4,}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?$").unwrap());
+static TIME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?$").unwrap());
+
+impl BuiltinType {
+    /// The qualified name of the type, e.g. `xs:integer`.
+    pub fn qualified_name(&self) -> &'static str {
+        match self {
+            BuiltinType::String => "xs:string",
+            BuiltinType::Integer => "xs:integer",
+            BuiltinType::Decimal => "xs:decimal",
+            BuiltinType::Double => "xs:double",
+            BuiltinType::Boolean => "xs:boolean",
+            BuiltinType::Date => "xs:date",
+            BuiltinType::DateTime => "xs:dateTime",
+            BuiltinType::Time => "xs:time",
+        }
+    }
+
+    /// Look up a built-in type by its local name, e.g. `integer` or `nonNegativeInteger`.
+    pub fn from_local_name(name: &str) -> Option<BuiltinType> {
+        let ty = match name {
+            "string" | "normalizedString" | "token" | "language" | "Name" | "NCName" | "NMTOKEN" | "NMTOKENS"
+            | "ID" | "IDREF" | "IDREFS" | "ENTITY" | "ENTITIES" | "anyURI" | "QName" | "NOTATION" | "anySimpleType"
+            | "base64Binary" | "hexBinary" | "duration" | "gYear" | "gYearMonth" | "gMonth" | "gMonthDay" | "gDay" => BuiltinType::String,
+            "integer" | "long" | "int" | "short" | "byte" | "nonNegativeInteger" | "positiveInteger" | "nonPositiveInteger"
+            | "negativeInteger" | "unsignedLong" | "unsignedInt" | "unsignedShort" | "unsignedByte" => BuiltinType::Integer,
+            "decimal" => BuiltinType::Decimal,
+            "double" | "float" => BuiltinType::Double,
+            "boolean" => BuiltinType::Boolean,
+            "date" => BuiltinType::Date,
+            "dateTime" => BuiltinType::DateTime,
+            "time" => BuiltinType::Time,
+            _ => return None,
+        };
+        Some(ty)
+    }
+
+    /// Check that `text` is in the lexical space of the type.
+    pub fn accepts(&self, text: &str) -> bool {
+        let text = text.trim();
+
+        match self {
+            BuiltinType::String => true,
+            BuiltinType::Integer => INTEGER.is_match(text),
+            BuiltinType::Decimal => DECIMAL.is_match(text),
+            BuiltinType::Double => DOUBLE.is_match(text),
+            BuiltinType::Boolean => matches!(text, "true" | "false" | "1" | "0"),
+            BuiltinType::Date => DATE.is_match(text),
+            BuiltinType::DateTime => DATE_TIME.is_match(text),
+            BuiltinType::Time => TIME.is_match(text),
+        }
+    }
+}
+
+impl SimpleType {
+    pub fn new(base: BuiltinType) -> Self {
+        SimpleType { base, facets: Facets::default() }
+    }
+
+    /// Check that `text` is a valid value of this type, including its facets. No type accepts characters XML does not allow.
+    pub fn accepts(&self, text: &str) -> bool {
+        text.chars().all(is_xml_char) && self.base.accepts(text) && self.facets.accepts(text.trim())
+    }
+
+    /// Describe the type for error messages, e.g. `xs:string (enumeration: a, b)`.
+    pub fn describe(&self) -> String {
+        let facets = &self.facets;
+        let mut parts = Vec::new();
+
+        if !facets.enumeration.is_empty() {
+            parts.push(format!("enumeration: {}", facets.enumeration.join(", ")));
+        }
+        for pattern in &facets.patterns {
+            parts.push(format!("pattern: {}", pattern.as_str()));
+        }
+        for (name, bound) in [("minInclusive", facets.min_inclusive), ("maxInclusive", facets.max_inclusive), ("minExclusive", facets.min_exclusive), ("maxExclusive", facets.max_exclusive)] {
+            if let Some(bound) = bound {
+                parts.push(format!("{}: {}", name, bound));
+            }
+        }
+        for (name, limit) in [("length", facets.length), ("minLength", facets.min_length), ("maxLength", facets.max_length), ("totalDigits", facets.total_digits), ("fractionDigits", facets.fraction_digits)] {
+            if let Some(limit) = limit {
+                parts.push(format!("{}: {}", name, limit));
+            }
+        }
+
+        if parts.is_empty() {
+            self.base.qualified_name().to_string()
+        } else {
+            format!("{} ({})", self.base.qualified_name(), parts.join("; "))
+        }
+    }
+}
+
+impl Facets {
+    pub fn is_empty(&self) -> bool {
+        self == &Facets::default()
+    }
+
+    /// Check a value against the facets. The value must already be in the lexical space of the base type.
+    pub fn accepts(&self, text: &str) -> bool {
+        if !self.enumeration.is_empty() && !self.enumeration.iter().any(|value| value == text) {
+            return false;
+        }
+
+        if !self.patterns.iter().all(|pattern| pattern.is_match(text)) {
+            return false;
+        }
+
+        let length = text.chars().count();
+        if self.length.is_some_and(|l| length != l) || self.min_length.is_some_and(|l| length < l) || self.max_length.is_some_and(|l| length > l) {
+            return false;
+        }
+
+        if self.total_digits.is_some() || self.fraction_digits.is_some() {
+            let digits = text.trim_start_matches(['+', '-']);
+            let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+            let int_part = int_part.trim_start_matches('0');
+            let frac_part = frac_part.trim_end_matches('0');
+
+            if self.total_digits.is_some_and(|t| int_part.len() + frac_part.len() > t) || self.fraction_digits.is_some_and(|f| frac_part.len() > f) {
+                return false;
+            }
+        }
+
+        let bounded = self.min_inclusive.is_some() || self.max_inclusive.is_some() || self.min_exclusive.is_some() || self.max_exclusive.is_some();
+        if bounded {
+            let Ok(number) = text.parse::<f64>() else {
+                return false;
+            };
+            if self.min_inclusive.is_some_and(|b| number < b)
+                || self.max_inclusive.is_some_and(|b| number > b)
+                || self.min_exclusive.is_some_and(|b| number <= b)
+                || self.max_exclusive.is_some_and(|b| number >= b)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+impl Element {
+    pub fn new(name: &str, ty: ElementType) -> Self {
+        Element { name: name.to_string(), min_occurs: 1, max_occurs: Some(1), ty }
+    }
+}
+
+/// A place where a JSON document or an XML document does not match a schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Path of the element, e.g. `/People/Person/Age`.
+    pub path: String,
+    pub kind: ViolationKind,
+}
+
+/// What does not match the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// The document root does not match any global element of the schema.
+    UnknownRoot(String),
+    /// A required element is missing.
+    MissingElement(String),
+    /// An element or JSON field is not allowed here.
+    UnexpectedElement(String),
+    /// An element occurs more often than `maxOccurs` allows.
+    TooManyElements { name: String, max: u32 },
+    /// A required attribute is missing.
+    MissingAttribute(String),
+    /// An attribute is not declared for this element.
+    UnexpectedAttribute(String),
+    /// Text content is not allowed in this element.
+    UnexpectedText,
+    /// A JSON field that the conversion cannot map to the schema, such as `$content`.
+    Unsupported(String),
+    /// A value does not match its declared simple type.
+    InvalidValue { value: String, expected: String },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            ViolationKind::UnknownRoot(name) => write!(f, "{}: `{}` is not a global element of the schema", self.path, name),
+            ViolationKind::MissingElement(name) => write!(f, "{}: missing required element `{}`", self.path, name),
+            ViolationKind::UnexpectedElement(name) => write!(f, "{}: unexpected element `{}`", self.path, name),
+            ViolationKind::TooManyElements { name, max } => write!(f, "{}: element `{}` occurs more than {} times", self.path, name, max),
+            ViolationKind::MissingAttribute(name) => write!(f, "{}: missing required attribute `{}`", self.path, name),
+            ViolationKind::UnexpectedAttribute(name) => write!(f, "{}: unexpected attribute `{}`", self.path, name),
+            ViolationKind::UnexpectedText => write!(f, "{}: text content is not allowed", self.path),
+            ViolationKind::Unsupported(name) => write!(f, "{}: `{}` is not supported with a schema", self.path, name),
+            ViolationKind::InvalidValue { value, expected } => write!(f, "{}: `{}` is not a valid {}", self.path, value, expected),
+        }
+    }
+}
+
+impl Schema {
+    /// Look up the complex type of an element, resolving named types and element references.
+    pub fn complex_type<'a>(&'a self, ty: &'a ElementType) -> Option<&'a ComplexType> {
+        match self.resolve(ty) {
+            ElementType::Complex(complex) => Some(complex),
+            ElementType::Named(name) => self.complex_types.get(name),
+            _ => None,
+        }
+    }
+
+    /// Resolve an element reference to the type of the global element it refers to.
+    /// Unknown references resolve to `ElementType::Any`, other types to themselves.
+    pub fn resolve<'a>(&'a self, ty: &'a ElementType) -> &'a ElementType {
+        match ty {
+            ElementType::Ref(name) => self.elements
+                .iter()
+                .find(|element| &element.name == name && !matches!(element.ty, ElementType::Ref(_)))
+                .map_or(&ElementType::Any, |element| &element.ty),
+            ty => ty,
+        }
+    }
+
+    /// Write the schema as an XSD document.
+    pub fn to_xsd(&self) -> Result<String, ConversionError> {
+        let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+
+        write_declaration(&mut writer, "1.0", Some("UTF-8"))?;
+
+        let mut schema = BytesStart::new("xs:schema");
+        schema.push_attribute(("xmlns:xs", XS_NAMESPACE));
+
+        if let Some(namespace) = &self.target_namespace {
+            schema.push_attribute(("xmlns", namespace.as_str()));
+            schema.push_attribute(("targetNamespace", namespace.as_str()));
+        }
+
+        schema.push_attribute(("elementFormDefault", if self.elements_qualified { "qualified" } else { "unqualified" }));
+        write_start_tag(&mut writer, &schema)?;
+
+        for element in &self.elements {
+            write_element(&mut writer, element, true)?;
+        }
+
+        for (name, complex) in &self.complex_types {
+            write_complex_type(&mut writer, complex, Some(name))?;
+        }
+
+        write_end_tag(&mut writer, &BytesEnd::new("xs:schema"))?;
+
+        Ok(String::from_utf8(writer.into_inner().into_inner())?)
+    }
+}
+
+fn write_element(writer: &mut Writer<Cursor<Vec<u8>>>, element: &Element, global: bool) -> Result<(), ConversionError> {
+    let mut start = BytesStart::new("xs:element");
+
+    if let ElementType::Ref(name) = &element.ty {
+        start.push_attribute(("ref", name.as_str()));
+    } else {
+        start.push_attribute(("name", element.name.as_str()));
+    }
+
+    if !global {
+        push_occurs(&mut start, element.min_occurs, element.max_occurs);
+    }
+
+    match &element.ty {
+        ElementType::Simple(simple) if simple.facets.is_empty() => {
+            start.push_attribute(("type", simple.base.qualified_name()));
+            write_empty_tag(writer, &start)?;
+        },
+        ElementType::Simple(simple) => {
+            write_start_tag(writer, &start)?;
+            write_simple_type(writer, simple)?;
+            write_end_tag(writer, &BytesEnd::new("xs:element"))?;
+        },
+        ElementType::Named(name) => {
+            start.push_attribute(("type", name.as_str()));
+            write_empty_tag(writer, &start)?;
+        },
+        ElementType::Ref(_) => {
+            write_empty_tag(writer, &start)?;
+        },
+        ElementType::Any => {
+            start.push_attribute(("type", "xs:anyType"));
+            write_empty_tag(writer, &start)?;
+        },
+        ElementType::Complex(complex) => {
+            write_start_tag(writer, &start)?;
+            write_complex_type(writer, complex, None)?;
+            write_end_tag(writer, &BytesEnd::new("xs:element"))?;
+        },
+    }
+    Ok(())
+}
+
+fn write_complex_type(writer: &mut Writer<Cursor<Vec<u8>>>, complex: &ComplexType, name: Option<&str>) -> Result<(), ConversionError> {
+    let mut start = BytesStart::new("xs:complexType");
+
+    if let Some(name) = name {
+        start.push_attribute(("name", name));
+    }
+
+    if complex.mixed {
+        start.push_attribute(("mixed", "true"));
+    }
+
+    if complex.attributes.is_empty() && complex.content == Content::Empty {
+        write_empty_tag(writer, &start)?;
+        return Ok(());
+    }
+
+    write_start_tag(writer, &start)?;
+
+    match &complex.content {
+        Content::Empty => {
+            write_attributes(writer, &complex.attributes)?;
+        },
+        Content::Simple(simple) => {
+            let mut extension = BytesStart::new("xs:extension");
+            extension.push_attribute(("base", simple.base.qualified_name()));
+
+            write_start_tag(writer, &BytesStart::new("xs:simpleContent"))?;
+
+            if complex.attributes.is_empty() {
+                write_empty_tag(writer, &extension)?;
+            } else {
+                write_start_tag(writer, &extension)?;
+                write_attributes(writer, &complex.attributes)?;
+                write_end_tag(writer, &BytesEnd::new("xs:extension"))?;
+            }
+
+            write_end_tag(writer, &BytesEnd::new("xs:simpleContent"))?;
+        },
+        Content::Group(group) => {
+            write_group(writer, group)?;
+            write_attributes(writer, &complex.attributes)?;
+        },
+    }
+
+    write_end_tag(writer, &BytesEnd::new("xs:complexType"))?;
+    Ok(())
+}
+
+fn write_group(writer: &mut Writer<Cursor<Vec<u8>>>, group: &Group) -> Result<(), ConversionError> {
+    let tag = match group.kind {
+        GroupKind::Sequence => "xs:sequence",
+        GroupKind::Choice => "xs:choice",
+        GroupKind::All => "xs:all",
+    };
+    let mut start = BytesStart::new(tag);
+    push_occurs(&mut start, group.min_occurs, group.max_occurs);
+
+    write_start_tag(writer, &start)?;
+
+    for particle in &group.particles {
+        match particle {
+            Particle::Element(element) => write_element(writer, element, false)?,
+            Particle::Group(group) => write_group(writer, group)?,
+        }
+    }
+
+    write_end_tag(writer, &BytesEnd::new(tag))?;
+    Ok(())
+}
+
+fn write_attributes(writer: &mut Writer<Cursor<Vec<u8>>>, attributes: &[Attribute]) -> Result<(), ConversionError> {
+    for attribute in attributes {
+        let mut start = BytesStart::new("xs:attribute");
+        start.push_attribute(("name", attribute.name.as_str()));
+
+        if attribute.ty.facets.is_empty() {
+            start.push_attribute(("type", attribute.ty.base.qualified_name()));
+        }
+
+        if attribute.required {
+            start.push_attribute(("use", "required"));
+        }
+
+        if attribute.ty.facets.is_empty() {
+            write_empty_tag(writer, &start)?;
+        } else {
+            write_start_tag(writer, &start)?;
+            write_simple_type(writer, &attribute.ty)?;
+            write_end_tag(writer, &BytesEnd::new("xs:attribute"))?;
+        }
+    }
+    Ok(())
+}
+
+fn write_simple_type(writer: &mut Writer<Cursor<Vec<u8>>>, simple: &SimpleType) -> Result<(), ConversionError> {
+    let facets = &simple.facets;
+    let mut restriction = BytesStart::new("xs:restriction");
+    restriction.push_attribute(("base", simple.base.qualified_name()));
+
+    write_start_tag(writer, &BytesStart::new("xs:simpleType"))?;
+    write_start_tag(writer, &restriction)?;
+
+    let mut values: Vec<(&str, String)> = Vec::new();
+    values.extend(facets.enumeration.iter().map(|value| ("xs:enumeration", value.clone())));
+    values.extend(facets.patterns.iter().map(|pattern| ("xs:pattern", pattern.as_str().to_string())));
+
+    for (tag, bound) in [("xs:minInclusive", facets.min_inclusive), ("xs:maxInclusive", facets.max_inclusive), ("xs:minExclusive", facets.min_exclusive), ("xs:maxExclusive", facets.max_exclusive)] {
+        if let Some(bound) = bound {
+            values.push((tag, bound.to_string()));
+        }
+    }
+    for (tag, limit) in [("xs:length", facets.length), ("xs:minLength", facets.min_length), ("xs:maxLength", facets.max_length), ("xs:totalDigits", facets.total_digits), ("xs:fractionDigits", facets.fraction_digits)] {
+        if let Some(limit) = limit {
+            values.push((tag, limit.to_string()));
+        }
+    }
+
+    for (tag, value) in values {
+        let mut facet = BytesStart::new(tag);
+        facet.push_attribute(("value", value.as_str()));
+        write_empty_tag(writer, &facet)?;
+    }
+
+    write_end_tag(writer, &BytesEnd::new("xs:restriction"))?;
+    write_end_tag(writer, &BytesEnd::new("xs:simpleType"))?;
+    Ok(())
+}
+
+fn push_occurs(start: &mut BytesStart, min_occurs: u32, max_occurs: Option<u32>) {
+    if min_occurs != 1 {
+        start.push_attribute(("minOccurs", min_occurs.to_string().as_str()));
+    }
+
+    match max_occurs {
+        Some(1) => {},
+        Some(max) => start.push_attribute(("maxOccurs", max.to_string().as_str())),
+        None => start.push_attribute(("maxOccurs", "unbounded")),
+    }
+}
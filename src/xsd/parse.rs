@@ -0,0 +1,408 @@
+use crate::error::ConversionError;
+use crate::xsd::tree::{XmlNode, strip_prefix};
+use crate::xsd::{Attribute, BuiltinType, ComplexType, Content, Element, ElementType, Facets, Group, GroupKind, Particle, Pattern, Schema, SimpleType};
+
+use std::collections::{HashMap, HashSet};
+
+/// # Parse an XSD document into a `Schema`.
+///
+/// Supports global and local element declarations, element references, named and anonymous complex types,
+/// `xs:sequence`, `xs:choice` and `xs:all` with `minOccurs`/`maxOccurs`, attributes, `xs:simpleContent`,
+/// `xs:complexContent` extensions and simple types restricting the built-in types with enumerations, patterns,
+/// value bounds, lengths and digit counts.
+///
+/// Element references become `ElementType::Ref`, so recursive elements are resolved once and refer back to themselves.
+/// A type derived from itself, directly or through other types, returns `ConversionError::InvalidSchema`.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::xsd::parse_xsd;
+///
+/// let xsd = r#"
+/// <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+///   <xs:element name="Person">
+///     <xs:complexType>
+///       <xs:sequence>
+///         <xs:element name="Name" type="xs:string"/>
+///         <xs:element name="Age" type="xs:integer" minOccurs="0"/>
+///       </xs:sequence>
+///       <xs:attribute name="id" type="xs:string" use="required"/>
+///     </xs:complexType>
+///   </xs:element>
+/// </xs:schema>
+/// "#;
+///
+/// let schema = parse_xsd(xsd).unwrap();
+///
+/// assert_eq!(schema.elements[0].name, "Person");
+/// ```
+pub fn parse_xsd(xsd: &str) -> Result<Schema, ConversionError> {
+    let root = XmlNode::parse(xsd)?;
+
+    if root.local_name() != "schema" {
+        return Err(ConversionError::InvalidSchema(format!("expected xs:schema, found {}", root.name)));
+    }
+
+    let mut parser = Parser::new(&root);
+    let elements_qualified = match root.attribute("elementFormDefault").map(str::trim) {
+        Some("qualified") => true,
+        Some("unqualified") | None => false,
+        Some(value) => return Err(ConversionError::InvalidSchema(format!("invalid elementFormDefault {}", value))),
+    };
+    let mut schema = Schema {
+        target_namespace: root.attribute("targetNamespace").map(String::from),
+        elements_qualified,
+        ..Default::default()
+    };
+
+    for name in parser.complex_nodes.keys().copied().collect::<Vec<_>>() {
+        let complex = parser.named_complex_type(name)?;
+        schema.complex_types.insert(name.to_string(), complex);
+    }
+
+    // Named simple types are resolved where they are used, but read here too, so errors in unused ones are reported.
+    for name in parser.simple_nodes.keys().copied().collect::<Vec<_>>() {
+        parser.simple_type_reference(name)?;
+    }
+
+    for node in root.children.iter().filter(|n| n.local_name() == "element") {
+        schema.elements.push(parser.element(node)?);
+    }
+
+    Ok(schema)
+}
+
+struct Parser<'a> {
+    element_nodes: HashMap<&'a str, &'a XmlNode>,
+    complex_nodes: HashMap<&'a str, &'a XmlNode>,
+    simple_nodes: HashMap<&'a str, &'a XmlNode>,
+    /// The named types being resolved, to detect types derived from themselves.
+    resolving: HashSet<&'a str>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(root: &'a XmlNode) -> Self {
+        let mut parser = Parser {
+            element_nodes: HashMap::new(),
+            complex_nodes: HashMap::new(),
+            simple_nodes: HashMap::new(),
+            resolving: HashSet::new(),
+        };
+
+        for node in &root.children {
+            let Some(name) = node.attribute("name") else {
+                continue;
+            };
+
+            match node.local_name() {
+                "element" => parser.element_nodes.insert(name, node),
+                "complexType" => parser.complex_nodes.insert(name, node),
+                "simpleType" => parser.simple_nodes.insert(name, node),
+                _ => None,
+            };
+        }
+
+        parser
+    }
+
+    fn element(&mut self, node: &'a XmlNode) -> Result<Element, ConversionError> {
+        let min_occurs = parse_min_occurs(node)?;
+        let max_occurs = parse_max_occurs(node)?;
+
+        if let Some(reference) = node.attribute("ref") {
+            let name = strip_prefix(reference);
+
+            if !self.element_nodes.contains_key(name) {
+                return Err(ConversionError::InvalidSchema(format!("unknown element reference {}", reference)));
+            }
+
+            return Ok(Element { name: name.to_string(), min_occurs, max_occurs, ty: ElementType::Ref(name.to_string()) });
+        }
+
+        let name = node
+            .attribute("name")
+            .ok_or_else(|| ConversionError::InvalidSchema("xs:element without name or ref".to_string()))?;
+
+        let ty = if let Some(type_name) = node.attribute("type") {
+            self.type_reference(type_name)?
+        } else if let Some(complex) = node.children.iter().find(|n| n.local_name() == "complexType") {
+            ElementType::Complex(self.complex_type(complex)?)
+        } else if let Some(simple) = node.children.iter().find(|n| n.local_name() == "simpleType") {
+            ElementType::Simple(self.simple_type(simple)?)
+        } else {
+            ElementType::Any
+        };
+
+        Ok(Element { name: name.to_string(), min_occurs, max_occurs, ty })
+    }
+
+    fn type_reference(&mut self, type_name: &str) -> Result<ElementType, ConversionError> {
+        let local = strip_prefix(type_name);
+
+        if local == "anyType" {
+            return Ok(ElementType::Any);
+        }
+        if self.complex_nodes.contains_key(local) {
+            return Ok(ElementType::Named(local.to_string()));
+        }
+
+        Ok(ElementType::Simple(self.simple_type_reference(type_name)?))
+    }
+
+    fn simple_type_reference(&mut self, type_name: &str) -> Result<SimpleType, ConversionError> {
+        let local = strip_prefix(type_name);
+
+        if let Some((&name, &node)) = self.simple_nodes.get_key_value(local) {
+            self.enter_type(name)?;
+            let simple = self.simple_type(node);
+            self.resolving.remove(name);
+            return simple;
+        }
+
+        let base = BuiltinType::from_local_name(local)
+            .ok_or_else(|| ConversionError::InvalidSchema(format!("unknown type {}", type_name)))?;
+
+        Ok(SimpleType { base, facets: builtin_facets(local) })
+    }
+
+    fn simple_type(&mut self, node: &'a XmlNode) -> Result<SimpleType, ConversionError> {
+        let Some(restriction) = node.children.iter().find(|n| n.local_name() == "restriction") else {
+            // Lists and unions are treated as plain strings.
+            return Ok(SimpleType::new(BuiltinType::String));
+        };
+
+        let mut simple = if let Some(base) = restriction.attribute("base") {
+            self.simple_type_reference(base)?
+        } else if let Some(inline) = restriction.children.iter().find(|n| n.local_name() == "simpleType") {
+            self.simple_type(inline)?
+        } else {
+            SimpleType::new(BuiltinType::String)
+        };
+
+        let facets = &mut simple.facets;
+        let mut enumeration = Vec::new();
+        let mut patterns = Vec::new();
+
+        for facet in &restriction.children {
+            let Some(value) = facet.attribute("value") else {
+                continue;
+            };
+
+            match facet.local_name() {
+                "enumeration" => enumeration.push(value.to_string()),
+                "pattern" => patterns.push(value.to_string()),
+                "minInclusive" => facets.min_inclusive = Some(parse_facet(facet, value)?),
+                "maxInclusive" => facets.max_inclusive = Some(parse_facet(facet, value)?),
+                "minExclusive" => facets.min_exclusive = Some(parse_facet(facet, value)?),
+                "maxExclusive" => facets.max_exclusive = Some(parse_facet(facet, value)?),
+                "length" => facets.length = Some(parse_facet(facet, value)?),
+                "minLength" => facets.min_length = Some(parse_facet(facet, value)?),
+                "maxLength" => facets.max_length = Some(parse_facet(facet, value)?),
+                "totalDigits" => facets.total_digits = Some(parse_facet(facet, value)?),
+                "fractionDigits" => facets.fraction_digits = Some(parse_facet(facet, value)?),
+                _ => {},
+            }
+        }
+
+        // Enumerations replace the ones of the base type. Patterns of one step are alternatives,
+        // while the patterns of every derivation step must all match.
+        if !enumeration.is_empty() {
+            facets.enumeration = enumeration;
+        }
+        if !patterns.is_empty() {
+            facets.patterns.push(Pattern::new(&patterns.join("|"))?);
+        }
+
+        Ok(simple)
+    }
+
+    /// Resolve the named complex type `name`, which must be declared.
+    fn named_complex_type(&mut self, name: &'a str) -> Result<ComplexType, ConversionError> {
+        self.enter_type(name)?;
+        let complex = self.complex_type(self.complex_nodes[name]);
+        self.resolving.remove(name);
+        complex
+    }
+
+    /// Mark the named type `name` as being resolved, failing if it already is: the type is derived from itself.
+    fn enter_type(&mut self, name: &'a str) -> Result<(), ConversionError> {
+        if !self.resolving.insert(name) {
+            return Err(ConversionError::InvalidSchema(format!("type {} is derived from itself", name)));
+        }
+        Ok(())
+    }
+
+    fn complex_type(&mut self, node: &'a XmlNode) -> Result<ComplexType, ConversionError> {
+        let mut complex = ComplexType {
+            mixed: node.attribute("mixed") == Some("true"),
+            ..Default::default()
+        };
+
+        for child in &node.children {
+            match child.local_name() {
+                "sequence" | "choice" | "all" => complex.content = Content::Group(self.group(child)?),
+                "attribute" => complex.attributes.extend(self.attribute(child)?),
+                "simpleContent" => self.simple_content(child, &mut complex)?,
+                "complexContent" => self.complex_content(child, &mut complex)?,
+                _ => {},
+            }
+        }
+
+        Ok(complex)
+    }
+
+    fn simple_content(&mut self, node: &'a XmlNode, complex: &mut ComplexType) -> Result<(), ConversionError> {
+        for derivation in node.children.iter().filter(|n| matches!(n.local_name(), "extension" | "restriction")) {
+            if let Some(base) = derivation.attribute("base") {
+                let base_type = if let Some((&base_name, _)) = self.complex_nodes.get_key_value(strip_prefix(base)) {
+                    let base_complex = self.named_complex_type(base_name)?;
+                    complex.attributes.extend(base_complex.attributes);
+
+                    match base_complex.content {
+                        Content::Simple(simple) => simple,
+                        _ => SimpleType::new(BuiltinType::String),
+                    }
+                } else {
+                    self.simple_type_reference(base)?
+                };
+                complex.content = Content::Simple(base_type);
+            }
+
+            for attribute in derivation.children.iter().filter(|n| n.local_name() == "attribute") {
+                complex.attributes.extend(self.attribute(attribute)?);
+            }
+        }
+        Ok(())
+    }
+
+    fn complex_content(&mut self, node: &'a XmlNode, complex: &mut ComplexType) -> Result<(), ConversionError> {
+        complex.mixed |= node.attribute("mixed") == Some("true");
+
+        for derivation in node.children.iter().filter(|n| matches!(n.local_name(), "extension" | "restriction")) {
+            let own = self.complex_type(derivation)?;
+            let is_extension = derivation.local_name() == "extension";
+
+            let base_name = derivation.attribute("base").map(strip_prefix)
+                .and_then(|base| self.complex_nodes.get_key_value(base))
+                .map(|(name, _)| *name);
+
+            let base = match base_name {
+                Some(base) if is_extension => self.named_complex_type(base)?,
+                _ => ComplexType::default(),
+            };
+
+            complex.mixed |= base.mixed || own.mixed;
+            complex.attributes.extend(base.attributes);
+            complex.attributes.extend(own.attributes);
+            complex.content = match (base.content, own.content) {
+                (Content::Group(base_group), Content::Group(own_group)) => Content::Group(Group {
+                    kind: GroupKind::Sequence,
+                    min_occurs: 1,
+                    max_occurs: Some(1),
+                    particles: vec![Particle::Group(base_group), Particle::Group(own_group)],
+                }),
+                (base_content, Content::Empty) => base_content,
+                (_, own_content) => own_content,
+            };
+        }
+        Ok(())
+    }
+
+    fn group(&mut self, node: &'a XmlNode) -> Result<Group, ConversionError> {
+        let kind = match node.local_name() {
+            "choice" => GroupKind::Choice,
+            "all" => GroupKind::All,
+            _ => GroupKind::Sequence,
+        };
+        let mut particles = Vec::new();
+
+        for child in &node.children {
+            match child.local_name() {
+                "element" => particles.push(Particle::Element(Box::new(self.element(child)?))),
+                "sequence" | "choice" | "all" => particles.push(Particle::Group(self.group(child)?)),
+                _ => {},
+            }
+        }
+
+        Ok(Group {
+            kind,
+            min_occurs: parse_min_occurs(node)?,
+            max_occurs: parse_max_occurs(node)?,
+            particles,
+        })
+    }
+
+    fn attribute(&mut self, node: &'a XmlNode) -> Result<Option<Attribute>, ConversionError> {
+        // Attribute references (e.g. `xml:lang`) and prohibited attributes are not modelled.
+        let Some(name) = node.attribute("name") else {
+            return Ok(None);
+        };
+        if node.attribute("use") == Some("prohibited") {
+            return Ok(None);
+        }
+
+        let ty = if let Some(type_name) = node.attribute("type") {
+            self.simple_type_reference(type_name)?
+        } else if let Some(simple) = node.children.iter().find(|n| n.local_name() == "simpleType") {
+            self.simple_type(simple)?
+        } else {
+            SimpleType::new(BuiltinType::String)
+        };
+
+        Ok(Some(Attribute {
+            name: name.to_string(),
+            ty,
+            required: node.attribute("use") == Some("required"),
+        }))
+    }
+}
+
+fn parse_facet<T: std::str::FromStr>(facet: &XmlNode, value: &str) -> Result<T, ConversionError> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| ConversionError::InvalidSchema(format!("invalid {} {}", facet.local_name(), value)))
+}
+
+/// Value ranges implied by the derived built-in integer types.
+fn builtin_facets(local: &str) -> Facets {
+    let (min, max) = match local {
+        "nonNegativeInteger" => (Some(0.0), None),
+        "positiveInteger" => (Some(1.0), None),
+        "nonPositiveInteger" => (None, Some(0.0)),
+        "negativeInteger" => (None, Some(-1.0)),
+        "long" => (Some(i64::MIN as f64), Some(i64::MAX as f64)),
+        "int" => (Some(i32::MIN as f64), Some(i32::MAX as f64)),
+        "short" => (Some(i16::MIN as f64), Some(i16::MAX as f64)),
+        "byte" => (Some(i8::MIN as f64), Some(i8::MAX as f64)),
+        "unsignedLong" => (Some(0.0), Some(u64::MAX as f64)),
+        "unsignedInt" => (Some(0.0), Some(u32::MAX as f64)),
+        "unsignedShort" => (Some(0.0), Some(u16::MAX as f64)),
+        "unsignedByte" => (Some(0.0), Some(u8::MAX as f64)),
+        _ => (None, None),
+    };
+
+    Facets { min_inclusive: min, max_inclusive: max, ..Default::default() }
+}
+
+fn parse_min_occurs(node: &XmlNode) -> Result<u32, ConversionError> {
+    match node.attribute("minOccurs") {
+        Some(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| ConversionError::InvalidSchema(format!("invalid minOccurs {}", value))),
+        None => Ok(1),
+    }
+}
+
+fn parse_max_occurs(node: &XmlNode) -> Result<Option<u32>, ConversionError> {
+    match node.attribute("maxOccurs").map(str::trim) {
+        Some("unbounded") => Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConversionError::InvalidSchema(format!("invalid maxOccurs {}", value))),
+        None => Ok(Some(1)),
+    }
+}
@@ -0,0 +1,66 @@
+use crate::document::{Document, Element, Node};
+use crate::error::ConversionError;
+
+/// A minimal element tree, used to read XSD documents and the XML they describe.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct XmlNode {
+    pub name: String,
+    /// The namespace declarations followed by the other attributes.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+    /// All text directly inside this element, concatenated.
+    pub text: String,
+}
+
+impl XmlNode {
+    /// Parse an XML document with `Document::from_xml` and return its root element.
+    ///
+    /// A document without exactly one root element returns `ConversionError::RootCount`,
+    /// and text outside the root element `ConversionError::TextOutsideRoot`.
+    pub fn parse(xml: &str) -> Result<XmlNode, ConversionError> {
+        let document = Document::from_xml(xml)?;
+        let mut roots = Vec::new();
+
+        for node in &document.nodes {
+            match node {
+                Node::Element(element) => roots.push(element),
+                Node::Text(text) | Node::CData(text) => return Err(ConversionError::TextOutsideRoot(text.clone())),
+                Node::Comment(_) | Node::ProcessingInstruction { .. } => {},
+            }
+        }
+
+        match roots.as_slice() {
+            [root] => Ok(XmlNode::from_element(root)),
+            roots => Err(ConversionError::RootCount(roots.len())),
+        }
+    }
+
+    fn from_element(element: &Element) -> XmlNode {
+        let attributes = element.namespaces
+            .iter()
+            .map(|namespace| (namespace.attribute_name(), namespace.uri.clone()))
+            .chain(element.attributes.iter().map(|attribute| (attribute.name.clone(), attribute.value.clone())))
+            .collect();
+
+        XmlNode {
+            name: element.name.clone(),
+            attributes,
+            children: element.elements().map(XmlNode::from_element).collect(),
+            text: element.text(),
+        }
+    }
+
+    /// The element name without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        strip_prefix(&self.name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+    }
+}
+
+/// Strip the namespace prefix of a qualified name, e.g. `xs:string` becomes `string`.
+pub(crate) fn strip_prefix(name: &str) -> &str {
+    name.split_once(':').map_or(name, |(_, local)| local)
+}
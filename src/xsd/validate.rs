@@ -0,0 +1,272 @@
+use crate::error::ConversionError;
+use crate::xsd::tree::XmlNode;
+use crate::xsd::{ComplexType, Content, Element, ElementType, Group, GroupKind, Particle, Schema, SimpleType, Violation, ViolationKind};
+
+/// # Validate XML against an XML Schema.
+///
+/// This function checks an XML document, for example the output of `json_to_xml_with_root`, against a practical XSD subset:
+/// `xs:sequence`, `xs:choice` and `xs:all` with `minOccurs`/`maxOccurs`, attributes with `use="required"`,
+/// and simple types with restrictions and enumerations.
+///
+/// Namespace declarations and `xsi:` attributes are always allowed. Elements are matched by their local name.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::generate_xml::json_to_xml_with_root;
+/// use json_to_xml::xsd::{parse_xsd, validate_xml};
+///
+/// let schema = parse_xsd(r#"
+/// <xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+///   <xs:element name="Person">
+///     <xs:complexType>
+///       <xs:sequence>
+///         <xs:element name="Age" type="xs:nonNegativeInteger"/>
+///         <xs:element name="Name" type="xs:string"/>
+///       </xs:sequence>
+///     </xs:complexType>
+///   </xs:element>
+/// </xs:schema>
+/// "#).unwrap();
+///
+/// let xml_string = json_to_xml_with_root(r#"{"person": {"name": "John Doe", "age": -1}}"#, "Root").unwrap();
+///
+/// let violations = validate_xml(&xml_string, &schema).unwrap();
+///
+/// for violation in &violations {
+///     println!("{}", violation);
+/// }
+/// ```
+///
+/// ## Expected Output:
+///
+/// ```text
+/// /Person/Age: `-1` is not a valid xs:integer (minInclusive: 0)
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either the list of violations (empty when the document is valid), or a `ConversionError` if the XML cannot be parsed,
+/// including `ConversionError::RootCount` when it does not have exactly one root element and `ConversionError::TextOutsideRoot`.
+pub fn validate_xml(xml_string: &str, schema: &Schema) -> Result<Vec<Violation>, ConversionError> {
+    let root = XmlNode::parse(xml_string)?;
+    let mut violations = Vec::new();
+    let path = format!("/{}", root.name);
+
+    match schema.elements.iter().find(|element| element.name == root.local_name()) {
+        Some(element) => validate_element(schema, element, &root, &path, &mut violations),
+        None => violations.push(Violation { path, kind: ViolationKind::UnknownRoot(root.name.clone()) }),
+    }
+
+    Ok(violations)
+}
+
+fn validate_element(schema: &Schema, element: &Element, node: &XmlNode, path: &str, violations: &mut Vec<Violation>) {
+    match schema.resolve(&element.ty) {
+        ElementType::Any => {},
+        ElementType::Simple(simple) => {
+            validate_attributes(&[], node, path, violations);
+
+            for child in &node.children {
+                violations.push(violation(path, ViolationKind::UnexpectedElement(child.name.clone())));
+            }
+
+            validate_value(simple, &node.text, path, violations);
+        },
+        ty => match schema.complex_type(ty) {
+            Some(complex) => validate_complex(schema, complex, node, path, violations),
+            None => violations.push(violation(path, ViolationKind::UnexpectedElement(node.name.clone()))),
+        },
+    }
+}
+
+fn validate_complex(schema: &Schema, complex: &ComplexType, node: &XmlNode, path: &str, violations: &mut Vec<Violation>) {
+    validate_attributes(&complex.attributes, node, path, violations);
+
+    match &complex.content {
+        Content::Simple(simple) => {
+            for child in &node.children {
+                violations.push(violation(path, ViolationKind::UnexpectedElement(child.name.clone())));
+            }
+
+            validate_value(simple, &node.text, path, violations);
+        },
+        content => {
+            if !complex.mixed && !node.text.trim().is_empty() {
+                violations.push(violation(path, ViolationKind::UnexpectedText));
+            }
+
+            let children: Vec<&XmlNode> = node.children.iter().collect();
+            let mut matcher = Matcher { schema, children: &children, pos: 0, path };
+
+            if let Content::Group(group) = content {
+                matcher.match_group(group, violations);
+            }
+
+            for child in &children[matcher.pos..] {
+                violations.push(violation(path, ViolationKind::UnexpectedElement(child.name.clone())));
+            }
+        },
+    }
+}
+
+fn validate_attributes(declared: &[crate::xsd::Attribute], node: &XmlNode, path: &str, violations: &mut Vec<Violation>) {
+    for attribute in declared {
+        match node.attribute(&attribute.name) {
+            Some(value) => validate_value(&attribute.ty, value, &format!("{}/@{}", path, attribute.name), violations),
+            None if attribute.required => violations.push(violation(path, ViolationKind::MissingAttribute(attribute.name.clone()))),
+            None => {},
+        }
+    }
+
+    for (name, _) in &node.attributes {
+        let is_namespace = name == "xmlns" || name.starts_with("xmlns:") || name.starts_with("xsi:");
+
+        if !is_namespace && !declared.iter().any(|attribute| &attribute.name == name) {
+            violations.push(violation(path, ViolationKind::UnexpectedAttribute(name.clone())));
+        }
+    }
+}
+
+fn validate_value(simple: &SimpleType, text: &str, path: &str, violations: &mut Vec<Violation>) {
+    if !simple.accepts(text) {
+        violations.push(violation(path, ViolationKind::InvalidValue { value: text.to_string(), expected: simple.describe() }));
+    }
+}
+
+fn violation(path: &str, kind: ViolationKind) -> Violation {
+    Violation { path: path.to_string(), kind }
+}
+
+/// Matches the child elements of one element against its content model, from left to right.
+struct Matcher<'a> {
+    schema: &'a Schema,
+    children: &'a [&'a XmlNode],
+    pos: usize,
+    path: &'a str,
+}
+
+impl Matcher<'_> {
+    fn match_group(&mut self, group: &Group, violations: &mut Vec<Violation>) {
+        let mut count = 0;
+
+        while group.max_occurs.is_none_or(|max| count < max) {
+            let start = self.pos;
+            let mut attempt = Vec::new();
+            let repeats = group.max_occurs.is_none_or(|max| count + 1 < max);
+
+            self.match_group_once(group, repeats, &mut attempt);
+
+            if self.pos == start {
+                // Nothing matched: the missing content only counts when the group itself is required.
+                if count < group.min_occurs {
+                    violations.extend(attempt);
+                }
+                break;
+            }
+
+            violations.extend(attempt);
+            count += 1;
+        }
+    }
+
+    /// Match one occurrence of a group. When the group `repeats` again, surplus elements are left to its next occurrence.
+    fn match_group_once(&mut self, group: &Group, repeats: bool, violations: &mut Vec<Violation>) {
+        match group.kind {
+            GroupKind::Sequence => {
+                for particle in &group.particles {
+                    self.match_particle(particle, repeats, violations);
+                }
+            },
+            GroupKind::Choice => {
+                let next = self.children.get(self.pos).map(|child| child.local_name());
+                let chosen = group.particles.iter().find(|particle| next.is_some_and(|name| starts_with(particle, name)));
+
+                match chosen {
+                    Some(particle) => self.match_particle(particle, repeats, violations),
+                    None => violations.push(violation(self.path, ViolationKind::MissingElement(first_names(&group.particles).join(" | ")))),
+                }
+            },
+            GroupKind::All => {
+                let mut seen = vec![false; group.particles.len()];
+
+                while let Some(child) = self.children.get(self.pos) {
+                    let found = group.particles.iter().enumerate().find(|(i, particle)| !seen[*i] && starts_with(particle, child.local_name()));
+
+                    let Some((i, particle)) = found else {
+                        break;
+                    };
+                    seen[i] = true;
+                    self.match_particle(particle, repeats, violations);
+                }
+
+                for (particle, seen) in group.particles.iter().zip(seen) {
+                    if let (Particle::Element(element), false) = (particle, seen)
+                        && element.min_occurs > 0 {
+                        violations.push(violation(self.path, ViolationKind::MissingElement(element.name.clone())));
+                    }
+                }
+            },
+        }
+    }
+
+    fn match_particle(&mut self, particle: &Particle, repeats: bool, violations: &mut Vec<Violation>) {
+        let element = match particle {
+            Particle::Element(element) => element,
+            Particle::Group(group) => return self.match_group(group, violations),
+        };
+
+        let mut count = 0;
+
+        while let Some(child) = self.children.get(self.pos).filter(|child| child.local_name() == element.name) {
+            if element.max_occurs.is_some_and(|max| count >= max) {
+                if repeats {
+                    break;
+                }
+                violations.push(violation(self.path, ViolationKind::TooManyElements { name: element.name.clone(), max: element.max_occurs.unwrap_or(0) }));
+
+                // Skip the surplus elements, so they are reported once.
+                while self.children.get(self.pos).is_some_and(|child| child.local_name() == element.name) {
+                    self.pos += 1;
+                }
+                break;
+            }
+
+            let path = self.child_path(child);
+            validate_element(self.schema, element, child, &path, violations);
+
+            count += 1;
+            self.pos += 1;
+        }
+
+        if count < element.min_occurs {
+            violations.push(violation(self.path, ViolationKind::MissingElement(element.name.clone())));
+        }
+    }
+
+    /// The path of a child element, with a 1-based position when it has siblings of the same name.
+    fn child_path(&self, child: &XmlNode) -> String {
+        let siblings = self.children.iter().filter(|sibling| sibling.name == child.name).count();
+
+        if siblings > 1 {
+            let index = self.children[..self.pos].iter().filter(|sibling| sibling.name == child.name).count();
+            format!("{}/{}[{}]", self.path, child.name, index + 1)
+        } else {
+            format!("{}/{}", self.path, child.name)
+        }
+    }
+}
+
+/// Check whether a particle can start with an element of the given name.
+fn starts_with(particle: &Particle, name: &str) -> bool {
+    match particle {
+        Particle::Element(element) => element.name == name,
+        Particle::Group(group) => group.particles.iter().any(|particle| starts_with(particle, name)),
+    }
+}
+
+fn first_names(particles: &[Particle]) -> Vec<String> {
+    particles.iter().flat_map(|particle| match particle {
+        Particle::Element(element) => vec![element.name.clone()],
+        Particle::Group(group) => first_names(&group.particles),
+    }).collect()
+}
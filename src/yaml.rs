@@ -0,0 +1,101 @@
+use crate::error::ConversionError;
+use crate::generate_xml::value_to_xml;
+use crate::options::ConversionOptions;
+
+use serde_json::{Map, Number, Value};
+use serde_yaml::Value as YamlValue;
+
+/// # Convert YAML to XML with a custom root element.
+///
+/// The YAML document is read into the same value tree as JSON and converted with the conventions of `json_to_xml_with_root`:
+/// keys starting with `@` become attributes, `$text` becomes text content and all tags are capitalized.
+///
+/// Anchors and aliases are resolved, and merge keys (`<<: *anchor`) are applied.
+/// Non-string keys are written as strings, tags (e.g. `!Point`) are dropped and `.inf`/`.nan` are kept as text.
+///
+/// Requires the `yaml` cargo feature.
+///
+/// # Example
+///
+/// ```rust
+/// use json_to_xml::yaml::yaml_to_xml;
+///
+/// let yaml_string = r#"
+/// defaults: &defaults
+///   "@env": dev
+///   timeout: 30
+///
+/// service:
+///   <<: *defaults
+///   name: api
+/// "#;
+///
+/// let xml_string = yaml_to_xml(yaml_string, "Config").unwrap();
+///
+/// println!("{}", xml_string);
+/// ```
+///
+/// ## Expected Output (XML):
+///
+/// ```xml
+/// <?xml version="1.0" encoding="UTF-8"?>
+/// <!--Generated with json_to_xml 0.1.8-->
+/// <Config>
+///   <Defaults env="dev">
+///     <Timeout>30</Timeout>
+///   </Defaults>
+///   <Service env="dev">
+///     <Name>api</Name>
+///     <Timeout>30</Timeout>
+///   </Service>
+/// </Config>
+/// ```
+///
+/// ## Returns:
+/// A `Result` which is either a `String` containing the XML, or a `ConversionError::Yaml` if the YAML cannot be parsed.
+pub fn yaml_to_xml(yaml_string: &str, root: &str) -> Result<String, ConversionError> {
+    let mut yaml_value: YamlValue = serde_yaml::from_str(yaml_string)?;
+    yaml_value.apply_merge()?;
+
+    value_to_xml(yaml_to_json(yaml_value), root, &ConversionOptions::default())
+}
+
+/// Convert a YAML value into the JSON value tree used by the generator.
+fn yaml_to_json(yaml_value: YamlValue) -> Value {
+    match yaml_value {
+        YamlValue::Null => Value::Null,
+        YamlValue::Bool(b) => Value::Bool(b),
+        YamlValue::Number(num) => {
+            if let Some(i) = num.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = num.as_u64() {
+                Value::from(u)
+            } else {
+                // Infinity and NaN have no JSON number representation.
+                num.as_f64().and_then(Number::from_f64).map_or_else(|| Value::String(num.to_string()), Value::Number)
+            }
+        },
+        YamlValue::String(s) => Value::String(s),
+        YamlValue::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        YamlValue::Mapping(mapping) => {
+            let map: Map<String, Value> = mapping
+                .into_iter()
+                .map(|(key, value)| (yaml_key(key), yaml_to_json(value)))
+                .collect();
+            Value::Object(map)
+        },
+        YamlValue::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn yaml_key(key: YamlValue) -> String {
+    match key {
+        YamlValue::String(s) => s,
+        YamlValue::Null => "null".to_string(),
+        YamlValue::Bool(b) => b.to_string(),
+        YamlValue::Number(num) => num.to_string(),
+        YamlValue::Tagged(tagged) => yaml_key(tagged.value),
+        // Complex keys are rare; their JSON form keeps them readable.
+        key => yaml_to_json(key).to_string(),
+    }
+}
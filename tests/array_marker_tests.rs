@@ -0,0 +1,131 @@
+use json_to_xml::document::{xml_to_json, xml_to_json_with_options, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_options;
+use json_to_xml::options::{ArrayMarker, ConversionOptions, EmptyArray, TopLevelArray, TypeAnnotation, JSON_NAMESPACE};
+use proptest::prelude::*;
+use serde_json::{json, Map, Value};
+
+fn marked() -> ConversionOptions {
+    ConversionOptions { array_marker: Some(ArrayMarker::default()), ..Default::default() }
+}
+
+/// Convert to XML and back, returning the content of the root element without namespace declarations.
+fn round_trip(value: &Value, options: &ConversionOptions) -> Result<Value, ConversionError> {
+    let xml = json_to_xml_with_options(&value.to_string(), "Root", options)?;
+    let json: Value = serde_json::from_str(&xml_to_json_with_options(&xml, options)?)?;
+
+    Ok(match json.get("Root") {
+        Some(Value::Object(map)) => Value::Object(map.iter().filter(|(key, _)| !key.starts_with("@xmlns")).map(|(k, v)| (k.clone(), v.clone())).collect()),
+        Some(value) => value.clone(),
+        None => json,
+    })
+}
+
+#[test]
+fn test_marker_on_arrays() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_options(r#"{"tags": ["a"], "authors": [{"name": "Ann"}]}"#, "Post", &marked())?;
+
+    assert!(xml.contains(&format!(r#"<Post xmlns:json="{}">"#, JSON_NAMESPACE)));
+    assert!(xml.contains("<Authors json:array=\"true\">\n    <Name>Ann</Name>\n  </Authors>"));
+    assert!(xml.contains("<Tags json:array=\"true\">\n    <TagsItem>a</TagsItem>\n  </Tags>"));
+    Ok(())
+}
+
+#[test]
+fn test_marker_with_custom_namespace() -> Result<(), ConversionError> {
+    let options = ConversionOptions {
+        array_marker: Some(ArrayMarker { prefix: "j".to_string(), namespace: "urn:example".to_string() }),
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_options(r#"[1]"#, "Root", &options)?;
+
+    assert!(xml.ends_with("<Root xmlns:j=\"urn:example\" j:array=\"true\">\n  <RootItem>1</RootItem>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_single_item_array_differs_from_object() -> Result<(), ConversionError> {
+    let array = json_to_xml_with_options(r#"{"book": [{"title": "Rust"}]}"#, "Root", &marked())?;
+    let object = json_to_xml_with_options(r#"{"book": {"title": "Rust"}}"#, "Root", &marked())?;
+    assert_ne!(array, object);
+
+    assert_eq!(round_trip(&json!({ "Book": [{ "Title": "Rust" }] }), &marked())?, json!({ "Book": [{ "Title": "Rust" }] }));
+    assert_eq!(round_trip(&json!({ "Book": { "Title": "Rust" } }), &marked())?, json!({ "Book": { "Title": "Rust" } }));
+
+    // Without the marker, both read back as an object.
+    assert_eq!(serde_json::from_str::<Value>(&xml_to_json(&json_to_xml_with_options(r#"{"book": [{"title": "Rust"}]}"#, "Root", &ConversionOptions::default())?)?)?, json!({ "Book": { "Title": "Rust" } }));
+    Ok(())
+}
+
+#[test]
+fn test_marked_round_trips() -> Result<(), ConversionError> {
+    let options = ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..marked() };
+
+    for value in [
+        json!({ "Tags": ["a"], "Count": 1, "Ratio": 0.5, "Ok": true, "Missing": null, "Blank": "", "Empty": {} }),
+        json!({ "Matrix": [[1, 2], [3], []], "Single": [[]] }),
+        json!({ "Mixed": [1, { "A": "b" }, [true], null, ""] }),
+        json!({ "People": [{ "@id": "1", "Name": "Ann" }, { "@id": "2", "Tags": [] }] }),
+        json!({ "Tags": { "TagsItem": "not an array" } }),
+        json!({ "Tags": [] }),
+    ] {
+        assert_eq!(round_trip(&value, &options)?, value);
+    }
+
+    for empty_array in [EmptyArray::SelfClosing, EmptyArray::Marked("empty".to_string())] {
+        let options = ConversionOptions { empty_array, ..options.clone() };
+        assert_eq!(round_trip(&json!({ "Tags": [], "Rows": [[]] }), &options)?, json!({ "Tags": [], "Rows": [[]] }));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_marked_top_level_arrays() -> Result<(), ConversionError> {
+    let options = ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..marked() };
+    assert_eq!(round_trip(&json!([1, [2]]), &options)?, json!([1, [2]]));
+    assert_eq!(round_trip(&json!([]), &options)?, json!([]));
+
+    let options = ConversionOptions { top_level_array: TopLevelArray::Named("item".to_string()), ..marked() };
+    assert_eq!(round_trip(&json!(["a"]), &options)?, json!(["a"]));
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    let options = ConversionOptions { empty_array: EmptyArray::Marked("empty".to_string()), ..marked() };
+
+    for json in [r#"{"tags": ["a"], "authors": [{"@id": 1}, {"name": "Bo"}]}"#, r#"{"rows": [[], [1]]}"#, "[[]]", "[]", "1"] {
+        let value: Value = serde_json::from_str(json)?;
+        assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+    }
+    Ok(())
+}
+
+fn arb_value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        (-1000i32..1000).prop_map(|n| Value::from(f64::from(n) / 4.0 + 0.25)),
+        "[a-z0-9]{0,6}".prop_map(Value::from),
+    ];
+
+    leaf.prop_recursive(4, 48, 5, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(Value::Array),
+            prop::collection::vec(("[A-M][a-z]{0,4}", inner), 1..4).prop_map(|entries| Value::Object(entries.into_iter().collect::<Map<_, _>>())),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn prop_marked_round_trip_is_exact(entries in prop::collection::vec(("[A-M][a-z]{0,4}", arb_value()), 1..4)) {
+        let value = Value::Object(entries.into_iter().collect());
+        let options = ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..marked() };
+
+        let read = round_trip(&value, &options).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        prop_assert_eq!(read, value);
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg(any(feature = "msgpack", feature = "cbor"))]
+
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+
+#[cfg(feature = "msgpack")]
+mod msgpack {
+    use super::*;
+    use json_to_xml::binary::{msgpack_to_xml, msgpack_to_xml_with_options};
+    use json_to_xml::options::{BinaryEncoding, ConversionOptions};
+    use rmpv::Value;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, value).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_msgpack_matches_json_conversion() -> Result<(), ConversionError> {
+        let value = Value::Map(vec![
+            (Value::from("@id"), Value::from(42)),
+            (Value::from("name"), Value::from("Widget")),
+            (Value::from("tags"), Value::Array(vec![Value::from("a"), Value::from("b")])),
+            (Value::from("price"), Value::F64(9.5)),
+            (Value::from("active"), Value::Boolean(true)),
+            (Value::from("note"), Value::Nil),
+        ]);
+        let json = r#"{"@id": 42, "name": "Widget", "tags": ["a", "b"], "price": 9.5, "active": true, "note": null}"#;
+
+        assert_eq!(msgpack_to_xml(&encode(&value), "Item")?, json_to_xml_with_root(json, "Item")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_msgpack_binary_encodings() -> Result<(), ConversionError> {
+        let value = Value::Map(vec![
+            (Value::from("blob"), Value::Binary(vec![0xde, 0xad, 0xbe, 0xef])),
+            (Value::from("ext"), Value::Ext(5, vec![0x01, 0xff])),
+        ]);
+        let bytes = encode(&value);
+
+        let xml = msgpack_to_xml(&bytes, "Root")?;
+        assert!(xml.contains("<Blob>3q2+7w==</Blob>"));
+        assert!(xml.contains("<Ext>Af8=</Ext>"));
+
+        let options = ConversionOptions { binary_encoding: BinaryEncoding::Hex, ..Default::default() };
+        let xml = msgpack_to_xml_with_options(&bytes, "Root", &options)?;
+        assert!(xml.contains("<Blob>deadbeef</Blob>"));
+        assert!(xml.contains("<Ext>01ff</Ext>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_msgpack_truncated_input() {
+        let bytes = encode(&Value::Map(vec![(Value::from("name"), Value::from("Widget"))]));
+
+        let result = msgpack_to_xml(&bytes[..bytes.len() - 2], "Root");
+        assert!(matches!(result, Err(ConversionError::MessagePack(_))));
+    }
+}
+
+#[cfg(feature = "cbor")]
+mod cbor {
+    use super::*;
+    use json_to_xml::binary::{cbor_to_xml, cbor_to_xml_with_options};
+    use json_to_xml::options::{BinaryEncoding, ConversionOptions};
+    use ciborium::Value;
+
+    fn encode(value: &Value) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        ciborium::into_writer(value, &mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_cbor_matches_json_conversion() -> Result<(), ConversionError> {
+        let value = Value::Map(vec![
+            (Value::from("@id"), Value::from(42)),
+            (Value::from("name"), Value::from("Widget")),
+            (Value::from("tags"), Value::Array(vec![Value::from("a"), Value::from("b")])),
+            (Value::from("price"), Value::Float(9.5)),
+            (Value::from("active"), Value::Bool(true)),
+            (Value::from("note"), Value::Null),
+        ]);
+        let json = r#"{"@id": 42, "name": "Widget", "tags": ["a", "b"], "price": 9.5, "active": true, "note": null}"#;
+
+        assert_eq!(cbor_to_xml(&encode(&value), "Item")?, json_to_xml_with_root(json, "Item")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_bytes_and_tags() -> Result<(), ConversionError> {
+        let value = Value::Map(vec![
+            (Value::from("blob"), Value::Bytes(vec![0xde, 0xad, 0xbe, 0xef])),
+            (Value::from("created"), Value::Tag(0, Box::new(Value::from("2024-05-01T12:00:00Z")))),
+            (Value::from("ratio"), Value::Float(f64::INFINITY)),
+        ]);
+        let bytes = encode(&value);
+
+        let xml = cbor_to_xml(&bytes, "Root")?;
+        assert!(xml.contains("<Blob>3q2+7w==</Blob>"));
+        assert!(xml.contains("<Created>2024-05-01T12:00:00Z</Created>"));
+        assert!(xml.contains("<Ratio>inf</Ratio>"));
+
+        let options = ConversionOptions { binary_encoding: BinaryEncoding::Hex, ..Default::default() };
+        let xml = cbor_to_xml_with_options(&bytes, "Root", &options)?;
+        assert!(xml.contains("<Blob>deadbeef</Blob>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cbor_truncated_input() {
+        let bytes = encode(&Value::Map(vec![(Value::from("name"), Value::from("Widget"))]));
+
+        let result = cbor_to_xml(&bytes[..bytes.len() - 2], "Root");
+        assert!(matches!(result, Err(ConversionError::Cbor(_))));
+    }
+}
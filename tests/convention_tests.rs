@@ -0,0 +1,170 @@
+use json_to_xml::convention::{json_to_xml_with_convention, xml_to_json_with_convention, Abdera, BadgerFish, Convention, GData, JsonML, Parker, Standard};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+use json_to_xml::options::{BooleanFormat, ConversionOptions};
+use serde_json::{json, Value};
+
+const XML: &str = r#"<feed xmlns="http://www.w3.org/2005/Atom" xmlns:os="http://a9.com/-/spec/opensearch/1.1/">
+  <title type="text">News</title>
+  <os:totalResults>2</os:totalResults>
+  <entry id="1">First</entry>
+  <entry id="2">Second</entry>
+</feed>"#;
+
+fn to_json(convention: &dyn Convention) -> Result<Value, ConversionError> {
+    Ok(serde_json::from_str(&xml_to_json_with_convention(XML, convention)?)?)
+}
+
+fn round_trip(convention: &dyn Convention, value: &Value) -> Result<Value, ConversionError> {
+    let xml = json_to_xml_with_convention(&value.to_string(), "Root", convention, &ConversionOptions::default())?;
+    Ok(serde_json::from_str(&xml_to_json_with_convention(&xml, convention)?)?)
+}
+
+#[test]
+fn test_standard_matches_json_to_xml() -> Result<(), ConversionError> {
+    let json = r#"{"@id": "7", "name": "Pen", "tags": ["a", "b"]}"#;
+
+    assert_eq!(json_to_xml_with_convention(json, "Item", &Standard, &ConversionOptions::default())?, json_to_xml_with_root(json, "Item")?);
+    Ok(())
+}
+
+#[test]
+fn test_badgerfish() -> Result<(), ConversionError> {
+    let value = to_json(&BadgerFish)?;
+
+    assert_eq!(value, json!({
+        "feed": {
+            "@xmlns": { "$": "http://www.w3.org/2005/Atom", "os": "http://a9.com/-/spec/opensearch/1.1/" },
+            "title": { "@type": "text", "$": "News" },
+            "os:totalResults": { "$": "2" },
+            "entry": [{ "@id": "1", "$": "First" }, { "@id": "2", "$": "Second" }]
+        }
+    }));
+    assert_eq!(round_trip(&BadgerFish, &value)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_parker() -> Result<(), ConversionError> {
+    let value = to_json(&Parker)?;
+
+    assert_eq!(value, json!({ "title": "News", "os:totalResults": 2, "entry": ["First", "Second"] }));
+
+    let xml = json_to_xml_with_convention(r#"{"count": 2, "flags": [true, false], "note": null}"#, "Stats", &Parker, &ConversionOptions::default())?;
+    assert!(xml.contains("<Stats>\n  <count>2</count>\n  <flags>true</flags>\n  <flags>false</flags>\n  <note/>\n</Stats>"));
+
+    let list = json_to_xml_with_convention("[1, 2]", "List", &Parker, &ConversionOptions::default())?;
+    assert!(list.contains("<List>\n  <ListItem>1</ListItem>\n  <ListItem>2</ListItem>\n</List>"));
+    assert_eq!(xml_to_json_with_convention(&list, &Parker)?, "[1,2]");
+    Ok(())
+}
+
+#[test]
+fn test_parker_reads_numbers_as_written() -> Result<(), ConversionError> {
+    let xml = "<r><a>007</a><b> 7 </b><c>7</c><d>1.5</d><e> true</e><f>false</f><g>-0.25</g></r>";
+
+    let value: Value = serde_json::from_str(&xml_to_json_with_convention(xml, &Parker)?)?;
+
+    assert_eq!(value, json!({ "a": "007", "b": " 7 ", "c": 7, "d": 1.5, "e": " true", "f": false, "g": -0.25 }));
+    Ok(())
+}
+
+#[test]
+fn test_gdata() -> Result<(), ConversionError> {
+    let value = to_json(&GData)?;
+
+    assert_eq!(value, json!({
+        "version": "1.0",
+        "encoding": "UTF-8",
+        "feed": {
+            "xmlns": "http://www.w3.org/2005/Atom",
+            "xmlns$os": "http://a9.com/-/spec/opensearch/1.1/",
+            "title": { "type": "text", "$t": "News" },
+            "os$totalResults": { "$t": "2" },
+            "entry": [{ "id": "1", "$t": "First" }, { "id": "2", "$t": "Second" }]
+        }
+    }));
+    assert_eq!(round_trip(&GData, &value)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_abdera() -> Result<(), ConversionError> {
+    let value = to_json(&Abdera)?;
+
+    assert_eq!(value["name"], "feed");
+    assert_eq!(value["attributes"]["xmlns:os"], "http://a9.com/-/spec/opensearch/1.1/");
+    assert_eq!(value["children"][0], json!({ "name": "title", "attributes": { "type": "text" }, "children": ["News"] }));
+    assert_eq!(value["children"].as_array().unwrap().len(), 4);
+    assert_eq!(round_trip(&Abdera, &value)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_jsonml() -> Result<(), ConversionError> {
+    let value: Value = serde_json::from_str(&xml_to_json_with_convention(r#"<p class="x">Hi <b>there</b>!</p>"#, &JsonML)?)?;
+
+    assert_eq!(value, json!(["p", { "class": "x" }, "Hi ", ["b", "there"], "!"]));
+    assert_eq!(round_trip(&JsonML, &value)?, value);
+
+    let value = to_json(&JsonML)?;
+    assert_eq!(value[2], json!(["title", { "type": "text" }, "News"]));
+    assert_eq!(round_trip(&JsonML, &value)?, value);
+    Ok(())
+}
+
+#[test]
+fn test_scalar_formats_apply_to_conventions() -> Result<(), ConversionError> {
+    let options = ConversionOptions { boolean_format: BooleanFormat::YesNo, ..Default::default() };
+
+    let xml = json_to_xml_with_convention(r#"["flag", {"on": true}, false]"#, "Root", &JsonML, &options)?;
+    assert!(xml.contains(r#"<flag on="yes">no</flag>"#));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_convention_input() {
+    let result = json_to_xml_with_convention(r#"{"p": "text"}"#, "Root", &JsonML, &ConversionOptions::default());
+    assert!(matches!(result, Err(ConversionError::Convention(_))));
+
+    let result = json_to_xml_with_convention(r#"["p"]"#, "Root", &Abdera, &ConversionOptions::default());
+    assert!(matches!(result, Err(ConversionError::Convention(_))));
+}
+
+#[test]
+fn test_invalid_names_are_convention_errors() {
+    let cases: [(&str, &dyn Convention); 5] = [
+        (r#"[""]"#, &JsonML),
+        (r#"["a b"]"#, &JsonML),
+        (r#"{"name": "p", "attributes": {"<": 1}}"#, &Abdera),
+        (r#"{"feed": {"b$c": {"$t": "x"}}}"#, &GData),
+        (r#"{"a": {"@x": 1, "b c": {}}}"#, &BadgerFish),
+    ];
+
+    for (json, convention) in cases {
+        let result = json_to_xml_with_convention(json, "Root", convention, &ConversionOptions::default());
+        assert!(matches!(result, Err(ConversionError::Convention(_))), "{}: {:?}", json, result);
+    }
+
+    let error = json_to_xml_with_convention(r#"{"feed": {"b$c": {"$t": "x"}}}"#, "Root", &GData, &ConversionOptions::default()).unwrap_err();
+    assert_eq!(error.to_string(), r#"Convention error: undeclared namespace prefix "b" in "b:c""#);
+
+    let declared = r#"{"feed": {"xmlns$b": "urn:b", "b$c": {"$t": "x"}}}"#;
+    assert!(json_to_xml_with_convention(declared, "Root", &GData, &ConversionOptions::default()).is_ok());
+}
+
+#[test]
+fn test_top_level_arrays_have_one_root() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_convention("[1, 2]", "Root", &BadgerFish, &ConversionOptions::default())?;
+    assert!(xml.ends_with("<Root>\n  <RootItem>1</RootItem>\n  <RootItem>2</RootItem>\n</Root>"), "{}", xml);
+
+    let xml = json_to_xml_with_convention(r#"[{"x": {"$": 1}}, {"x": {"$": 2}}]"#, "Root", &BadgerFish, &ConversionOptions::default())?;
+    assert!(xml.ends_with("<Root>\n  <RootItem>\n    <x>1</x>\n  </RootItem>\n  <RootItem>\n    <x>2</x>\n  </RootItem>\n</Root>"), "{}", xml);
+
+    let xml = json_to_xml_with_convention(r#"[{"$t": "a"}, {"$t": "b"}]"#, "Root", &GData, &ConversionOptions::default())?;
+    assert!(xml.ends_with("<Root>\n  <RootItem>a</RootItem>\n  <RootItem>b</RootItem>\n</Root>"), "{}", xml);
+
+    let xml = json_to_xml_with_convention("[]", "Root", &BadgerFish, &ConversionOptions::default())?;
+    assert!(xml.ends_with("<Root/>"), "{}", xml);
+    Ok(())
+}
@@ -0,0 +1,120 @@
+#![cfg(feature = "csv")]
+
+use json_to_xml::csv::{csv_to_xml, CsvOptions};
+use json_to_xml::error::ConversionError;
+use json_to_xml::options::{ConversionOptions, RootPolicy, TypeAnnotation};
+
+#[test]
+fn test_csv_rows_and_attributes() -> Result<(), ConversionError> {
+    let csv = "@id,name,zip,active\n1,\"Doe, John\",00501,true\n2,Jane,90210,FALSE\n";
+
+    let xml = csv_to_xml(csv, &CsvOptions::default())?;
+
+    assert!(xml.contains("<Rows>\n  <Row id=\"1\">"));
+    assert!(xml.contains("<Name>Doe, John</Name>"));
+    assert!(xml.contains("<Zip>00501</Zip>"));
+    assert!(xml.contains("<Active>true</Active>"));
+    assert!(xml.contains("<Active>false</Active>"));
+    assert_eq!(xml.matches("<Row ").count(), 2);
+    assert!(xml.ends_with("</Rows>"));
+    Ok(())
+}
+
+#[test]
+fn test_csv_type_inference() -> Result<(), ConversionError> {
+    let csv = "count;flag;mixed;note\n10;yes;1;\n-2.5e1;no;true;x\n;yes;2;\n";
+    let options = CsvOptions { delimiter: b';', root: "data".to_string(), row: "entry".to_string(), ..Default::default() };
+
+    let xml = csv_to_xml(csv, &options)?;
+
+    assert!(xml.contains("<Data>"));
+    assert!(xml.contains("<Entry>\n    <Count>10</Count>"));
+    assert!(xml.contains("<Count>-2.5e1</Count>"));
+    assert!(xml.contains("<Count></Count>"));
+    assert!(xml.contains("<Flag>yes</Flag>"));
+    assert!(xml.contains("<Mixed>true</Mixed>"));
+    Ok(())
+}
+
+#[test]
+fn test_csv_numbers_keep_their_text() -> Result<(), ConversionError> {
+    let options = CsvOptions {
+        conversion: ConversionOptions { type_annotation: TypeAnnotation::Attribute("type".to_string()), ..Default::default() },
+        ..Default::default()
+    };
+
+    let xml = csv_to_xml("price,qty,code\n1.50,3,007\n2.25,-4,12\n", &options)?;
+
+    let price = if cfg!(feature = "arbitrary_precision") { "<Price type=\"number\">1.50</Price>" } else { "<Price type=\"string\">1.50</Price>" };
+    assert!(xml.contains(price));
+    assert!(xml.contains("<Qty type=\"number\">-4</Qty>"));
+    assert!(xml.contains("<Code type=\"string\">007</Code>"));
+    Ok(())
+}
+
+#[test]
+fn test_csv_duplicate_headers() {
+    let result = csv_to_xml("name,name\nA,B\n", &CsvOptions::default());
+
+    assert!(matches!(result, Err(ConversionError::DuplicateColumn(header)) if header == "name"));
+}
+
+#[test]
+fn test_csv_without_type_inference() -> Result<(), ConversionError> {
+    let options = CsvOptions {
+        infer_types: false,
+        conversion: ConversionOptions { type_annotation: TypeAnnotation::Attribute("type".to_string()), ..Default::default() },
+        ..Default::default()
+    };
+
+    let xml = csv_to_xml("n,b\n1,true\n", &options)?;
+
+    assert!(xml.contains("<N type=\"string\">1</N>"));
+    assert!(xml.contains("<B type=\"string\">true</B>"));
+    Ok(())
+}
+
+#[test]
+fn test_csv_declares_xsi_namespaces() -> Result<(), ConversionError> {
+    let options = CsvOptions {
+        conversion: ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..Default::default() },
+        ..Default::default()
+    };
+
+    let xml = csv_to_xml("n\n1\n", &options)?;
+
+    assert!(xml.contains("<Rows xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" xmlns:xs=\"http://www.w3.org/2001/XMLSchema\">"));
+    assert!(xml.contains("<N xsi:type=\"xs:integer\">1</N>"));
+    Ok(())
+}
+
+#[test]
+fn test_csv_error() {
+    let result = csv_to_xml("a,b\n1,2,3\n", &CsvOptions::default());
+
+    assert!(matches!(result, Err(ConversionError::Csv(_))));
+}
+
+#[test]
+fn test_csv_rejects_invalid_names() {
+    let result = csv_to_xml("a\n1\n", &CsvOptions { root: "1bad".to_string(), ..Default::default() });
+    assert!(matches!(result, Err(ConversionError::InvalidName { name, .. }) if name == "1bad"));
+
+    let result = csv_to_xml("a\n", &CsvOptions { row: "a row".to_string(), ..Default::default() });
+    assert!(matches!(result, Err(ConversionError::InvalidName { name, .. }) if name == "A row"));
+}
+
+#[test]
+fn test_csv_root_policy() -> Result<(), ConversionError> {
+    let with_policy = |root_policy| CsvOptions { conversion: ConversionOptions { root_policy, ..Default::default() }, ..Default::default() };
+
+    let xml = csv_to_xml("n\n1\n2\n", &with_policy(RootPolicy::Fragment))?;
+    assert_eq!(xml, "<Row>\n  <N>1</N>\n</Row>\n<Row>\n  <N>2</N>\n</Row>");
+
+    let result = csv_to_xml("n\n1\n2\n", &with_policy(RootPolicy::Error));
+    assert!(matches!(result, Err(ConversionError::RootCount(2))));
+
+    let xml = csv_to_xml("n\n1\n", &with_policy(RootPolicy::Error))?;
+    assert!(xml.ends_with("-->\n<Row>\n  <N>1</N>\n</Row>"));
+    Ok(())
+}
@@ -0,0 +1,172 @@
+use json_to_xml::document::{xml_to_json, xml_to_toml, Document, Element, Node};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml_with_options, json_to_xml_with_root};
+use json_to_xml::options::{BooleanFormat, ConversionOptions, NumberFormat, TypeAnnotation};
+use serde_json::{json, Value};
+
+#[test]
+fn test_from_value_matches_json_to_xml() -> Result<(), ConversionError> {
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::Attribute("type".to_string()),
+        number_format: NumberFormat { decimal_places: Some(2), ..Default::default() },
+        ..Default::default()
+    };
+    let inputs = [
+        r#"{"@id": "1", "name": "Widget", "tags": ["a", "b"], "parts": [{"@no": 1, "label": "x"}, {"@no": 2, "label": "y"}]}"#,
+        r#"{"book": {"title": "Rust", "price": 9.5, "note": null, "extra": {}, "empty": ""}}"#,
+        r#"{"order": {"@id": 3, "lines": [{"sku": "A"}, {"sku": "B"}], "paid": true}}"#,
+    ];
+
+    for input in inputs {
+        let value: Value = serde_json::from_str(input)?;
+        let document = Document::from_value(&value, "Root", &options)?;
+
+        assert_eq!(document.to_xml()?, json_to_xml_with_options(input, "Root", &options)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_from_value_names_the_compiled_version() -> Result<(), ConversionError> {
+    let document = Document::from_value(&json!({ "a": 1 }), "Root", &ConversionOptions::default())?;
+
+    assert_eq!(document.nodes[0], Node::Comment(format!("Generated with json_to_xml {}", env!("CARGO_PKG_VERSION"))));
+    Ok(())
+}
+
+#[test]
+fn test_xml_round_trip_keeps_nodes() -> Result<(), ConversionError> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!--catalog export-->
+<?xml-stylesheet href="catalog.xsl"?>
+<c:Catalog xmlns:c="urn:catalog" xmlns="urn:default" c:version="2">
+  <Item id="1">Fish &amp; Chips</Item>
+  <Note><![CDATA[<b>raw</b>]]></Note>
+  <Empty></Empty>
+  <Closed/>
+</c:Catalog>"#;
+
+    let document = Document::from_xml(xml)?;
+
+    assert_eq!(document.nodes.len(), 3);
+    assert_eq!(document.nodes[0], Node::Comment("catalog export".to_string()));
+    assert_eq!(document.nodes[1], Node::ProcessingInstruction { target: "xml-stylesheet".to_string(), content: r#"href="catalog.xsl""#.to_string() });
+
+    let catalog = document.root().unwrap();
+    assert_eq!(catalog.name, "c:Catalog");
+    assert_eq!(catalog.namespaces.len(), 2);
+    assert_eq!(catalog.namespaces[0].prefix.as_deref(), Some("c"));
+    assert_eq!(catalog.namespaces[1].prefix, None);
+    assert_eq!(catalog.attribute("c:version"), Some("2"));
+
+    let children: Vec<&Element> = catalog.elements().collect();
+    assert_eq!(children[0].text(), "Fish & Chips");
+    assert_eq!(children[1].children, vec![Node::CData("<b>raw</b>".to_string())]);
+    assert_eq!(children[2].children, vec![Node::Text(String::new())]);
+    assert!(children[3].children.is_empty());
+
+    assert_eq!(document.to_xml()?, xml);
+    Ok(())
+}
+
+#[test]
+fn test_to_value_mapping() -> Result<(), ConversionError> {
+    let xml = r#"<Shop xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <Product sku="A1">
+    <Name>Pen</Name>
+    <Price xsi:type="xs:decimal">1.5</Price>
+    <Mass xsi:type="xs:double">1e-7</Mass>
+    <InStock xsi:type="xs:boolean">true</InStock>
+    <Colors><ColorsItem>red</ColorsItem><ColorsItem>blue</ColorsItem></Colors>
+    <Discount><None/></Discount>
+    <Weight unit="g">12</Weight>
+  </Product>
+  <Product sku="B2"/>
+</Shop>"#;
+
+    let value = Document::from_xml(xml)?.to_value();
+
+    assert_eq!(value, json!({
+        "Shop": {
+            "@xmlns:xsi": "http://www.w3.org/2001/XMLSchema-instance",
+            "Product": [
+                {
+                    "@sku": "A1",
+                    "Name": "Pen",
+                    "Price": 1.5,
+                    "Mass": 1e-7,
+                    "InStock": true,
+                    "Colors": ["red", "blue"],
+                    "Discount": null,
+                    "Weight": { "@unit": "g", "$text": "12" }
+                },
+                { "@sku": "B2" }
+            ]
+        }
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_json_xml_json_round_trip() -> Result<(), ConversionError> {
+    let json = r#"{"@id": "42", "Title": "Report", "Authors": ["Ann", "Bob"], "Sections": [{"Heading": "Intro"}, {"Heading": "End"}], "Draft": {}}"#;
+
+    let xml = json_to_xml_with_root(json, "Doc")?;
+    let round_trip: Value = serde_json::from_str(&xml_to_json(&xml)?)?;
+
+    assert_eq!(round_trip, json!({ "Doc": serde_json::from_str::<Value>(json)? }));
+    Ok(())
+}
+
+#[test]
+fn test_attribute_annotation_round_trip() -> Result<(), ConversionError> {
+    let json = r#"{"Age": 30, "Ratio": 1.5, "Active": false, "Code": "007", "Note": null, "Size": {"@unit": "cm", "$text": 12}}"#;
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::Attribute("type".to_string()),
+        boolean_format: BooleanFormat::YesNo,
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_options(json, "Item", &options)?;
+    let round_trip = Document::from_xml(&xml)?.to_value_with_options(&options);
+
+    assert_eq!(round_trip, json!({ "Item": serde_json::from_str::<Value>(json)? }));
+    Ok(())
+}
+
+#[test]
+fn test_unknown_entity() {
+    let result = Document::from_xml("<Root>&nbsp;</Root>");
+    assert!(matches!(result, Err(ConversionError::Xml(_))));
+
+    let document = Document::from_xml("<Root>&lt;&#65;&gt;</Root>").unwrap();
+    assert_eq!(document.root().unwrap().text(), "<A>");
+}
+
+#[test]
+fn test_xml_to_toml() -> Result<(), ConversionError> {
+    let xml = r#"<Config version="1"><Name>app</Name><Port xsi:type="xs:integer">8080</Port><Proxy><None/></Proxy><Hosts><HostsItem>a</HostsItem><HostsItem>b</HostsItem></Hosts></Config>"#;
+
+    let toml_string = xml_to_toml(xml)?;
+
+    assert_eq!(toml_string, "[Config]\n\"@version\" = \"1\"\nHosts = [\"a\", \"b\"]\nName = \"app\"\nPort = 8080\n");
+    Ok(())
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_xml_to_yaml() -> Result<(), ConversionError> {
+    let yaml = json_to_xml::document::xml_to_yaml(r#"<List><Entry>one</Entry><Entry>two</Entry></List>"#)?;
+
+    assert_eq!(yaml, "List:\n  Entry:\n  - one\n  - two\n");
+    Ok(())
+}
+
+#[test]
+fn test_unclosed_element() {
+    let result = Document::from_xml("<Root><Open></Root>");
+    assert!(matches!(result, Err(ConversionError::Xml(_))));
+
+    let result = Document::from_xml("<Root><Open>");
+    assert!(matches!(result, Err(ConversionError::Xml(_))));
+}
@@ -0,0 +1,77 @@
+use json_to_xml::document::{xml_to_json_with_options, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options};
+use json_to_xml::options::{ConversionOptions, EmptyArray};
+use serde_json::{json, Value};
+
+fn with_empty_array(empty_array: EmptyArray) -> ConversionOptions {
+    ConversionOptions { empty_array, ..Default::default() }
+}
+
+#[test]
+fn test_empty_array_element() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"post": {"tags": [], "title": "Hi"}}"#)?;
+    assert!(xml.ends_with("<Post>\n  <Tags></Tags>\n  <Title>Hi</Title>\n</Post>"));
+
+    let xml = json_to_xml(r#"{"matrix": [[], [1]]}"#)?;
+    assert!(xml.ends_with("<Matrix>\n  <MatrixItem></MatrixItem>\n  <MatrixItem>\n    <MatrixItemItem>1</MatrixItemItem>\n  </MatrixItem>\n</Matrix>"));
+    Ok(())
+}
+
+#[test]
+fn test_empty_array_omitted() -> Result<(), ConversionError> {
+    let options = with_empty_array(EmptyArray::Omit);
+
+    let xml = json_to_xml_with_options(r#"{"post": {"tags": [], "title": "Hi"}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Post>\n  <Title>Hi</Title>\n</Post>"));
+
+    let xml = json_to_xml_with_options(r#"{"tags": [], "title": "Hi"}"#, "Root", &options)?;
+    assert!(xml.ends_with("-->\n<Title>Hi</Title>"));
+    Ok(())
+}
+
+#[test]
+fn test_empty_array_self_closing() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_options(r#"{"post": {"tags": []}}"#, "Root", &with_empty_array(EmptyArray::SelfClosing))?;
+
+    assert!(xml.ends_with("<Post>\n  <Tags/>\n</Post>"));
+    Ok(())
+}
+
+#[test]
+fn test_empty_array_marked() -> Result<(), ConversionError> {
+    let options = with_empty_array(EmptyArray::Marked("json:array".to_string()));
+
+    let xml = json_to_xml_with_options(r#"{"@xmlns:json": "urn:json", "tags": [], "items": [[]]}"#, "Root", &options)?;
+    assert!(xml.contains(r#"<Tags json:array="true"/>"#));
+    assert!(xml.contains(r#"<ItemsItem json:array="true"/>"#));
+
+    let xml = json_to_xml_with_options("[]", "Root", &options)?;
+    assert!(xml.ends_with(r#"<Root json:array="true"/>"#));
+    Ok(())
+}
+
+#[test]
+fn test_marked_empty_array_round_trip() -> Result<(), ConversionError> {
+    let options = with_empty_array(EmptyArray::Marked("array".to_string()));
+    let value = json!({ "Post": { "Tags": [], "Title": "Hi", "Empty": {}, "Blank": "" } });
+
+    let xml = json_to_xml_with_options(&value.to_string(), "Root", &options)?;
+    let json: Value = serde_json::from_str(&xml_to_json_with_options(&xml, &options)?)?;
+
+    assert_eq!(json, value);
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    for empty_array in [EmptyArray::Element, EmptyArray::Omit, EmptyArray::SelfClosing, EmptyArray::Marked("array".to_string())] {
+        let options = with_empty_array(empty_array);
+
+        for json in [r#"{"post": {"tags": [], "title": "Hi"}}"#, r#"{"matrix": [[], [1]]}"#, "[[]]", "[]"] {
+            let value: Value = serde_json::from_str(json)?;
+            assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use json_to_xml::format::{format_number, to_plain_notation};
+use json_to_xml::options::NumberFormat;
+
+use serde_json::Number;
+
+#[test]
+fn test_plain_notation() {
+    assert_eq!(to_plain_notation("1e21"), "1000000000000000000000");
+    assert_eq!(to_plain_notation("1.5E3"), "1500");
+    assert_eq!(to_plain_notation("-2.5e-3"), "-0.0025");
+    assert_eq!(to_plain_notation("1.25e1"), "12.5");
+    assert_eq!(to_plain_notation("0e10"), "0");
+    assert_eq!(to_plain_notation("42.10"), "42.10");
+}
+
+#[test]
+fn test_plain_notation_keeps_huge_exponents() {
+    assert_eq!(to_plain_notation("1e999999999"), "1e999999999");
+    assert_eq!(to_plain_notation("-2.5E-99999999999999999999"), "-2.5E-99999999999999999999");
+    assert_eq!(to_plain_notation("1e-1000").len(), 1002);
+
+    let format = NumberFormat { decimal_places: Some(2), ..Default::default() };
+    assert_eq!(format_number(&Number::from_f64(f64::MAX).unwrap(), &format).len(), 312);
+}
+
+#[test]
+fn test_decimal_places() {
+    let format = NumberFormat { decimal_places: Some(2), ..Default::default() };
+
+    assert_eq!(format_number(&Number::from_f64(2.345).unwrap(), &format), "2.35");
+    assert_eq!(format_number(&Number::from_f64(-0.001).unwrap(), &format), "0.00");
+    assert_eq!(format_number(&Number::from_f64(9.999).unwrap(), &format), "10.00");
+    assert_eq!(format_number(&Number::from(12), &format), "12.00");
+
+    let format = NumberFormat { decimal_places: Some(0), ..Default::default() };
+    assert_eq!(format_number(&Number::from_f64(-2.5).unwrap(), &format), "-3");
+}
+
+#[test]
+fn test_default_format_is_unchanged() {
+    let format = NumberFormat::default();
+
+    assert_eq!(format_number(&Number::from_f64(1e21).unwrap(), &format), "1e21");
+    assert_eq!(format_number(&Number::from(-5), &format), "-5");
+}
@@ -0,0 +1,152 @@
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+use json_to_xml::json5::{json5_to_xml, parse_json5, Json5Options};
+use json_to_xml::options::{ConversionOptions, RootPolicy};
+
+use serde_json::json;
+
+#[test]
+fn test_parse_json5_syntax() -> Result<(), ConversionError> {
+    let value = parse_json5(r#"
+    // A JSON5 document
+    {
+        unquoted: 'single \'quoted\'',
+        "double": "line \
+continued",
+        hex: -0xFF,
+        leading: .5,
+        trailing: 2.,
+        positive: +1.5,
+        infinity: -Infinity,
+        escapes: '\x41é😀\t',
+        $dollar_key: null,
+        list: [1, 2, /* inline */ 3,],
+    }
+    "#)?;
+
+    assert_eq!(value, json!({
+        "unquoted": "single 'quoted'",
+        "double": "line continued",
+        "hex": -255,
+        "leading": 0.5,
+        "trailing": 2.0,
+        "positive": 1.5,
+        "infinity": "-Infinity",
+        "escapes": "Aé😀\t",
+        "$dollar_key": null,
+        "list": [1, 2, 3],
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_json5_without_comments_matches_json() -> Result<(), ConversionError> {
+    let json5 = "{'@id': 1, person: {name: 'Alice', tags: ['a', 'b',],}, /* dropped */}";
+    let json = r#"{"@id": 1, "person": {"name": "Alice", "tags": ["a", "b"]}}"#;
+
+    assert_eq!(json5_to_xml(json5, "Root", &Json5Options::default())?, json_to_xml_with_root(json, "Root")?);
+    Ok(())
+}
+
+#[test]
+fn test_json5_comments_are_kept() -> Result<(), ConversionError> {
+    let json5 = r#"
+    /* header */
+    {
+        "@version": 2,
+        // the people
+        people: {
+            alice: {age: 30}, // oldest
+            bob: {
+                tags: ['x', /* in array */ 'y'],
+                /* end of bob */
+            },
+        },
+    }
+    // footer
+    "#;
+
+    let xml = json5_to_xml(json5, "Root", &Json5Options { keep_comments: true, ..Default::default() })?;
+
+    let expected = r#"<!--header-->
+<Root version="2">
+  <!--the people-->
+  <People>
+    <Alice>
+      <Age>30</Age>
+    </Alice>
+    <!--oldest-->
+    <Bob>
+      <Tags>
+        <TagsItem>x</TagsItem>
+        <!--in array-->
+        <TagsItem>y</TagsItem>
+      </Tags>
+      <!--end of bob-->
+    </Bob>
+  </People>
+</Root>
+<!--footer-->"#;
+
+    assert!(xml.ends_with(expected), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_json5_comments_keep_the_root_logic() -> Result<(), ConversionError> {
+    let options = Json5Options { keep_comments: true, ..Default::default() };
+
+    let xml = json5_to_xml("// c\n5", "Root", &options)?;
+    assert!(xml.ends_with("<!--c-->\n<Root>5</Root>"), "{}", xml);
+
+    let xml = json5_to_xml("[1, /* two */ 2]", "Root", &options)?;
+    assert!(xml.ends_with("<Root>\n  <RootItem>1</RootItem>\n  <!--two-->\n  <RootItem>2</RootItem>\n</Root>"), "{}", xml);
+
+    let xml = json5_to_xml("{a: 1, // one\n b: 2}", "Root", &options)?;
+    assert!(xml.ends_with("<Root>\n  <A>1</A>\n  <!--one-->\n  <B>2</B>\n</Root>"), "{}", xml);
+
+    let options = Json5Options {
+        keep_comments: true,
+        conversion: ConversionOptions { root_policy: RootPolicy::Error, ..Default::default() },
+    };
+    assert!(matches!(json5_to_xml("{a: 1, // one\n b: 2}", "Root", &options), Err(ConversionError::RootCount(2))));
+    Ok(())
+}
+
+#[test]
+fn test_json5_comments_of_attributes() -> Result<(), ConversionError> {
+    let options = Json5Options { keep_comments: true, ..Default::default() };
+
+    let xml = json5_to_xml("{item: {'@id': 1, // the id\n}}", "Root", &options)?;
+    assert!(xml.ends_with("<!--the id-->\n<Item id=\"1\"/>"), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_json5_comment_text_is_escaped() -> Result<(), ConversionError> {
+    let xml = json5_to_xml("{a: 1, // a -- b -\n}", "Root", &Json5Options { keep_comments: true, ..Default::default() })?;
+
+    assert!(xml.contains("<!--a - - b - -->"));
+
+    let xml = json5_to_xml("{a: 1, // a---b ----\n}", "Root", &Json5Options { keep_comments: true, ..Default::default() })?;
+    assert!(xml.contains("<!--a- - -b - - - - -->"));
+
+    let result = json5_to_xml("{a: 1, // bell \u{7}\n}", "Root", &Json5Options { keep_comments: true, ..Default::default() });
+    assert!(matches!(result, Err(ConversionError::InvalidCharacter { character: '\u{7}', .. })), "{:?}", result);
+    Ok(())
+}
+
+#[test]
+fn test_json5_syntax_errors() {
+    let error = |input: &str| match parse_json5(input) {
+        Err(ConversionError::Json5 { line, column, .. }) => (line, column),
+        result => panic!("expected a JSON5 error, got {:?}", result),
+    };
+
+    assert_eq!(error("{\n  a: 1,\n  b: tru\n}"), (3, 6));
+    assert_eq!(error("{a: 'open"), (1, 10));
+    assert_eq!(error("[1 2]"), (1, 4));
+    assert_eq!(error("{a: 007}"), (1, 5));
+    assert_eq!(error("/* never closed"), (1, 1));
+    assert_eq!(error(&"[".repeat(200)), (1, 130));
+}
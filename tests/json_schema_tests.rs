@@ -0,0 +1,263 @@
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml_with_options, json_to_xml_with_root};
+use json_to_xml::json_schema::json_to_xml_with_json_schema;
+use json_to_xml::options::{ArrayMarker, ConversionOptions, EmptyArray, NestedArray, RootPolicy, TypeAnnotation, JSON_NAMESPACE};
+use json_to_xml::rules::{Rule, RuleAction};
+use std::collections::HashMap;
+
+#[test]
+fn test_schema_without_hints_matches_default_conversion() -> Result<(), ConversionError> {
+    let json = r#"{
+        "@version": "1",
+        "person": {"@id": "42", "name": "Alice", "tags": ["a", "b"], "pets": [{"name": "Rex"}, {"name": "Tom"}], "nickname": null}
+    }"#;
+    let schema = r#"{"type": "object", "properties": {"person": {"type": "object"}}}"#;
+
+    assert_eq!(json_to_xml_with_json_schema(json, schema, "Root", &ConversionOptions::default())?, json_to_xml_with_root(json, "Root")?);
+    Ok(())
+}
+
+#[test]
+fn test_schema_without_hints_matches_conversion_with_options() -> Result<(), ConversionError> {
+    let json = r#"{"a": {"weight": {"@unit": "kg", "$text": 42}, "list": [{}, {"x": 1}], "codes": [[1, 2], []]}}"#;
+    let schema = r#"{
+        "properties": {
+            "a": {
+                "properties": {
+                    "weight": {"type": "object"},
+                    "list": {"type": "array", "items": {"type": "object"}},
+                    "codes": {"type": "array", "items": {"type": "array"}}
+                }
+            }
+        }
+    }"#;
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::XsiType,
+        array_marker: Some(ArrayMarker::default()),
+        rules: vec![Rule::new("$.a.list[1].x", RuleAction::Attribute)?],
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+
+    assert_eq!(xml, json_to_xml_with_options(json, "Root", &options)?);
+    assert!(xml.contains("<Weight unit=\"kg\" xsi:type=\"xs:integer\">42</Weight>"), "{}", xml);
+    assert_eq!(xml.matches("<List ").count(), 1, "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_attribute_and_name_hints() -> Result<(), ConversionError> {
+    let json = r#"{"book": {"isbn": "978-3-16-148410-0", "title": "The Book", "@lang": "en"}}"#;
+    let schema = r#"{
+        "properties": {
+            "book": {
+                "xml": {"name": "book"},
+                "properties": {
+                    "isbn": {"type": "string", "xml": {"attribute": true, "name": "ISBN"}},
+                    "title": {"type": "string", "xml": {"name": "title"}}
+                }
+            }
+        }
+    }"#;
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &ConversionOptions::default())?;
+
+    assert!(xml.contains("<book lang=\"en\" ISBN=\"978-3-16-148410-0\">"));
+    assert!(xml.contains("<title>The Book</title>"));
+    assert!(!xml.contains("<Isbn>"));
+    assert!(!xml.contains("<Root"));
+    Ok(())
+}
+
+#[test]
+fn test_wrapped_and_unwrapped_arrays() -> Result<(), ConversionError> {
+    let json = r#"{"order": {"lines": [{"sku": "A"}, {"sku": "B"}], "notes": ["x", "y"], "codes": [1, 2]}}"#;
+    let schema = r##"{
+        "properties": {
+            "order": {
+                "properties": {
+                    "lines": {"type": "array", "xml": {"name": "Lines", "wrapped": true}, "items": {"$ref": "#/$defs/Line"}},
+                    "notes": {"type": "array", "items": {"type": "string", "xml": {"name": "note"}}},
+                    "codes": {"type": "array", "xml": {"name": "code"}}
+                }
+            }
+        },
+        "$defs": {"Line": {"type": "object", "xml": {"name": "line"}}}
+    }"##;
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &ConversionOptions::default())?;
+
+    assert!(xml.contains("<Lines>\n    <line>\n      <Sku>A</Sku>\n    </line>\n    <line>\n      <Sku>B</Sku>\n    </line>\n  </Lines>"));
+    assert!(xml.contains("<note>x</note>\n  <note>y</note>"));
+    assert!(xml.contains("<code>1</code>\n  <code>2</code>"));
+    assert!(!xml.contains("<Notes>"));
+    Ok(())
+}
+
+#[test]
+fn test_namespace_hints_are_declared_once() -> Result<(), ConversionError> {
+    let json = r#"{"@id": 1, "item": {"name": "Pen", "price": {"amount": 2, "currency": "EUR"}}}"#;
+    let schema = r##"{
+        "xml": {"name": "catalog", "namespace": "urn:catalog"},
+        "properties": {
+            "item": {
+                "allOf": [{"$ref": "#/components/schemas/Item"}]
+            }
+        },
+        "components": {
+            "schemas": {
+                "Item": {
+                    "xml": {"prefix": "it", "namespace": "urn:item"},
+                    "properties": {
+                        "name": {"xml": {"prefix": "it", "namespace": "urn:item"}},
+                        "price": {
+                            "properties": {
+                                "currency": {"xml": {"attribute": true, "prefix": "cur", "namespace": "urn:currency"}}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }"##;
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &ConversionOptions::default())?;
+
+    assert!(xml.contains("<catalog xmlns=\"urn:catalog\" id=\"1\">"));
+    assert!(xml.contains("<it:Item xmlns:it=\"urn:item\">"));
+    assert!(xml.contains("<it:Name>Pen</it:Name>"));
+    assert!(xml.contains("<Price xmlns:cur=\"urn:currency\" cur:currency=\"EUR\">"));
+    assert!(xml.contains("</catalog>"));
+    Ok(())
+}
+
+#[test]
+fn test_invalid_json_schema() {
+    let result = json_to_xml_with_json_schema("{}", "{not json", "Root", &ConversionOptions::default());
+
+    assert!(matches!(result, Err(ConversionError::Json(_))));
+}
+
+#[test]
+fn test_root_policy() -> Result<(), ConversionError> {
+    let json = r#"{"item": [{"sku": "a"}, {"sku": "b"}]}"#;
+    let schema = r#"{"properties": {"item": {"type": "array", "items": {"type": "object", "xml": {"name": "product"}}}}}"#;
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Catalog", &ConversionOptions::default())?;
+    assert!(xml.ends_with("<Catalog>\n  <product>\n    <Sku>a</Sku>\n  </product>\n  <product>\n    <Sku>b</Sku>\n  </product>\n</Catalog>"), "{}", xml);
+
+    let options = ConversionOptions { root_policy: RootPolicy::Error, ..Default::default() };
+    assert!(matches!(json_to_xml_with_json_schema(json, schema, "Catalog", &options), Err(ConversionError::RootCount(2))));
+
+    let options = ConversionOptions { root_policy: RootPolicy::Fragment, ..Default::default() };
+    let xml = json_to_xml_with_json_schema(json, schema, "Catalog", &options)?;
+    assert_eq!(xml, "<product>\n  <Sku>a</Sku>\n</product>\n<product>\n  <Sku>b</Sku>\n</product>");
+    Ok(())
+}
+
+#[test]
+fn test_empty_arrays() -> Result<(), ConversionError> {
+    let json = r#"{"order": {"lines": [], "notes": [], "codes": []}}"#;
+    let schema = r#"{
+        "properties": {
+            "order": {
+                "properties": {
+                    "lines": {"type": "array", "xml": {"wrapped": true}, "items": {"type": "object", "xml": {"name": "line"}}},
+                    "notes": {"type": "array", "items": {"type": "string"}},
+                    "codes": {"type": "array", "xml": {"name": "code"}}
+                }
+            }
+        }
+    }"#;
+
+    let options = ConversionOptions { empty_array: EmptyArray::Marked("empty".to_string()), ..Default::default() };
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+    assert!(xml.ends_with("<Order>\n  <Lines empty=\"true\"/>\n  <Notes empty=\"true\"/>\n</Order>"), "{}", xml);
+
+    let options = ConversionOptions { empty_array: EmptyArray::Omit, ..Default::default() };
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+    assert!(xml.ends_with("<Order/>"), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content() -> Result<(), ConversionError> {
+    let json = r#"{"p": {"@class": "intro", "$content": ["Hello ", {"b": "world"}]}}"#;
+    let schema = r#"{"properties": {"p": {"xml": {"name": "para"}}}}"#;
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &ConversionOptions::default())?;
+
+    assert!(xml.ends_with("<para class=\"intro\">Hello <B>world</B></para>"), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_rules() -> Result<(), ConversionError> {
+    let json = r#"{"order": {"id": 7, "secret": "x", "note": "<b>", "lines": [{"sku": "A"}]}}"#;
+    let schema = r#"{
+        "properties": {
+            "order": {
+                "properties": {
+                    "lines": {"type": "array", "xml": {"wrapped": true}, "items": {"xml": {"name": "line"}}},
+                    "note": {"type": "string"}
+                }
+            }
+        }
+    }"#;
+    let options = ConversionOptions {
+        rules: vec![
+            Rule::new("/order/id", RuleAction::Attribute)?,
+            Rule::new("/order/secret", RuleAction::Skip)?,
+            Rule::new("/order/note", RuleAction::CData)?,
+        ],
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+    assert!(xml.ends_with(concat!(
+        "<Order id=\"7\">\n  <Lines>\n    <line>\n      <Sku>A</Sku>\n    </line>\n  </Lines>\n",
+        "  <Note><![CDATA[<b>]]></Note>\n</Order>",
+    )), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_rename() -> Result<(), ConversionError> {
+    let json = r#"{"customer": {"@cust_id": 3, "addr_line_1": "Main St", "name": "Ann", "phone_no": "555"}}"#;
+    let schema = r#"{"properties": {"customer": {"properties": {"addr_line_1": {"type": "string"}, "name": {"xml": {"name": "fullName"}}}}}}"#;
+    let options = ConversionOptions {
+        rename: HashMap::from([
+            ("@cust_id".to_string(), "@id".to_string()),
+            ("addr_line_1".to_string(), "AddressLine1".to_string()),
+            ("name".to_string(), "Name".to_string()),
+            ("/customer/phone_no".to_string(), "Phone".to_string()),
+        ]),
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+    assert!(xml.ends_with("<Customer id=\"3\">\n  <AddressLine1>Main St</AddressLine1>\n  <fullName>Ann</fullName>\n  <Phone>555</Phone>\n</Customer>"), "{}", xml);
+
+    let options = ConversionOptions { rename: HashMap::from([("name".to_string(), "full name".to_string())]), ..Default::default() };
+    assert!(matches!(json_to_xml_with_json_schema(json, schema, "Root", &options), Err(ConversionError::InvalidRename { .. })));
+    Ok(())
+}
+
+#[test]
+fn test_array_marker_and_nested_array_names() -> Result<(), ConversionError> {
+    let json = r#"{"m": [[1, 2]], "t": [1]}"#;
+    let schema = r#"{"properties": {"t": {"type": "array", "xml": {"name": "tag"}}}}"#;
+    let options = ConversionOptions {
+        array_marker: Some(ArrayMarker::default()),
+        nested_array: NestedArray::Named(vec!["row".to_string(), "cell".to_string()]),
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_json_schema(json, schema, "Root", &options)?;
+
+    assert!(xml.contains(&format!("<Root xmlns:json=\"{}\">", JSON_NAMESPACE)));
+    assert!(xml.contains("<M json:array=\"true\">\n    <Row json:array=\"true\">\n      <Cell>1</Cell>\n      <Cell>2</Cell>\n    </Row>\n  </M>"));
+    assert!(xml.contains("<tag json:array=\"true\">1</tag>"));
+    Ok(())
+}
@@ -1,5 +1,6 @@
-use json_to_xml::generate_xml::{json_to_xml};
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options};
 use json_to_xml::error::ConversionError;
+use json_to_xml::options::{BooleanFormat, ConversionOptions, NumberFormat, TypeAnnotation};
 
 #[test]
 fn test_basic_conversion() -> Result<(), ConversionError> {
@@ -198,4 +199,192 @@ fn test_deep_nested_mixed_arrays() -> Result<(), ConversionError> {
     assert!(xml.contains("<D>50</D>"));
     assert!(xml.contains("<CItem>60</CItem>"));
     Ok(())
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_xsi_type_annotation() -> Result<(), ConversionError> {
+    let json = r#"{
+        "person": {
+            "name": "Alice",
+            "age": 30,
+            "height": 1.68,
+            "active": true,
+            "tags": ["a", 1]
+        }
+    }"#;
+
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::XsiType,
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+
+    assert!(xml.contains("xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\""));
+    assert!(xml.contains("xmlns:xs=\"http://www.w3.org/2001/XMLSchema\""));
+    assert!(xml.contains("<Name xsi:type=\"xs:string\">Alice</Name>"));
+    assert!(xml.contains("<Age xsi:type=\"xs:integer\">30</Age>"));
+    assert!(xml.contains("<Height xsi:type=\"xs:decimal\">1.68</Height>"));
+    assert!(xml.contains("<Active xsi:type=\"xs:boolean\">true</Active>"));
+    assert!(xml.contains("<TagsItem xsi:type=\"xs:integer\">1</TagsItem>"));
+    assert!(xml.contains("<Person>"));
+    Ok(())
+}
+
+#[test]
+fn test_custom_type_attribute() -> Result<(), ConversionError> {
+    let json = r#"{
+        "person": {
+            "age": "30",
+            "years": 30,
+            "nothing": null
+        }
+    }"#;
+
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::Attribute("type".to_string()),
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+
+    assert!(xml.contains("<Age type=\"string\">30</Age>"));
+    assert!(xml.contains("<Years type=\"number\">30</Years>"));
+    assert!(xml.contains("<Nothing type=\"null\">"));
+    assert!(!xml.contains("<Person type="));
+    Ok(())
+}
+
+#[test]
+fn test_type_attribute_collision() {
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::Attribute("type".to_string()),
+        ..Default::default()
+    };
+    let result = json_to_xml_with_options(r#"{"o": {"@type": "x", "$text": 1}}"#, "Root", &options);
+
+    assert!(matches!(result, Err(ConversionError::DuplicateAttribute { ref path, ref name }) if path == "/o" && name == "type"), "{:?}", result);
+}
+
+#[test]
+fn test_xsi_type_of_exponent_numbers() -> Result<(), ConversionError> {
+    let json = r#"{"big": 1e21, "small": 1e-7, "plain": 1.5}"#;
+
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::XsiType,
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("<Big xsi:type=\"xs:double\">1e21</Big>"));
+    assert!(xml.contains("<Small xsi:type=\"xs:double\">1e-7</Small>"));
+    assert!(xml.contains("<Plain xsi:type=\"xs:decimal\">1.5</Plain>"));
+
+    let options = ConversionOptions {
+        type_annotation: TypeAnnotation::XsiType,
+        number_format: NumberFormat { plain_notation: true, ..Default::default() },
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("<Big xsi:type=\"xs:decimal\">1000000000000000000000</Big>"));
+    Ok(())
+}
+
+#[test]
+fn test_number_format_options() -> Result<(), ConversionError> {
+    let json = r#"{
+        "big": 1e21,
+        "price": 0.30000000000000004,
+        "count": 7
+    }"#;
+
+    let xml = json_to_xml(json)?;
+    assert!(xml.contains("<Big>1e21</Big>"));
+
+    let options = ConversionOptions {
+        number_format: NumberFormat { plain_notation: true, ..Default::default() },
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("<Big>1000000000000000000000</Big>"));
+    assert!(xml.contains("<Price>0.30000000000000004</Price>"));
+
+    let options = ConversionOptions {
+        number_format: NumberFormat { decimal_places: Some(2), ..Default::default() },
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("<Big>1000000000000000000000.00</Big>"));
+    assert!(xml.contains("<Price>0.30</Price>"));
+    assert!(xml.contains("<Count>7.00</Count>"));
+    Ok(())
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn test_arbitrary_precision_passthrough() -> Result<(), ConversionError> {
+    let json = r#"{
+        "id": 123456789012345678901234567890,
+        "amount": 0.100000000000000000000000001
+    }"#;
+
+    let xml = json_to_xml(json)?;
+
+    assert!(xml.contains("<Id>123456789012345678901234567890</Id>"));
+    assert!(xml.contains("<Amount>0.100000000000000000000000001</Amount>"));
+    Ok(())
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[test]
+fn test_huge_exponent_keeps_exponent_notation() -> Result<(), ConversionError> {
+    let options = ConversionOptions {
+        number_format: NumberFormat { decimal_places: Some(2), ..Default::default() },
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_options(r#"{"big": 1e999999999}"#, "Root", &options)?;
+
+    assert!(xml.contains("<Big>1e999999999</Big>"));
+    Ok(())
+}
+
+#[test]
+fn test_boolean_format_options() -> Result<(), ConversionError> {
+    let json = r#"{
+        "item": {
+            "@enabled": true,
+            "@count": 3,
+            "visible": false,
+            "flags": [true, false]
+        }
+    }"#;
+
+    let xml = json_to_xml(json)?;
+    assert!(xml.contains("enabled=\"true\""));
+    assert!(xml.contains("count=\"3\""));
+    assert!(xml.contains("<Visible>false</Visible>"));
+
+    let options = ConversionOptions {
+        boolean_format: BooleanFormat::OneZero,
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("enabled=\"1\""));
+    assert!(xml.contains("<Visible>0</Visible>"));
+    assert!(xml.contains("<FlagsItem>1</FlagsItem>"));
+
+    let options = ConversionOptions {
+        boolean_format: BooleanFormat::YesNo,
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("enabled=\"yes\""));
+    assert!(xml.contains("<Visible>no</Visible>"));
+
+    let options = ConversionOptions {
+        boolean_format: BooleanFormat::Capitalized,
+        ..Default::default()
+    };
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+    assert!(xml.contains("enabled=\"True\""));
+    assert!(xml.contains("<Visible>False</Visible>"));
+    Ok(())
+}
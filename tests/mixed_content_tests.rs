@@ -0,0 +1,79 @@
+use json_to_xml::document::{xml_to_json, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml_with_options, json_to_xml_with_root};
+use json_to_xml::options::{ConversionOptions, NumberFormat};
+use serde_json::{json, Value};
+
+#[test]
+fn test_mixed_content_in_order() -> Result<(), ConversionError> {
+    let json = r#"{"article": {"title": "Greeting", "p": {"@class": "intro", "$content": ["Hello ", {"b": "world"}, ", bye"]}}}"#;
+
+    let xml = json_to_xml_with_root(json, "Root")?;
+
+    assert!(xml.contains("<Article>\n  <P class=\"intro\">Hello <B>world</B>, bye</P>\n  <Title>Greeting</Title>\n</Article>"));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content_keeps_whitespace() -> Result<(), ConversionError> {
+    let json = r#"{"p": {"$content": ["  two  spaces ", {"em": {"strong": "nested"}}, " ", {"a": {"@href": "/x", "$text": "link"}}, "\n"]}}"#;
+
+    let xml = json_to_xml_with_root(json, "Root")?;
+
+    assert!(xml.contains("<P>  two  spaces <Em><Strong>nested</Strong></Em> <A href=\"/x\">link</A>\n</P>"));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content_scalars() -> Result<(), ConversionError> {
+    let json = r#"{"line": {"$content": ["Total: ", 4.5, " ", {"unit": "EUR"}, " paid: ", true, null]}}"#;
+    let options = ConversionOptions { number_format: NumberFormat { decimal_places: Some(2), ..Default::default() }, ..Default::default() };
+
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+
+    assert!(xml.contains("<Line>Total: 4.50 <Unit>EUR</Unit> paid: true<None/></Line>"));
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    let json = r#"{"@lang": "en", "p": {"$content": ["Hello ", {"b": "world"}, ", ", {"i": "bye"}]}, "note": "x"}"#;
+    let value: Value = serde_json::from_str(json)?;
+
+    let document = Document::from_value(&value, "Doc", &ConversionOptions::default())?;
+
+    assert_eq!(document.to_xml()?, json_to_xml_with_root(json, "Doc")?);
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content_round_trip() -> Result<(), ConversionError> {
+    let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Doc>
+  <P class="intro">Hello <B>bold <I>and italic</I></B> <Em>x</Em>, bye</P>
+  <List>
+    <Entry>one</Entry>
+  </List>
+</Doc>"#;
+
+    let document = Document::from_xml(xml)?;
+    assert_eq!(document.to_xml()?, xml);
+
+    let value: Value = serde_json::from_str(&xml_to_json(xml)?)?;
+    assert_eq!(value["Doc"]["P"], json!({
+        "@class": "intro",
+        "$content": ["Hello ", {"B": {"$content": ["bold ", {"I": "and italic"}]}}, " ", {"Em": "x"}, ", bye"]
+    }));
+
+    let regenerated = json_to_xml_with_root(&value["Doc"].to_string(), "Doc")?;
+    assert!(regenerated.contains(r#"<P class="intro">Hello <B>bold <I>and italic</I></B> <Em>x</Em>, bye</P>"#));
+    Ok(())
+}
+
+#[test]
+fn test_single_value_content() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_root(r#"{"k1": {"$content": "text"}, "k2": {"$content": 7}, "k3": {"$content": {"b": "bold"}}}"#, "Root")?;
+
+    assert!(xml.ends_with("<Root>\n  <K1>text</K1>\n  <K2>7</K2>\n  <K3>\n    <B>bold</B>\n  </K3>\n</Root>"), "{}", xml);
+    Ok(())
+}
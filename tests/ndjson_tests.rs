@@ -0,0 +1,107 @@
+use json_to_xml::error::ConversionError;
+use json_to_xml::ndjson::{ndjson_to_xml, MalformedLines, NdjsonOptions};
+use json_to_xml::options::{ArrayMarker, ConversionOptions, RootPolicy};
+
+#[test]
+fn test_ndjson_records_share_one_root() -> Result<(), ConversionError> {
+    let input = "{\"level\": \"info\", \"tags\": [\"a\", \"b\"]}\r\n\n{\"level\": \"warn\", \"detail\": {\"code\": 7}}\n{}";
+    let options = NdjsonOptions {
+        root: "events".to_string(),
+        record: "event".to_string(),
+        ..Default::default()
+    };
+
+    let mut output = Vec::new();
+    let summary = ndjson_to_xml(input.as_bytes(), &mut output, &options)?;
+    let xml = String::from_utf8(output)?;
+
+    assert_eq!(summary.records, 3);
+    assert!(summary.skipped.is_empty());
+    assert!(xml.contains("<Events>\n  <Event>\n    <Level>info</Level>"));
+    assert!(xml.contains("<TagsItem>b</TagsItem>"));
+    assert!(xml.contains("<Detail>\n      <Code>7</Code>\n    </Detail>"));
+    assert!(xml.contains("<Event/>"));
+    assert!(xml.ends_with("</Events>"));
+    Ok(())
+}
+
+#[test]
+fn test_ndjson_skips_malformed_lines() -> Result<(), ConversionError> {
+    let input = "{\"id\": 1}\n{\"id\": \n\n{\"id\": 3}\n[1,";
+    let options = NdjsonOptions { malformed_lines: MalformedLines::Skip, ..Default::default() };
+
+    let mut output = Vec::new();
+    let summary = ndjson_to_xml(input.as_bytes(), &mut output, &options)?;
+    let xml = String::from_utf8(output)?;
+
+    assert_eq!(summary.records, 2);
+    assert_eq!(summary.skipped.iter().map(|skipped| skipped.line).collect::<Vec<_>>(), vec![2, 5]);
+    assert!(xml.contains("<Id>1</Id>") && xml.contains("<Id>3</Id>"));
+    assert!(xml.ends_with("</Records>"));
+    Ok(())
+}
+
+#[test]
+fn test_ndjson_aborts_on_malformed_line() {
+    let input = "{\"id\": 1}\n{\"id\": 2}\n{id: 3}\n";
+
+    let mut output = Vec::new();
+    let result = ndjson_to_xml(input.as_bytes(), &mut output, &NdjsonOptions::default());
+
+    assert!(matches!(result, Err(ConversionError::Line { line: 3, .. })));
+    assert!(String::from_utf8_lossy(&output).contains("<Id>2</Id>"));
+}
+
+#[test]
+fn test_ndjson_declares_array_marker_namespace() -> Result<(), ConversionError> {
+    let options = NdjsonOptions {
+        conversion: ConversionOptions { array_marker: Some(ArrayMarker::default()), ..Default::default() },
+        ..Default::default()
+    };
+
+    let mut output = Vec::new();
+    ndjson_to_xml("{\"tags\": [\"a\"]}\n".as_bytes(), &mut output, &options)?;
+    let xml = String::from_utf8(output)?;
+
+    assert!(xml.contains("<Records xmlns:json=\"urn:json-to-xml:json\">"));
+    assert!(xml.contains("<Tags json:array=\"true\">"));
+    Ok(())
+}
+
+#[test]
+fn test_ndjson_rejects_invalid_root_name() {
+    let options = NdjsonOptions { root: "my root<".to_string(), ..Default::default() };
+
+    let mut output = Vec::new();
+    let result = ndjson_to_xml("{\"id\": 1}\n".as_bytes(), &mut output, &options);
+
+    assert!(matches!(result, Err(ConversionError::InvalidName { name, .. }) if name == "My root<"));
+    assert!(output.is_empty());
+}
+
+#[test]
+fn test_ndjson_root_policy() -> Result<(), ConversionError> {
+    let input = "{\"id\": 1}\n{\"id\": 2}\n";
+    let with_policy = |root_policy| NdjsonOptions {
+        conversion: ConversionOptions { root_policy, array_marker: Some(ArrayMarker::default()), ..Default::default() },
+        ..Default::default()
+    };
+
+    let mut output = Vec::new();
+    ndjson_to_xml(input.as_bytes(), &mut output, &with_policy(RootPolicy::Fragment))?;
+    assert_eq!(
+        String::from_utf8(output)?,
+        "<Record xmlns:json=\"urn:json-to-xml:json\">\n  <Id>1</Id>\n</Record>\n<Record xmlns:json=\"urn:json-to-xml:json\">\n  <Id>2</Id>\n</Record>"
+    );
+
+    let mut output = Vec::new();
+    let result = ndjson_to_xml(input.as_bytes(), &mut output, &with_policy(RootPolicy::Error));
+    assert!(matches!(result, Err(ConversionError::RootCount(2))));
+
+    let mut output = Vec::new();
+    ndjson_to_xml("{\"id\": 1}\n".as_bytes(), &mut output, &with_policy(RootPolicy::Error))?;
+    let xml = String::from_utf8(output)?;
+    assert!(xml.contains("-->\n<Record xmlns:json=\"urn:json-to-xml:json\">\n  <Id>1</Id>\n</Record>"));
+    assert!(!xml.contains("<Records"));
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use json_to_xml::document::{xml_to_json, xml_to_json_with_options, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options};
+use json_to_xml::options::{ArrayMarker, ConversionOptions, NestedArray, TopLevelArray};
+use serde_json::{json, Value};
+
+fn rows_and_cells() -> ConversionOptions {
+    ConversionOptions { nested_array: NestedArray::Named(vec!["row".to_string(), "cell".to_string()]), ..Default::default() }
+}
+
+fn read_back(xml: &str, options: &ConversionOptions) -> Result<Value, ConversionError> {
+    Ok(serde_json::from_str(&xml_to_json_with_options(xml, options)?)?)
+}
+
+#[test]
+fn test_item_suffix_levels() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"matrix": [[1, 2], [3]]}"#)?;
+
+    assert!(xml.ends_with(
+        "<Matrix>\n  <MatrixItem>\n    <MatrixItemItem>1</MatrixItemItem>\n    <MatrixItemItem>2</MatrixItemItem>\n  </MatrixItem>\n  <MatrixItem>\n    <MatrixItemItem>3</MatrixItemItem>\n  </MatrixItem>\n</Matrix>"
+    ));
+    assert_eq!(serde_json::from_str::<Value>(&xml_to_json(&xml)?)?, json!({ "Matrix": [["1", "2"], ["3"]] }));
+    Ok(())
+}
+
+#[test]
+fn test_named_levels() -> Result<(), ConversionError> {
+    let options = rows_and_cells();
+    let xml = json_to_xml_with_options(r#"{"matrix": [[1, 2], [3]], "tags": ["a"]}"#, "Root", &options)?;
+
+    assert!(xml.contains("<Matrix>\n    <Row>\n      <Cell>1</Cell>\n      <Cell>2</Cell>\n    </Row>\n    <Row>\n      <Cell>3</Cell>\n    </Row>\n  </Matrix>"));
+    assert!(xml.contains("<Tags>\n    <TagsItem>a</TagsItem>\n  </Tags>"));
+    assert_eq!(read_back(&xml, &options)?, json!({ "Root": { "Matrix": [["1", "2"], ["3"]], "Tags": ["a"] } }));
+    Ok(())
+}
+
+#[test]
+fn test_levels_beyond_names() -> Result<(), ConversionError> {
+    let options = rows_and_cells();
+    let xml = json_to_xml_with_options(r#"{"cube": [[[1]]]}"#, "Root", &options)?;
+
+    assert!(xml.ends_with("<Cube>\n  <Row>\n    <Cell>\n      <CellItem>1</CellItem>\n    </Cell>\n  </Row>\n</Cube>"));
+    assert_eq!(read_back(&xml, &options)?, json!({ "Cube": [[["1"]]] }));
+    Ok(())
+}
+
+#[test]
+fn test_named_top_level_levels() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_options("[[1], [2]]", "Grid", &rows_and_cells())?;
+    assert!(xml.ends_with("<Grid>\n  <Row>\n    <Cell>1</Cell>\n  </Row>\n  <Row>\n    <Cell>2</Cell>\n  </Row>\n</Grid>"));
+
+    let options = ConversionOptions { top_level_array: TopLevelArray::Named("line".to_string()), ..rows_and_cells() };
+    let xml = json_to_xml_with_options("[[1]]", "Grid", &options)?;
+    assert!(xml.ends_with("<Grid>\n  <Line>\n    <Cell>1</Cell>\n  </Line>\n</Grid>"));
+    Ok(())
+}
+
+#[test]
+fn test_marked_nested_round_trip() -> Result<(), ConversionError> {
+    let options = ConversionOptions { array_marker: Some(ArrayMarker::default()), ..rows_and_cells() };
+    let value = json!({ "Matrix": [["a", "b"], ["c"], []], "Single": [["d"]], "Cube": [[["e"]]] });
+
+    let xml = json_to_xml_with_options(&value.to_string(), "Root", &options)?;
+    let json = read_back(&xml, &options)?;
+
+    assert_eq!(json["Root"]["Matrix"], value["Matrix"]);
+    assert_eq!(json["Root"]["Single"], value["Single"]);
+    assert_eq!(json["Root"]["Cube"], value["Cube"]);
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    for options in [ConversionOptions::default(), rows_and_cells()] {
+        for json in [r#"{"matrix": [[1, 2], [3], []]}"#, r#"{"cube": [[[1]], [[]]]}"#, "[[1], 2]", r#"{"mixed": [1, [2], {"a": [3]}]}"#] {
+            let value: Value = serde_json::from_str(json)?;
+            assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use json_to_xml::document::Document;
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_options;
+use json_to_xml::json5::{json5_to_xml, Json5Options};
+use json_to_xml::options::ConversionOptions;
+use json_to_xml::rules::{Rule, RuleAction};
+use serde_json::Value;
+use std::collections::HashMap;
+
+fn with_rename(renames: &[(&str, &str)]) -> ConversionOptions {
+    let rename: HashMap<String, String> = renames.iter().map(|(key, name)| (key.to_string(), name.to_string())).collect();
+    ConversionOptions { rename, ..Default::default() }
+}
+
+#[test]
+fn test_global_rename() -> Result<(), ConversionError> {
+    let options = with_rename(&[("addr_line_1", "AddressLine1"), ("zip", "postalCode")]);
+
+    let xml = json_to_xml_with_options(r#"{"customer": {"addr_line_1": "1 Main St", "zip": "12345"}, "shop": {"zip": "54321"}}"#, "Root", &options)?;
+
+    assert!(xml.contains("<Customer>\n    <AddressLine1>1 Main St</AddressLine1>\n    <PostalCode>12345</PostalCode>\n  </Customer>"));
+    assert!(xml.contains("<Shop>\n    <PostalCode>54321</PostalCode>\n  </Shop>"));
+    Ok(())
+}
+
+#[test]
+fn test_path_qualified_rename() -> Result<(), ConversionError> {
+    let options = with_rename(&[("name", "FullName"), ("/shop/name", "ShopName"), ("/orders/1/id", "LastId")]);
+
+    let xml = json_to_xml_with_options(r#"{"customer": {"name": "Ann"}, "shop": {"name": "Corner"}, "orders": [{"id": 1}, {"id": 2}]}"#, "Root", &options)?;
+
+    assert!(xml.contains("<Customer>\n    <FullName>Ann</FullName>\n  </Customer>"));
+    assert!(xml.contains("<Shop>\n    <ShopName>Corner</ShopName>\n  </Shop>"));
+    assert!(xml.contains("<Orders>\n    <Id>1</Id>\n  </Orders>\n  <Orders>\n    <LastId>2</LastId>\n  </Orders>"));
+    Ok(())
+}
+
+#[test]
+fn test_rename_arrays_and_attributes() -> Result<(), ConversionError> {
+    let options = with_rename(&[("lines", "line"), ("tags", "labels"), ("@id", "@orderId"), ("/order/@ref", "reference")]);
+
+    let xml = json_to_xml_with_options(r#"{"order": {"@id": 7, "@ref": "x", "lines": [{"sku": "A1"}], "tags": ["a"]}}"#, "Root", &options)?;
+
+    // Item names follow the renamed element.
+    assert!(xml.ends_with("<Order orderId=\"7\" reference=\"x\">\n  <Line>\n    <Sku>A1</Sku>\n  </Line>\n  <Labels>\n    <LabelsItem>a</LabelsItem>\n  </Labels>\n</Order>"));
+    Ok(())
+}
+
+#[test]
+fn test_rule_rename_takes_precedence() -> Result<(), ConversionError> {
+    let options = ConversionOptions {
+        rules: vec![Rule::new("$.customer.name", RuleAction::Rename("customerName".to_string()))?],
+        ..with_rename(&[("name", "FullName")])
+    };
+
+    let xml = json_to_xml_with_options(r#"{"customer": {"name": "Ann"}, "shop": {"name": "Corner"}}"#, "Root", &options)?;
+
+    assert!(xml.contains("<customerName>Ann</customerName>"));
+    assert!(xml.contains("<FullName>Corner</FullName>"));
+    Ok(())
+}
+
+#[test]
+fn test_rename_with_json5_comments() -> Result<(), ConversionError> {
+    let options = Json5Options { keep_comments: true, conversion: with_rename(&[("/service/port_no", "Port"), ("service", "Server")]) };
+
+    let xml = json5_to_xml("{ service: { // The port\n port_no: 8080 } }", "Root", &options)?;
+
+    assert!(xml.contains("<Server>\n  <!--The port-->\n  <Port>8080</Port>\n</Server>"), "{}", xml);
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    let options = with_rename(&[("addr_line_1", "AddressLine1"), ("/customer/zip", "PostalCode"), ("/customer/@id", "key"), ("items", "entries")]);
+
+    for json in [r#"{"customer": {"@id": 1, "addr_line_1": "1 Main St", "zip": "12345"}, "items": [{"zip": 1}, {"zip": 2}]}"#, r#"[{"addr_line_1": "x"}]"#] {
+        let value: Value = serde_json::from_str(json)?;
+        assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_invalid_rename_targets() {
+    for name in ["", "first name", "1st", "@"] {
+        let result = json_to_xml_with_options(r#"{"a": 1}"#, "Root", &with_rename(&[("a", name)]));
+        assert!(matches!(result, Err(ConversionError::InvalidRename { ref key, name: ref invalid }) if key == "a" && invalid == name), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_rename_collisions() -> Result<(), ConversionError> {
+    let result = json_to_xml_with_options(r#"{"o": {"@a": 1, "@b": 2}}"#, "Root", &with_rename(&[("@a", "@b")]));
+    assert!(matches!(result, Err(ConversionError::DuplicateAttribute { ref path, ref name }) if path == "/o" && name == "b"), "{:?}", result);
+
+    // Sibling elements are not checked: both are written.
+    let xml = json_to_xml_with_options(r#"{"o": {"a": 1, "b": 2}}"#, "Root", &with_rename(&[("a", "b")]))?;
+    assert!(xml.ends_with("<O>\n  <B>1</B>\n  <B>2</B>\n</O>"));
+    Ok(())
+}
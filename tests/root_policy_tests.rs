@@ -0,0 +1,88 @@
+use json_to_xml::document::Document;
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options};
+use json_to_xml::options::{ConversionOptions, RootPolicy};
+use serde_json::Value;
+
+fn with_policy(root_policy: RootPolicy) -> ConversionOptions {
+    ConversionOptions { root_policy, ..Default::default() }
+}
+
+#[test]
+fn test_single_key_is_root_element() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"book": {"title": "Rust"}}"#)?;
+
+    assert!(xml.ends_with("-->\n<Book>\n  <Title>Rust</Title>\n</Book>"));
+    Ok(())
+}
+
+#[test]
+fn test_multiple_keys_are_wrapped() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"a": 1, "b": {"c": 2}}"#)?;
+    assert!(xml.ends_with("<Root>\n  <A>1</A>\n  <B>\n    <C>2</C>\n  </B>\n</Root>"));
+
+    let xml = json_to_xml(r#"{"item": [{"id": 1}, {"id": 2}]}"#)?;
+    assert!(xml.ends_with("<Root>\n  <Item>\n    <Id>1</Id>\n  </Item>\n  <Item>\n    <Id>2</Id>\n  </Item>\n</Root>"));
+
+    let xml = json_to_xml("{}")?;
+    assert!(xml.ends_with("-->\n<Root/>"));
+    Ok(())
+}
+
+#[test]
+fn test_always_wraps() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_options(r#"{"book": {"title": "Rust"}}"#, "Library", &with_policy(RootPolicy::Always))?;
+
+    assert!(xml.ends_with("<Library>\n  <Book>\n    <Title>Rust</Title>\n  </Book>\n</Library>"));
+    Ok(())
+}
+
+#[test]
+fn test_error_policy() -> Result<(), ConversionError> {
+    let options = with_policy(RootPolicy::Error);
+
+    let xml = json_to_xml_with_options(r#"{"book": {"title": "Rust"}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Book>\n  <Title>Rust</Title>\n</Book>"));
+
+    let xml = json_to_xml_with_options(r#"{"@id": 7, "a": 1, "b": 2}"#, "Root", &options)?;
+    assert!(xml.contains(r#"<Root id="7">"#));
+
+    assert!(matches!(json_to_xml_with_options(r#"{"a": 1, "b": 2}"#, "Root", &options), Err(ConversionError::RootCount(2))));
+    assert!(matches!(json_to_xml_with_options(r#"{"item": [{"id": 1}, {"id": 2}]}"#, "Root", &options), Err(ConversionError::RootCount(2))));
+    assert!(matches!(json_to_xml_with_options("{}", "Root", &options), Err(ConversionError::RootCount(0))));
+    Ok(())
+}
+
+#[test]
+fn test_fragment() -> Result<(), ConversionError> {
+    let options = with_policy(RootPolicy::Fragment);
+
+    let xml = json_to_xml_with_options(r#"{"a": 1, "b": {"c": 2}}"#, "Root", &options)?;
+    assert_eq!(xml, "<A>1</A>\n<B>\n  <C>2</C>\n</B>");
+
+    let xml = json_to_xml_with_options(r#"{"@id": 7, "a": 1}"#, "Root", &options)?;
+    assert_eq!(xml, "<Root id=\"7\">\n  <A>1</A>\n</Root>");
+
+    let xml = json_to_xml_with_options("[1]", "Root", &options)?;
+    assert_eq!(xml, "<Root>\n  <RootItem>1</RootItem>\n</Root>");
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    for policy in [RootPolicy::WhenNeeded, RootPolicy::Always, RootPolicy::Error] {
+        let options = with_policy(policy);
+
+        for json in [r#"{"book": {"title": "Rust"}}"#, r#"{"@id": 7, "a": 1}"#, "{}"] {
+            let value: Value = serde_json::from_str(json)?;
+            let document = Document::from_value(&value, "Root", &options).and_then(|document| document.to_xml());
+
+            assert_eq!(format!("{:?}", document), format!("{:?}", json_to_xml_with_options(json, "Root", &options)));
+        }
+    }
+
+    let value: Value = serde_json::from_str(r#"{"a": 1, "b": 2}"#)?;
+    let document = Document::from_value(&value, "Root", &with_policy(RootPolicy::Fragment))?;
+    assert_eq!(document.nodes.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,169 @@
+use json_to_xml::document::Document;
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_options;
+use json_to_xml::options::ConversionOptions;
+use json_to_xml::rules::{PathPattern, Rule, RuleAction};
+use serde_json::Value;
+
+fn with_rules(rules: &[(&str, RuleAction)]) -> Result<ConversionOptions, ConversionError> {
+    let rules = rules.iter().map(|(pattern, action)| Rule::new(pattern, action.clone())).collect::<Result<_, _>>()?;
+    Ok(ConversionOptions { rules, ..Default::default() })
+}
+
+#[test]
+fn test_path_patterns() -> Result<(), ConversionError> {
+    let cases = [
+        ("/order/id", "/order/id", true),
+        ("/order/id", "/order/items/id", false),
+        ("/items/*", "/items/3", true),
+        ("/a~1b/c~0d", "/a~1b/c~0d", true),
+        ("", "", true),
+        ("$", "", true),
+        ("$.order.id", "/order/id", true),
+        ("$['order'][\"id\"]", "/order/id", true),
+        ("$.items[*]", "/items/0", true),
+        ("$.items[*]", "/items", false),
+        ("$.items[1]", "/items/1", true),
+        ("$.items.1", "/items/1", true),
+        ("$..id", "/id", true),
+        ("$..id", "/order/items/2/id", true),
+        ("$..id", "/order/identifier", false),
+        ("$.order..sku", "/order/items/0/sku", true),
+        ("$..[*]", "/a/b", true),
+        ("$.*.id", "/order/id", true),
+        ("$.*.id", "/id", false),
+    ];
+
+    for (pattern, pointer, matches) in cases {
+        assert_eq!(pattern.parse::<PathPattern>()?.matches(pointer), matches, "{} on {}", pattern, pointer);
+    }
+
+    for pattern in ["order.id", "$.", "$.items[", "$.items[x]", "$order"] {
+        assert!(matches!(pattern.parse::<PathPattern>(), Err(ConversionError::InvalidPath(_))), "{}", pattern);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_rename() -> Result<(), ConversionError> {
+    let options = with_rules(&[
+        ("$.order.items[*]", RuleAction::Rename("Line".to_string())),
+        ("$.order.tags", RuleAction::Rename("labels".to_string())),
+        ("$.order.tags[*]", RuleAction::Rename("label".to_string())),
+        ("/order/@id", RuleAction::Rename("orderId".to_string())),
+    ])?;
+
+    let xml = json_to_xml_with_options(r#"{"order": {"@id": 7, "items": [{"sku": "A1"}], "tags": ["a"]}}"#, "Root", &options)?;
+
+    assert!(xml.ends_with("<Order orderId=\"7\">\n  <Line>\n    <Sku>A1</Sku>\n  </Line>\n  <labels>\n    <label>a</label>\n  </labels>\n</Order>"));
+    Ok(())
+}
+
+#[test]
+fn test_attribute() -> Result<(), ConversionError> {
+    let options = with_rules(&[
+        ("$.order.id", RuleAction::Rename("number".to_string())),
+        ("$.order.id", RuleAction::Attribute),
+        ("$.order.customer", RuleAction::Attribute),
+        ("$.version", RuleAction::Attribute),
+    ])?;
+
+    let xml = json_to_xml_with_options(r#"{"order": {"id": 7, "customer": {"name": "Ann"}, "total": 9.5}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Order number=\"7\">\n  <Customer>\n    <Name>Ann</Name>\n  </Customer>\n  <Total>9.5</Total>\n</Order>"));
+
+    // A top-level attribute needs the root element.
+    let xml = json_to_xml_with_options(r#"{"version": 2, "order": {"id": 7}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Root version=\"2\">\n  <Order number=\"7\"/>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_attribute_collision() -> Result<(), ConversionError> {
+    let options = with_rules(&[("$.o.id", RuleAction::Attribute)])?;
+
+    let result = json_to_xml_with_options(r#"{"o": {"@id": 1, "id": 2}}"#, "Root", &options);
+    assert!(matches!(result, Err(ConversionError::DuplicateAttribute { ref path, ref name }) if path == "/o" && name == "id"), "{:?}", result);
+    Ok(())
+}
+
+#[test]
+fn test_invalid_rename() {
+    for name in ["x y", "", "1st", "a:b:c"] {
+        let result = Rule::new("$.a", RuleAction::Rename(name.to_string()));
+        assert!(matches!(result, Err(ConversionError::InvalidName { ref path, name: ref invalid }) if path == "$.a" && invalid == name), "{:?}", result);
+    }
+}
+
+#[test]
+fn test_text() -> Result<(), ConversionError> {
+    let options = with_rules(&[("$.weight.unit", RuleAction::Attribute), ("$..value", RuleAction::Text)])?;
+
+    let xml = json_to_xml_with_options(r#"{"weight": {"unit": "kg", "value": 42}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Weight unit=\"kg\">42</Weight>"));
+
+    let xml = json_to_xml_with_options(r#"{"weight": {"value": null}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Weight>\n  <None/>\n</Weight>"));
+    Ok(())
+}
+
+#[test]
+fn test_skip() -> Result<(), ConversionError> {
+    let options = with_rules(&[("$.meta", RuleAction::Skip), ("$.tags[1]", RuleAction::Skip), ("$.post['@draft']", RuleAction::Skip), ("$.empty.*", RuleAction::Skip)])?;
+
+    let xml = json_to_xml_with_options(r#"{"meta": {"source": "web"}, "post": {"@draft": true, "tags": ["x"]}}"#, "Root", &options)?;
+    assert!(xml.ends_with("-->\n<Post>\n  <Tags>\n    <TagsItem>x</TagsItem>\n  </Tags>\n</Post>"));
+
+    let xml = json_to_xml_with_options(r#"{"tags": ["a", "b", "c"], "empty": {"a": 1}}"#, "Root", &options)?;
+    assert!(xml.ends_with("<Root>\n  <Empty/>\n  <Tags>\n    <TagsItem>a</TagsItem>\n    <TagsItem>c</TagsItem>\n  </Tags>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_cdata() -> Result<(), ConversionError> {
+    let options = with_rules(&[("$..note", RuleAction::CData), ("$", RuleAction::CData)])?;
+
+    let xml = json_to_xml_with_options(r#"{"order": {"note": "a <b> & ]]> c", "count": 1}}"#, "Root", &options)?;
+    assert!(xml.contains("<Note><![CDATA[a <b> & ]]]]><![CDATA[> c]]></Note>"));
+
+    let xml = json_to_xml_with_options(r#""<top>""#, "Root", &options)?;
+    assert!(xml.ends_with("<Root><![CDATA[<top>]]></Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_format() -> Result<(), ConversionError> {
+    let options = with_rules(&[
+        ("$..price", RuleAction::Format(|value| format!("{:.2}", value.as_f64().unwrap_or_default()))),
+        ("$.item.dimensions", RuleAction::Format(|value| value.as_array().map(|items| items.iter().map(Value::to_string).collect::<Vec<_>>().join("x")).unwrap_or_default())),
+        ("$.item['@sku']", RuleAction::Format(|value| value.as_str().unwrap_or_default().to_uppercase())),
+    ])?;
+
+    let xml = json_to_xml_with_options(r#"{"item": {"@sku": "a1", "price": 3, "dimensions": [2, 3]}}"#, "Root", &options)?;
+
+    assert!(xml.ends_with("<Item sku=\"A1\">\n  <Dimensions>2x3</Dimensions>\n  <Price>3.00</Price>\n</Item>"));
+    Ok(())
+}
+
+#[test]
+fn test_document_matches_generator() -> Result<(), ConversionError> {
+    let options = with_rules(&[
+        ("$.order.items[*]", RuleAction::Rename("Line".to_string())),
+        ("$.order.id", RuleAction::Attribute),
+        ("$..note", RuleAction::CData),
+        ("$..value", RuleAction::Text),
+        ("$..meta", RuleAction::Skip),
+        ("$.order.tags[0]", RuleAction::Skip),
+        ("$.order.sizes", RuleAction::Format(|value| value.to_string())),
+        ("$.order.rows[*][*]", RuleAction::Rename("cell".to_string())),
+    ])?;
+
+    for json in [
+        r#"{"order": {"id": 7, "items": [{"sku": "A1", "meta": 1}, {"meta": 2}], "note": "<x> ]]>", "tags": ["a", "b"], "sizes": [], "rows": [[1], []]}}"#,
+        r#"{"weight": {"value": 42, "meta": {}}, "meta": "x"}"#,
+        r#"[{"value": 1}, {"meta": 2}]"#,
+    ] {
+        let value: Value = serde_json::from_str(json)?;
+        assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+    }
+    Ok(())
+}
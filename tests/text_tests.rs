@@ -0,0 +1,96 @@
+use json_to_xml::document::{xml_to_json, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options, json_to_xml_with_root};
+use json_to_xml::options::{BooleanFormat, ConversionOptions, NumberFormat, TypeAnnotation};
+use serde_json::{json, Value};
+
+#[test]
+fn test_text_with_attributes() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"weight": {"@unit": "kg", "$text": 42}}"#)?;
+    assert!(xml.contains(r#"<Weight unit="kg">42</Weight>"#));
+
+    let xml = json_to_xml(r#"{"weight": {"@unit": "kg", "$text": "42"}}"#)?;
+    assert!(xml.contains(r#"<Weight unit="kg">42</Weight>"#));
+    Ok(())
+}
+
+#[test]
+fn test_text_without_attributes() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"weight": {"$text": 42}, "label": {"$text": "heavy"}}"#)?;
+
+    assert!(xml.contains("<Root>\n  <Label>heavy</Label>\n  <Weight>42</Weight>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_text_formatting_options() -> Result<(), ConversionError> {
+    let json = r#"{"price": {"@currency": "EUR", "$text": 9.5}, "active": {"$text": true}, "discount": {"@type": "none", "$text": null}}"#;
+    let options = ConversionOptions {
+        number_format: NumberFormat { decimal_places: Some(2), ..Default::default() },
+        boolean_format: BooleanFormat::YesNo,
+        ..Default::default()
+    };
+
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+
+    assert!(xml.contains(r#"<Price currency="EUR">9.50</Price>"#));
+    assert!(xml.contains("<Active>yes</Active>"));
+    assert!(xml.contains("<Discount type=\"none\">\n    <None/>\n  </Discount>"));
+    Ok(())
+}
+
+#[test]
+fn test_text_type_annotation() -> Result<(), ConversionError> {
+    let json = r#"{"weight": {"@unit": "kg", "$text": 42}, "name": {"$text": "Box"}}"#;
+    let options = ConversionOptions { type_annotation: TypeAnnotation::Attribute("type".to_string()), ..Default::default() };
+
+    let xml = json_to_xml_with_options(json, "Root", &options)?;
+
+    assert!(xml.contains(r#"<Name type="string">Box</Name>"#));
+    assert!(xml.contains(r#"<Weight unit="kg" type="number">42</Weight>"#));
+    Ok(())
+}
+
+#[test]
+fn test_top_level_text_gets_root_element() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_root(r#"{"$text": 5}"#, "count")?;
+    assert!(xml.ends_with("<Count>5</Count>"));
+
+    let xml = json_to_xml_with_root(r#"{"@unit": "m", "$text": 5}"#, "length")?;
+    assert!(xml.ends_with(r#"<Length unit="m">5</Length>"#));
+    Ok(())
+}
+
+#[test]
+fn test_text_in_object_arrays() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"size": [{"@unit": "cm", "$text": 10}, {"@unit": "in", "$text": 4}]}"#)?;
+    assert!(xml.contains("<Size unit=\"cm\">10</Size>\n  <Size unit=\"in\">4</Size>"));
+
+    let xml = json_to_xml(r#"{"size": [{"$text": 10}, {"$text": 4}]}"#)?;
+    assert!(xml.contains("<Size>10</Size>\n  <Size>4</Size>"));
+    Ok(())
+}
+
+#[test]
+fn test_document_text_matches_generator() -> Result<(), ConversionError> {
+    let options = ConversionOptions { type_annotation: TypeAnnotation::Attribute("type".to_string()), ..Default::default() };
+
+    for json in [
+        r#"{"weight": {"@unit": "kg", "$text": 42}, "flag": {"$text": false}}"#,
+        r#"{"discount": {"@kind": "none", "$text": null}}"#,
+        r#"{"$text": 5}"#,
+    ] {
+        let value: Value = serde_json::from_str(json)?;
+        assert_eq!(Document::from_value(&value, "Root", &options)?.to_xml()?, json_to_xml_with_options(json, "Root", &options)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_null_text_round_trip() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"discount": {"@type": "none", "$text": null}}"#)?;
+
+    let value: Value = serde_json::from_str(&xml_to_json(&xml)?)?;
+    assert_eq!(value, json!({ "Discount": { "@type": "none", "$text": null } }));
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+use json_to_xml::toml::toml_to_xml;
+
+#[test]
+fn test_toml_matches_json_conversion() -> Result<(), ConversionError> {
+    let toml = r#"
+"@xmlns:pr" = "http://test/person"
+
+[person]
+"@id" = "42"
+name = "Alice"
+age = 30
+height = 1.68
+tags = ["a", "b"]
+
+[[person.pets]]
+name = "Rex"
+
+[[person.pets]]
+name = "Tom"
+"#;
+    let json = r#"{
+        "@xmlns:pr": "http://test/person",
+        "person": {"@id": "42", "name": "Alice", "age": 30, "height": 1.68, "tags": ["a", "b"], "pets": [{"name": "Rex"}, {"name": "Tom"}]}
+    }"#;
+
+    assert_eq!(toml_to_xml(toml, "People")?, json_to_xml_with_root(json, "People")?);
+    Ok(())
+}
+
+#[test]
+fn test_toml_datetimes_are_iso_8601() -> Result<(), ConversionError> {
+    let toml = r#"
+offset = 1979-05-27T00:32:00.999999-07:00
+local = 1979-05-27T07:32:00
+date = 1979-05-27
+time = 07:32:00
+ratio = inf
+"#;
+
+    let xml = toml_to_xml(toml, "Root")?;
+
+    assert!(xml.contains("<Offset>1979-05-27T00:32:00.999999-07:00</Offset>"));
+    assert!(xml.contains("<Local>1979-05-27T07:32:00</Local>"));
+    assert!(xml.contains("<Date>1979-05-27</Date>"));
+    assert!(xml.contains("<Time>07:32:00</Time>"));
+    assert!(xml.contains("<Ratio>inf</Ratio>"));
+    Ok(())
+}
+
+#[test]
+fn test_toml_parse_error() {
+    let result = toml_to_xml("name = ", "Root");
+
+    assert!(matches!(result, Err(ConversionError::Toml(_))));
+}
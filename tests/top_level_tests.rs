@@ -0,0 +1,67 @@
+use json_to_xml::document::{xml_to_json, Document};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options, json_to_xml_with_root};
+use json_to_xml::options::{ConversionOptions, TopLevelArray, TypeAnnotation};
+use serde_json::{json, Value};
+
+const HEADER: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!--Generated with json_to_xml 0.1.8-->\n";
+
+#[test]
+fn test_top_level_scalars() -> Result<(), ConversionError> {
+    assert_eq!(json_to_xml(r#""hello""#)?, format!("{HEADER}<Root>hello</Root>"));
+    assert_eq!(json_to_xml("42")?, format!("{HEADER}<Root>42</Root>"));
+    assert_eq!(json_to_xml("true")?, format!("{HEADER}<Root>true</Root>"));
+    assert_eq!(json_to_xml("null")?, format!("{HEADER}<Root>\n  <None/>\n</Root>"));
+    assert_eq!(json_to_xml(r#""""#)?, format!("{HEADER}<Root></Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_top_level_arrays() -> Result<(), ConversionError> {
+    let xml = json_to_xml_with_root(r#"[1, "two", null]"#, "list")?;
+    assert_eq!(xml, format!("{HEADER}<List>\n  <ListItem>1</ListItem>\n  <ListItem>two</ListItem>\n  <ListItem>\n    <None/>\n  </ListItem>\n</List>"));
+
+    let xml = json_to_xml_with_root(r#"[{"@id": 1, "name": "a"}, {"name": "b"}, {}]"#, "Users")?;
+    assert_eq!(xml, format!("{HEADER}<Users>\n  <UsersItem id=\"1\">\n    <Name>a</Name>\n  </UsersItem>\n  <UsersItem>\n    <Name>b</Name>\n  </UsersItem>\n  <UsersItem/>\n</Users>"));
+
+    assert_eq!(json_to_xml("[]")?, format!("{HEADER}<Root/>"));
+    Ok(())
+}
+
+#[test]
+fn test_named_top_level_items() -> Result<(), ConversionError> {
+    let options = ConversionOptions { top_level_array: TopLevelArray::Named("item".to_string()), ..Default::default() };
+
+    let xml = json_to_xml_with_options(r#"[{"sku": "A"}, "loose"]"#, "Root", &options)?;
+
+    assert_eq!(xml, format!("{HEADER}<Root>\n  <Item>\n    <Sku>A</Sku>\n  </Item>\n  <Item>loose</Item>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_top_level_type_annotation() -> Result<(), ConversionError> {
+    let options = ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..Default::default() };
+
+    let xml = json_to_xml_with_options("7", "Count", &options)?;
+    assert!(xml.ends_with(r#"<Count xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xs="http://www.w3.org/2001/XMLSchema" xsi:type="xs:integer">7</Count>"#));
+
+    let xml = json_to_xml_with_options("[true]", "Flags", &options)?;
+    assert!(xml.contains(r#"<Flags xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xs="http://www.w3.org/2001/XMLSchema">"#));
+    assert!(xml.contains(r#"<FlagsItem xsi:type="xs:boolean">true</FlagsItem>"#));
+    Ok(())
+}
+
+#[test]
+fn test_top_level_values_parse_back() -> Result<(), ConversionError> {
+    for json in [r#""hello""#, "42", "null", r#"[1, "two"]"#, r#"[{"name": "a"}, {"name": "b"}]"#, "[]"] {
+        let xml = json_to_xml_with_root(json, "Root")?;
+        assert!(Document::from_xml(&xml).is_ok(), "not well-formed: {xml}");
+
+        let value: Value = serde_json::from_str(json)?;
+        assert_eq!(Document::from_value(&value, "Root", &ConversionOptions::default())?.to_xml()?, xml);
+    }
+
+    let value: Value = serde_json::from_str(&xml_to_json(&json_to_xml_with_root(r#"["a", "b"]"#, "Tags")?)?)?;
+    assert_eq!(value, json!({ "Tags": ["a", "b"] }));
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use json_to_xml::document::{Document, Node};
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::{json_to_xml, json_to_xml_with_options};
+use json_to_xml::options::{ArrayMarker, ConversionOptions, EmptyArray, RootPolicy, TypeAnnotation};
+use proptest::prelude::*;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde_json::{Map, Value};
+
+/// Rejects the names the reader accepts although XML does not: empty ones, ones starting with a digit, `-` or `.`,
+/// and ones with spaces or markup characters.
+fn check_name(name: &[u8]) -> Result<(), String> {
+    let name = String::from_utf8_lossy(name);
+    let valid = name.chars().next().is_some_and(|c| !c.is_ascii_digit() && c != '-' && c != '.')
+        && !name.chars().any(|c| c.is_whitespace() || c.is_control() || "<>&'\"=/!?;,()[]{}$@#%*+`^|~\\".contains(c))
+        && name.split(':').count() <= 2
+        && name.split(':').all(|part| !part.is_empty());
+    if valid { Ok(()) } else { Err(format!("invalid name {:?}", name)) }
+}
+
+/// Rejects the characters XML does not allow, which the reader passes through.
+fn check_chars(text: &str) -> Result<(), String> {
+    match text.chars().find(|c| !matches!(c, '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..)) {
+        Some(c) => Err(format!("invalid character {:?}", c)),
+        None => Ok(()),
+    }
+}
+
+fn check_element(element: &BytesStart) -> Result<(), String> {
+    check_name(element.name().as_ref())?;
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        check_name(attribute.key.as_ref())?;
+        check_chars(&attribute.unescape_value().map_err(|e| e.to_string())?)?;
+    }
+    Ok(())
+}
+
+/// Read the XML and return the number of root elements, or the first well-formedness error.
+fn root_count(xml: &str) -> Result<usize, String> {
+    let mut reader = Reader::from_str(xml);
+    let mut depth = 0usize;
+    let mut roots = 0;
+
+    loop {
+        match reader.read_event().map_err(|e| e.to_string())? {
+            Event::Start(element) => {
+                check_element(&element)?;
+                roots += usize::from(depth == 0);
+                depth += 1;
+            },
+            Event::Empty(element) => {
+                check_element(&element)?;
+                roots += usize::from(depth == 0);
+            },
+            Event::End(_) => depth -= 1,
+            Event::Text(text) => check_chars(&text.decode().map_err(|e| e.to_string())?)?,
+            Event::CData(text) => check_chars(&text.decode().map_err(|e| e.to_string())?)?,
+            Event::Comment(text) => check_chars(&text.decode().map_err(|e| e.to_string())?)?,
+            Event::GeneralRef(reference) => {
+                if let Some(c) = reference.resolve_char_ref().map_err(|e| e.to_string())? {
+                    check_chars(c.encode_utf8(&mut [0; 4]))?;
+                }
+            },
+            Event::Eof if depth == 0 => return Ok(roots),
+            Event::Eof => return Err(format!("{} unclosed elements", depth)),
+            _ => {},
+        }
+    }
+}
+
+fn arb_key() -> impl Strategy<Value = String> {
+    prop_oneof![
+        6 => "[a-z][a-zA-Z0-9_]{0,6}",
+        2 => "@[a-z][a-z0-9]{0,4}",
+        1 => any::<String>(),
+        1 => "@.{0,4}",
+        1 => Just("$text".to_string()),
+        1 => Just("$content".to_string()),
+    ]
+}
+
+fn arb_json() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Null),
+        any::<bool>().prop_map(Value::from),
+        any::<i64>().prop_map(Value::from),
+        (-1e9f64..1e9).prop_map(Value::from),
+        any::<String>().prop_map(Value::from),
+    ];
+
+    leaf.prop_recursive(4, 48, 5, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..5).prop_map(Value::Array),
+            prop::collection::vec((arb_key(), inner), 0..5).prop_map(|entries| Value::Object(entries.into_iter().collect::<Map<_, _>>())),
+        ]
+    })
+}
+
+fn arb_options() -> impl Strategy<Value = ConversionOptions> {
+    (any::<bool>(), any::<bool>(), prop_oneof![Just(EmptyArray::Element), Just(EmptyArray::Omit), Just(EmptyArray::Marked("empty".to_string()))])
+        .prop_map(|(marked, annotated, empty_array)| ConversionOptions {
+            array_marker: marked.then(ArrayMarker::default),
+            type_annotation: if annotated { TypeAnnotation::XsiType } else { TypeAnnotation::None },
+            empty_array,
+            ..Default::default()
+        })
+}
+
+/// The XML, or `None` if a key is not an XML name or a string holds a character XML does not allow. Other errors fail the test.
+fn converted(result: Result<String, ConversionError>) -> Result<Option<String>, TestCaseError> {
+    match result {
+        Ok(xml) => Ok(Some(xml)),
+        Err(ConversionError::InvalidName { .. } | ConversionError::InvalidCharacter { .. }) => Ok(None),
+        Err(e) => Err(TestCaseError::fail(e.to_string())),
+    }
+}
+
+fn with_policy(root_policy: RootPolicy) -> ConversionOptions {
+    ConversionOptions { root_policy, ..Default::default() }
+}
+
+proptest! {
+    #[test]
+    fn prop_output_has_one_root(value in arb_json()) {
+        if let Some(xml) = converted(json_to_xml(&value.to_string()))? {
+            prop_assert_eq!(root_count(&xml), Ok(1), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn prop_always_wrapped_output_has_one_root(value in arb_json()) {
+        if let Some(xml) = converted(json_to_xml_with_options(&value.to_string(), "Root", &with_policy(RootPolicy::Always)))? {
+            prop_assert_eq!(root_count(&xml), Ok(1), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn prop_error_policy_has_one_root_or_fails(value in arb_json()) {
+        match json_to_xml_with_options(&value.to_string(), "Root", &with_policy(RootPolicy::Error)) {
+            Ok(xml) => prop_assert_eq!(root_count(&xml), Ok(1), "{}", xml),
+            Err(ConversionError::RootCount(count)) => prop_assert_ne!(count, 1),
+            Err(ConversionError::InvalidName { .. } | ConversionError::InvalidCharacter { .. }) => {},
+            Err(e) => return Err(TestCaseError::fail(e.to_string())),
+        }
+    }
+
+    #[test]
+    fn prop_fragment_is_balanced(value in arb_json()) {
+        if let Some(xml) = converted(json_to_xml_with_options(&value.to_string(), "Root", &with_policy(RootPolicy::Fragment)))? {
+            prop_assert!(root_count(&xml).is_ok(), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn prop_annotated_output_has_one_root(value in arb_json()) {
+        let options = ConversionOptions { type_annotation: TypeAnnotation::XsiType, ..Default::default() };
+        if let Some(xml) = converted(json_to_xml_with_options(&value.to_string(), "Root", &options))? {
+            prop_assert_eq!(root_count(&xml), Ok(1), "{}", xml);
+        }
+    }
+
+    #[test]
+    fn prop_document_matches_generator(value in arb_json(), options in arb_options()) {
+        // Parse the value back, so both sides see the same floats.
+        let value: Value = serde_json::from_str(&value.to_string()).map_err(|e| TestCaseError::fail(e.to_string()))?;
+        let xml = converted(json_to_xml_with_options(&value.to_string(), "Root", &options))?;
+        let document = converted(Document::from_value(&value, "Root", &options).and_then(|document| document.to_xml()))?;
+
+        prop_assert_eq!(document, xml);
+    }
+}
+
+#[test]
+fn test_invalid_names_are_rejected() {
+    for json in [r#"{"": 1}"#, r#"{"a b": 1}"#, r#"{"1st": 1}"#, r#"{"x": {"@a b": 1}}"#, r#"{"x": {"@": 1}}"#, r#"{"a:": 1}"#] {
+        let result = json_to_xml(json);
+        assert!(matches!(result, Err(ConversionError::InvalidName { .. })), "{}: {:?}", json, result);
+    }
+
+    let error = json_to_xml(r#"{"people": [{"first name": "Ann"}]}"#).unwrap_err();
+    assert_eq!(error.to_string(), r#"Invalid XML name "First name" at JSON path "/people/0/first name""#);
+}
+
+#[test]
+fn test_invalid_characters_are_rejected() {
+    for json in [r#"{"a": "\u0001"}"#, r#"{"a": {"@id": "x\u001f"}}"#, r#"{"a": ["\uffff"]}"#, r#"{"a": {"$text": "\ufffe"}}"#] {
+        let result = json_to_xml(json);
+        assert!(matches!(result, Err(ConversionError::InvalidCharacter { .. })), "{}: {:?}", json, result);
+    }
+
+    let error = json_to_xml(r#"{"people": [{"name": "A\u0001"}]}"#).unwrap_err();
+    assert_eq!(error.to_string(), r#"Invalid XML character U+0001 at JSON path "/people/0/name""#);
+
+    let xml = json_to_xml(r#"{"a": "tab\tline\nfeed\r"}"#).unwrap();
+    assert_eq!(root_count(&xml), Ok(1));
+}
+
+#[test]
+fn test_document_names_are_checked() {
+    let mut document = Document::from_xml("<a/>").unwrap();
+    if let Some(Node::Element(element)) = document.nodes.first_mut() {
+        element.name = "a b".to_string();
+    }
+
+    assert!(matches!(document.to_xml(), Err(ConversionError::InvalidName { .. })));
+}
+
+#[test]
+fn test_empty_array_is_balanced() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"reviews": []}"#)?;
+
+    assert_eq!(root_count(&xml), Ok(1));
+    assert!(xml.contains("<Reviews>"));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_array_with_attributes_first() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"a": [{"@id": 1, "x": 1}, 2]}"#)?;
+
+    assert!(xml.ends_with("<A>\n  <AItem id=\"1\">\n    <X>1</X>\n  </AItem>\n  <AItem>2</AItem>\n</A>"));
+    Ok(())
+}
+
+#[test]
+fn test_object_array_with_attributes_and_empty_objects() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"a": [{}, {"@id": 1}, {"x": 1}]}"#)?;
+    assert!(xml.ends_with("<Root>\n  <A id=\"1\"/>\n  <A>\n    <X>1</X>\n  </A>\n</Root>"));
+
+    let xml = json_to_xml(r#"{"a": [{"@id": 1, "x": 1}, {"x": 2}]}"#)?;
+    assert!(xml.ends_with("<Root>\n  <A id=\"1\">\n    <X>1</X>\n  </A>\n  <A>\n    <X>2</X>\n  </A>\n</Root>"));
+    Ok(())
+}
+
+#[test]
+fn test_nested_arrays_are_balanced() -> Result<(), ConversionError> {
+    let xml = json_to_xml(r#"{"matrix": [[1, 2], [3]]}"#)?;
+
+    assert_eq!(root_count(&xml), Ok(1));
+    Ok(())
+}
@@ -0,0 +1,510 @@
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+use json_to_xml::options::ConversionOptions;
+use json_to_xml::rules::{Rule, RuleAction};
+use json_to_xml::xsd::{infer_xsd, infer_schema, json_to_xml_with_schema, parse_xsd, validate_xml, BuiltinType, Content, ElementType, Particle, Violation, ViolationKind};
+
+use serde_json::json;
+
+#[test]
+fn test_infer_elements_and_attributes() -> Result<(), ConversionError> {
+    let xsd = infer_xsd(&[r#"{
+        "@xmlns:pr": "http://test/person",
+        "@version": "1.0",
+        "person": {
+            "@id": 7,
+            "name": "Alice",
+            "height": 1.68,
+            "active": true
+        }
+    }"#], "People")?;
+
+    assert!(xsd.contains("<xs:element name=\"People\">"));
+    assert!(xsd.contains("<xs:element name=\"Person\">"));
+    assert!(xsd.contains("<xs:element name=\"Name\" type=\"xs:string\"/>"));
+    assert!(xsd.contains("<xs:element name=\"Height\" type=\"xs:decimal\"/>"));
+    assert!(xsd.contains("<xs:element name=\"Active\" type=\"xs:boolean\"/>"));
+    assert!(xsd.contains("<xs:attribute name=\"id\" type=\"xs:integer\" use=\"required\"/>"));
+    assert!(xsd.contains("<xs:attribute name=\"version\" type=\"xs:string\" use=\"required\"/>"));
+    assert!(!xsd.contains("xmlns:pr"));
+    Ok(())
+}
+
+#[test]
+fn test_infer_arrays_are_unbounded() -> Result<(), ConversionError> {
+    let xsd = infer_xsd(&[r#"{
+        "order": {
+            "lines": [{"sku": "A", "qty": 1}, {"sku": "B", "qty": 2}],
+            "tags": ["x", "y"]
+        }
+    }"#], "Root")?;
+
+    assert!(xsd.contains("<xs:element name=\"Lines\" maxOccurs=\"unbounded\">"));
+    assert!(xsd.contains("<xs:element name=\"TagsItem\" maxOccurs=\"unbounded\" type=\"xs:string\"/>"));
+    Ok(())
+}
+
+#[test]
+fn test_infer_merges_samples() {
+    let schema = infer_schema(&[
+        json!({"item": {"@code": "a", "price": 1, "note": "x"}}),
+        json!({"item": {"price": 2.5}}),
+    ], "Root");
+
+    assert_eq!(schema.elements.len(), 1);
+    assert_eq!(schema.elements[0].name, "Item");
+
+    let ElementType::Complex(item) = &schema.elements[0].ty else {
+        panic!("expected a complex type");
+    };
+    assert!(!item.attributes[0].required);
+
+    let Content::Group(group) = &item.content else {
+        panic!("expected child elements");
+    };
+    let elements: Vec<_> = group.particles.iter().map(|p| match p {
+        Particle::Element(e) => e,
+        Particle::Group(_) => panic!("unexpected group"),
+    }).collect();
+
+    assert_eq!(elements[0].name, "Note");
+    assert_eq!(elements[0].min_occurs, 0);
+    assert_eq!(elements[1].name, "Price");
+    assert_eq!(elements[1].min_occurs, 1);
+    assert_eq!(elements[1].ty, ElementType::Simple(json_to_xml::xsd::SimpleType::new(BuiltinType::Decimal)));
+}
+
+#[test]
+fn test_infer_text_with_attributes() -> Result<(), ConversionError> {
+    let xsd = infer_xsd(&[r#"{"weight": {"@unit": "kg", "$text": "5"}}"#], "Root")?;
+
+    assert!(xsd.contains("<xs:simpleContent>"));
+    assert!(xsd.contains("<xs:extension base=\"xs:string\">"));
+    assert!(xsd.contains("<xs:attribute name=\"unit\" type=\"xs:string\" use=\"required\"/>"));
+    Ok(())
+}
+
+const ORDER_XSD: &str = r#"<?xml version="1.0"?>
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:orders" elementFormDefault="qualified">
+  <xs:complexType name="LineType">
+    <xs:sequence>
+      <xs:element name="Sku" type="xs:string"/>
+      <xs:element name="Quantity" type="xs:positiveInteger"/>
+      <xs:element name="Price" type="PriceType"/>
+    </xs:sequence>
+  </xs:complexType>
+  <xs:simpleType name="PriceType">
+    <xs:restriction base="xs:decimal"/>
+  </xs:simpleType>
+  <xs:element name="Order">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="Customer" type="xs:string"/>
+        <xs:element name="Paid" type="xs:boolean"/>
+        <xs:element name="Lines">
+          <xs:complexType>
+            <xs:sequence>
+              <xs:element name="Line" type="LineType" maxOccurs="unbounded"/>
+            </xs:sequence>
+          </xs:complexType>
+        </xs:element>
+        <xs:element name="Note" type="xs:string" minOccurs="0"/>
+      </xs:sequence>
+      <xs:attribute name="id" type="xs:integer" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+#[test]
+fn test_parse_xsd() -> Result<(), ConversionError> {
+    let schema = parse_xsd(ORDER_XSD)?;
+
+    assert_eq!(schema.target_namespace.as_deref(), Some("urn:orders"));
+    assert_eq!(schema.elements.len(), 1);
+    assert!(schema.complex_types.contains_key("LineType"));
+
+    let order = schema.complex_type(&schema.elements[0].ty).unwrap();
+    assert_eq!(order.attributes[0].name, "id");
+    assert!(order.attributes[0].required);
+    Ok(())
+}
+
+#[test]
+fn test_schema_driven_conversion() -> Result<(), ConversionError> {
+    let schema = parse_xsd(ORDER_XSD)?;
+    let json = r#"{
+        "order": {
+            "lines": [
+                {"price": 1e1, "sku": "A-1", "quantity": 2.0},
+                {"quantity": 1, "sku": "B-2", "price": "3.50"}
+            ],
+            "paid": true,
+            "ID": 17,
+            "customer": "ACME"
+        }
+    }"#;
+
+    let xml = json_to_xml_with_schema(json, &schema, &ConversionOptions::default())?;
+
+    assert!(xml.contains("<Order xmlns=\"urn:orders\" id=\"17\">"));
+    assert!(xml.contains("<Paid>true</Paid>"));
+    assert!(xml.contains("<Quantity>2</Quantity>"));
+    // With `arbitrary_precision` the number keeps its input form `1e1`, which has no fraction digits.
+    let price = if cfg!(feature = "arbitrary_precision") { "<Price>10</Price>" } else { "<Price>10.0</Price>" };
+    assert!(xml.contains(price));
+    assert!(xml.contains("<Price>3.50</Price>"));
+    assert!(!xml.contains("<Note>"));
+
+    let customer = xml.find("<Customer>").unwrap();
+    let paid = xml.find("<Paid>").unwrap();
+    let lines = xml.find("<Lines>").unwrap();
+    assert!(customer < paid && paid < lines);
+    assert_eq!(xml.matches("<Line>").count(), 2);
+    Ok(())
+}
+
+#[test]
+fn test_schema_violations_are_reported() -> Result<(), ConversionError> {
+    let schema = parse_xsd(ORDER_XSD)?;
+    let json = r#"{
+        "order": {
+            "paid": "maybe",
+            "lines": [{"sku": "A-1", "quantity": 2, "price": 1, "color": "red"}],
+            "extra": 1
+        }
+    }"#;
+
+    let Err(ConversionError::Schema(violations)) = json_to_xml_with_schema(json, &schema, &ConversionOptions::default()) else {
+        panic!("expected schema violations");
+    };
+
+    assert!(violations.contains(&Violation { path: "/Order".to_string(), kind: ViolationKind::MissingAttribute("id".to_string()) }));
+    assert!(violations.contains(&Violation { path: "/Order".to_string(), kind: ViolationKind::MissingElement("Customer".to_string()) }));
+    assert!(violations.contains(&Violation { path: "/Order".to_string(), kind: ViolationKind::UnexpectedElement("extra".to_string()) }));
+    assert!(violations.contains(&Violation { path: "/Order/Lines/Line".to_string(), kind: ViolationKind::UnexpectedElement("color".to_string()) }));
+    assert!(violations.contains(&Violation {
+        path: "/Order/Paid".to_string(),
+        kind: ViolationKind::InvalidValue { value: "\"maybe\"".to_string(), expected: "xs:boolean".to_string() },
+    }));
+    Ok(())
+}
+
+#[test]
+fn test_generated_xml_validates_against_inferred_schema() -> Result<(), ConversionError> {
+    let json = r#"{"@version": "1.0", "person": [{"@id": 1, "name": "Alice", "tags": ["a", "b"]}, {"@id": 2, "name": "Bob", "tags": []}]}"#;
+    let schema = infer_schema(&[serde_json::from_str(json)?], "People");
+    let xml = json_to_xml_with_root(json, "People")?;
+
+    assert_eq!(validate_xml(&xml, &schema)?, vec![]);
+    Ok(())
+}
+
+#[test]
+fn test_exponent_numbers_validate_against_inferred_schema() -> Result<(), ConversionError> {
+    let json = r#"{"readings": {"reading": [{"value": 1e-7}, {"value": 2.5}, {"value": 3}]}}"#;
+    let schema = infer_schema(&[serde_json::from_str(json)?], "Root");
+    let xml = json_to_xml_with_root(json, "Root")?;
+
+    assert!(schema.to_xsd()?.contains("<xs:element name=\"Value\" type=\"xs:double\"/>"));
+    assert_eq!(validate_xml(&xml, &schema)?, vec![]);
+    Ok(())
+}
+
+#[test]
+fn test_wrapped_samples_validate_against_inferred_schema() -> Result<(), ConversionError> {
+    let samples = [r#"{"reading": [{"value": 1}, {"value": 2}]}"#, r#"{"reading": [{"value": 3}]}"#];
+    let schema = infer_schema(&samples.map(|sample| serde_json::from_str(sample).unwrap()), "Readings");
+
+    assert_eq!(schema.elements.iter().map(|element| element.name.as_str()).collect::<Vec<_>>(), vec!["Readings", "Reading"]);
+    for sample in samples {
+        assert_eq!(validate_xml(&json_to_xml_with_root(sample, "Readings")?, &schema)?, vec![]);
+    }
+    Ok(())
+}
+
+const RESTRICTED_XSD: &str = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="CodeType">
+    <xs:restriction base="xs:string">
+      <xs:pattern value="[A-Z]{3}"/>
+    </xs:restriction>
+  </xs:simpleType>
+  <xs:element name="Item">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:choice>
+          <xs:element name="Code" type="CodeType"/>
+          <xs:element name="Ean" type="xs:long"/>
+        </xs:choice>
+        <xs:element name="Size">
+          <xs:simpleType>
+            <xs:restriction base="xs:string">
+              <xs:enumeration value="S"/>
+              <xs:enumeration value="M"/>
+            </xs:restriction>
+          </xs:simpleType>
+        </xs:element>
+        <xs:element name="Stock">
+          <xs:simpleType>
+            <xs:restriction base="xs:integer">
+              <xs:minInclusive value="0"/>
+              <xs:maxInclusive value="100"/>
+            </xs:restriction>
+          </xs:simpleType>
+        </xs:element>
+        <xs:element name="Note" type="xs:string" maxOccurs="2"/>
+      </xs:sequence>
+      <xs:attribute name="id" type="xs:positiveInteger" use="required"/>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+#[test]
+fn test_validate_facets_and_occurrences() -> Result<(), ConversionError> {
+    let schema = parse_xsd(RESTRICTED_XSD)?;
+
+    let valid = "<Item id=\"3\"><Ean>4006381333931</Ean><Size>M</Size><Stock>100</Stock><Note>a</Note></Item>";
+    assert_eq!(validate_xml(valid, &schema)?, vec![]);
+
+    let invalid = "<Item><Code>abc</Code><Size>XL</Size><Stock>101</Stock><Note>a</Note><Note>b</Note><Note>c</Note></Item>";
+    let violations = validate_xml(invalid, &schema)?;
+
+    assert_eq!(violations, vec![
+        Violation { path: "/Item".to_string(), kind: ViolationKind::MissingAttribute("id".to_string()) },
+        Violation {
+            path: "/Item/Code".to_string(),
+            kind: ViolationKind::InvalidValue { value: "abc".to_string(), expected: "xs:string (pattern: [A-Z]{3})".to_string() },
+        },
+        Violation {
+            path: "/Item/Size".to_string(),
+            kind: ViolationKind::InvalidValue { value: "XL".to_string(), expected: "xs:string (enumeration: S, M)".to_string() },
+        },
+        Violation {
+            path: "/Item/Stock".to_string(),
+            kind: ViolationKind::InvalidValue { value: "101".to_string(), expected: "xs:integer (minInclusive: 0; maxInclusive: 100)".to_string() },
+        },
+        Violation { path: "/Item".to_string(), kind: ViolationKind::TooManyElements { name: "Note".to_string(), max: 2 } },
+    ]);
+    Ok(())
+}
+
+#[test]
+fn test_validate_structure() -> Result<(), ConversionError> {
+    let schema = parse_xsd(RESTRICTED_XSD)?;
+
+    let violations = validate_xml("<Item id=\"1\" color=\"red\"><Size>S</Size><Stock>1</Stock><Note>a</Note><Extra/></Item>", &schema)?;
+    assert_eq!(violations, vec![
+        Violation { path: "/Item".to_string(), kind: ViolationKind::UnexpectedAttribute("color".to_string()) },
+        Violation { path: "/Item".to_string(), kind: ViolationKind::MissingElement("Code | Ean".to_string()) },
+        Violation { path: "/Item".to_string(), kind: ViolationKind::UnexpectedElement("Extra".to_string()) },
+    ]);
+
+    let violations = validate_xml("<Order/>", &schema)?;
+    assert_eq!(violations, vec![Violation { path: "/Order".to_string(), kind: ViolationKind::UnknownRoot("Order".to_string()) }]);
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content_validates_against_inferred_schema() -> Result<(), ConversionError> {
+    let samples = [
+        r#"{"p": {"$content": ["Hello ", {"b": "world"}, ", ", {"i": "again"}, {"b": "!"}]}}"#,
+        r#"{"p": {"$content": {"i": "alone"}}}"#,
+        r#"{"p": {"$content": 7}}"#,
+    ];
+    let schema = infer_schema(&samples.map(|sample| serde_json::from_str(sample).unwrap()), "Root");
+
+    assert!(!schema.to_xsd()?.contains("$content"));
+    for sample in samples {
+        assert_eq!(validate_xml(&json_to_xml_with_root(sample, "Root")?, &schema)?, vec![]);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_types_derived_from_themselves_are_rejected() {
+    let complex = r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:complexType name="T">
+    <xs:complexContent>
+      <xs:extension base="T">
+        <xs:sequence><xs:element name="a" type="xs:string"/></xs:sequence>
+      </xs:extension>
+    </xs:complexContent>
+  </xs:complexType>
+  <xs:element name="Root" type="T"/>
+</xs:schema>"#;
+    assert!(matches!(parse_xsd(complex), Err(ConversionError::InvalidSchema(_))));
+
+    let simple = r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="S"><xs:restriction base="S"/></xs:simpleType>
+  <xs:element name="Root" type="S"/>
+</xs:schema>"#;
+    assert!(matches!(parse_xsd(simple), Err(ConversionError::InvalidSchema(_))));
+}
+
+#[test]
+fn test_recursive_element_references() -> Result<(), ConversionError> {
+    let schema = parse_xsd(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="a">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element ref="a" minOccurs="0"/>
+        <xs:element ref="a" minOccurs="0"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#)?;
+
+    assert_eq!(validate_xml("<a><a><a><a/></a></a></a>", &schema)?, vec![]);
+    assert_eq!(validate_xml("<a><a><b/></a></a>", &schema)?, vec![
+        Violation { path: "/a/a".to_string(), kind: ViolationKind::UnexpectedElement("b".to_string()) },
+    ]);
+    assert!(schema.to_xsd()?.contains("<xs:element ref=\"a\" minOccurs=\"0\"/>"));
+    Ok(())
+}
+
+#[test]
+fn test_validate_rejects_content_after_the_root() -> Result<(), ConversionError> {
+    let schema = parse_xsd(r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"><xs:element name="A" type="xs:integer"/></xs:schema>"#)?;
+
+    assert_eq!(validate_xml("<!-- a -->\n<A>1</A>\n", &schema)?, vec![]);
+    assert!(matches!(validate_xml("<A>1</A><A>x</A><B/>", &schema), Err(ConversionError::RootCount(3))));
+    assert!(matches!(validate_xml("<A>1</A> trailing", &schema), Err(ConversionError::TextOutsideRoot(_))));
+    assert!(matches!(validate_xml("", &schema), Err(ConversionError::RootCount(0))));
+    Ok(())
+}
+
+#[test]
+fn test_patterns_are_compiled_when_parsed() -> Result<(), ConversionError> {
+    let schema = parse_xsd(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="Id">
+    <xs:simpleType>
+      <xs:restriction base="xs:string"><xs:pattern value="\i\c*"/></xs:restriction>
+    </xs:simpleType>
+  </xs:element>
+</xs:schema>"#)?;
+
+    assert_eq!(validate_xml("<Id>item-1</Id>", &schema)?, vec![]);
+    assert_eq!(validate_xml("<Id>1st</Id>", &schema)?.len(), 1);
+
+    let invalid = r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:simpleType name="Code"><xs:restriction base="xs:string"><xs:pattern value="[A-Z"/></xs:restriction></xs:simpleType>
+</xs:schema>"#;
+    assert!(matches!(parse_xsd(invalid), Err(ConversionError::InvalidSchema(_))));
+    Ok(())
+}
+
+#[test]
+fn test_repeated_choice_writes_every_alternative() -> Result<(), ConversionError> {
+    let schema = parse_xsd(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="Contacts">
+    <xs:complexType>
+      <xs:choice maxOccurs="3">
+        <xs:element name="Email" type="xs:string"/>
+        <xs:element name="Phone" type="xs:string"/>
+      </xs:choice>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#)?;
+
+    let xml = json_to_xml_with_schema(r#"{"email": ["a@example.com", "b@example.com"], "phone": "555"}"#, &schema, &ConversionOptions::default())?;
+
+    assert!(xml.contains("<Email>a@example.com</Email>\n  <Email>b@example.com</Email>\n  <Phone>555</Phone>"));
+    assert_eq!(validate_xml(&xml, &schema)?, vec![]);
+
+    let Err(ConversionError::Schema(violations)) = json_to_xml_with_schema(r#"{"email": ["a", "b"], "phone": ["1", "2"]}"#, &schema, &ConversionOptions::default()) else {
+        panic!("expected schema violations");
+    };
+    assert!(violations.contains(&Violation { path: "/Contacts".to_string(), kind: ViolationKind::TooManyElements { name: "Email | Phone".to_string(), max: 3 } }));
+    Ok(())
+}
+
+#[test]
+fn test_mixed_content_array_is_reported() -> Result<(), ConversionError> {
+    let schema = parse_xsd(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="P">
+    <xs:complexType mixed="true">
+      <xs:sequence><xs:element name="B" type="xs:string" minOccurs="0"/></xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#)?;
+
+    let Err(ConversionError::Schema(violations)) = json_to_xml_with_schema(r#"{"$content": ["Hello ", {"B": "world"}]}"#, &schema, &ConversionOptions::default()) else {
+        panic!("expected schema violations");
+    };
+    assert_eq!(violations, vec![Violation { path: "/P".to_string(), kind: ViolationKind::Unsupported("$content".to_string()) }]);
+    Ok(())
+}
+
+#[test]
+fn test_target_namespace_follows_element_form_default() -> Result<(), ConversionError> {
+    let xsd = |form: &str| format!(r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:notes"{}>
+  <xs:element name="Title" type="xs:string"/>
+  <xs:element name="Note">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element ref="Title"/>
+        <xs:element name="Body" type="xs:string"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#, form);
+    let json = r#"{"note": {"title": "Hi", "body": "Text"}}"#;
+
+    let unqualified = parse_xsd(&xsd(""))?;
+    assert!(!unqualified.elements_qualified);
+    let xml = json_to_xml_with_schema(json, &unqualified, &ConversionOptions::default())?;
+    assert!(xml.contains("<tns:Note xmlns:tns=\"urn:notes\">\n  <tns:Title>Hi</tns:Title>\n  <Body>Text</Body>\n</tns:Note>"));
+
+    let qualified = parse_xsd(&xsd(r#" elementFormDefault="qualified""#))?;
+    let xml = json_to_xml_with_schema(json, &qualified, &ConversionOptions::default())?;
+    assert!(xml.contains("<Note xmlns=\"urn:notes\">\n  <Title>Hi</Title>\n  <Body>Text</Body>\n</Note>"));
+
+    // A root of a simple type declares the namespace too.
+    let xml = json_to_xml_with_schema(r#"{"title": "Hi"}"#, &qualified, &ConversionOptions::default())?;
+    assert!(xml.contains("<Title xmlns=\"urn:notes\">Hi</Title>"));
+
+    assert!(matches!(parse_xsd(&xsd(r#" elementFormDefault="local""#)), Err(ConversionError::InvalidSchema(_))));
+    Ok(())
+}
+
+const ANY_XSD: &str = r#"
+<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+  <xs:element name="Root">
+    <xs:complexType>
+      <xs:sequence>
+        <xs:element name="Any" type="xs:anyType" maxOccurs="unbounded"/>
+      </xs:sequence>
+    </xs:complexType>
+  </xs:element>
+</xs:schema>"#;
+
+#[test]
+fn test_any_type_content_is_checked() -> Result<(), ConversionError> {
+    let schema = parse_xsd(ANY_XSD)?;
+    let options = ConversionOptions::default();
+
+    let invalid_name = r#"{"Root": {"Any": {"@a b\"": "x", "k": 1}}}"#;
+    assert!(matches!(json_to_xml_with_schema(invalid_name, &schema, &options), Err(ConversionError::InvalidName { .. })));
+    let invalid_text = r#"{"Root": {"Any": {"@a": "x\u0001"}}}"#;
+    assert!(matches!(json_to_xml_with_schema(invalid_text, &schema, &options), Err(ConversionError::InvalidCharacter { .. })));
+    let invalid_namespace = r#"{"Root": {"@xmlns:a b": "urn:a", "Any": 1}}"#;
+    assert!(matches!(json_to_xml_with_schema(invalid_namespace, &schema, &options), Err(ConversionError::InvalidName { .. })));
+
+    let json = r#"{"Root": {"Any": [{"$content": ["kept", {"b": 1}], "k": 1}, 2]}}"#;
+    let options = ConversionOptions {
+        rules: vec![Rule::new("$.Root.Any[0].k", RuleAction::Attribute)?],
+        ..ConversionOptions::default()
+    };
+    let xml = json_to_xml_with_schema(json, &schema, &options)?;
+
+    assert!(xml.contains(r#"<Any k="1">kept<B>1</B></Any>"#), "{}", xml);
+    assert!(xml.contains("<Any>2</Any>"), "{}", xml);
+    Ok(())
+}
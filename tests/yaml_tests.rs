@@ -0,0 +1,61 @@
+#![cfg(feature = "yaml")]
+
+use json_to_xml::error::ConversionError;
+use json_to_xml::generate_xml::json_to_xml_with_root;
+use json_to_xml::yaml::yaml_to_xml;
+
+#[test]
+fn test_yaml_matches_json_conversion() -> Result<(), ConversionError> {
+    let yaml = r#"
+"@xmlns:pr": http://test/person
+person:
+  "@id": "42"
+  name: Alice
+  age: 30
+  tags: [a, b]
+  nickname: ~
+"#;
+    let json = r#"{"@xmlns:pr": "http://test/person", "person": {"@id": "42", "name": "Alice", "age": 30, "tags": ["a", "b"], "nickname": null}}"#;
+
+    assert_eq!(yaml_to_xml(yaml, "People")?, json_to_xml_with_root(json, "People")?);
+    Ok(())
+}
+
+#[test]
+fn test_yaml_anchors_aliases_and_merge_keys() -> Result<(), ConversionError> {
+    let yaml = r#"
+base: &base
+  retries: 3
+  "@env": dev
+colors: &colors [red, green]
+service:
+  <<: *base
+  retries: 5
+  palette: *colors
+"#;
+
+    let xml = yaml_to_xml(yaml, "Root")?;
+
+    assert!(xml.contains("<Service env=\"dev\">"));
+    assert!(xml.contains("<PaletteItem>green</PaletteItem>"));
+    assert!(xml.contains("<Retries>5</Retries>"));
+    assert_eq!(xml.matches("<Retries>3</Retries>").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_yaml_special_values() -> Result<(), ConversionError> {
+    let xml = yaml_to_xml("point: !Point {x: 1, y: .inf}\ntrue: yes\n", "Root")?;
+
+    assert!(xml.contains("<X>1</X>"));
+    assert!(xml.contains("<Y>.inf</Y>"));
+    assert!(xml.contains("<True>yes</True>"));
+    Ok(())
+}
+
+#[test]
+fn test_yaml_parse_error() {
+    let result = yaml_to_xml("person: [unclosed", "Root");
+
+    assert!(matches!(result, Err(ConversionError::Yaml(_))));
+}